@@ -0,0 +1,4 @@
+pub mod wall;
+pub mod tenhou;
+pub mod round;
+pub mod game;
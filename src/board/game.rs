@@ -0,0 +1,425 @@
+use crate::board::round::*;
+use crate::board::wall::*;
+use crate::rule::Ruleset;
+use crate::tile::*;
+
+/// 対局の結果
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GameResult {
+    /// 最終的な持ち点（残った立直棒はトップが受け取る）
+    pub scores: [i32; 4],
+    /// 順位の順に並べた席（同点なら起家に近い席が上）
+    pub ranking: [usize; 4],
+    /// 持ち点から返し点を引き、ウマとオカを加えた最終的な得点
+    pub points: [i32; 4],
+}
+
+/// 対局（東風戦・半荘戦）の進行
+///
+/// 局ごとに持ち点・本場・供託の立直棒・親を更新する。親の和了と聴牌（聴牌連荘のとき）・途中流局（ルールに従う）では連荘し、
+/// オーラスでの和了止め、飛び、延長戦（西入・南入）はルールに従う。席`0`が起家。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::board::game::*;
+/// use mahjong_rs::rule::*;
+/// use mahjong_rs::tile::*;
+///
+/// let game = Game::new(&Ruleset::default());
+/// assert_eq!(game.round_wind(), Wind::East);
+/// assert_eq!(game.dealer(), 0);
+/// assert_eq!(game.scores(), [25000; 4]);
+/// assert!(!game.is_finished());
+/// ```
+pub struct Game {
+    ruleset: Ruleset,
+    scores: [i32; 4],
+    /// 何局目か（東1局が`0`、南1局が`4`、西1局が`8`）
+    round_index: usize,
+    honba: u32,
+    riichi_sticks: u32,
+    results: Vec<RoundResult>,
+    finished: bool,
+}
+
+impl Game {
+    /// 配給原点から対局を始める
+    pub fn new(ruleset: &Ruleset) -> Game {
+        return Game {
+            ruleset: ruleset.clone(),
+            scores: [ruleset.starting_points; 4],
+            round_index: 0,
+            honba: 0,
+            riichi_sticks: 0,
+            results: Vec::new(),
+            finished: false,
+        };
+    }
+
+    /// 各席の持ち点を返す
+    pub fn scores(&self) -> [i32; 4] {
+        return self.scores;
+    }
+
+    /// 場風を返す
+    pub fn round_wind(&self) -> Wind {
+        return match self.round_index / 4 {
+            0 => Wind::East,
+            1 => Wind::South,
+            2 => Wind::West,
+            _ => Wind::North,
+        };
+    }
+
+    /// 親の席を返す
+    pub fn dealer(&self) -> usize {
+        return self.round_index % 4;
+    }
+
+    /// 本場を返す
+    pub fn honba(&self) -> u32 {
+        return self.honba;
+    }
+
+    /// 場に残っている立直棒の本数を返す
+    pub fn riichi_sticks(&self) -> u32 {
+        return self.riichi_sticks;
+    }
+
+    /// これまでの局の結果を返す
+    pub fn results(&self) -> &[RoundResult] {
+        return &self.results;
+    }
+
+    /// 対局が終わったか否かを返す
+    pub fn is_finished(&self) -> bool {
+        return self.finished;
+    }
+
+    /// 次の局の開始時の状況を返す
+    pub fn setup(&self) -> RoundSetup {
+        return RoundSetup {
+            round_wind: self.round_wind(),
+            dealer: self.dealer(),
+            honba: self.honba,
+            riichi_sticks: self.riichi_sticks,
+            scores: self.scores,
+        };
+    }
+
+    /// 席を順位の順に並べて返す（同点なら起家に近い席が上）
+    pub fn ranking(&self) -> [usize; 4] {
+        let mut result = [0, 1, 2, 3];
+        result.sort_by_key(|&seat| -self.scores[seat]);
+        return result;
+    }
+
+    /// 対局を最後まで進める
+    ///
+    /// `players`は席の順に並べる。
+    pub fn run(
+        &mut self,
+        shuffler: &mut impl Shuffler,
+        players: &mut [Box<dyn Player>; 4],
+    ) -> GameResult {
+        while !self.finished {
+            self.play_round(shuffler, players);
+        }
+        return self.result();
+    }
+
+    /// 1局を進める
+    ///
+    /// 対局が既に終わっていれば panic する。
+    pub fn play_round(
+        &mut self,
+        shuffler: &mut impl Shuffler,
+        players: &mut [Box<dyn Player>; 4],
+    ) -> &RoundResult {
+        if self.finished {
+            panic!("The game is already finished!");
+        }
+        let wall = Wall::new(shuffler, self.ruleset.red_fives);
+        let mut round = Round::new(self.setup(), wall, &self.ruleset);
+        let result = round.run(players);
+        self.apply(result, players);
+        return self.results.last().unwrap();
+    }
+
+    /// 局の結果を反映して、次の局に進めるか対局を終える
+    fn apply(&mut self, result: RoundResult, players: &mut [Box<dyn Player>; 4]) {
+        for (score, delta) in self.scores.iter_mut().zip(result.deltas.iter()) {
+            *score += delta;
+        }
+        self.riichi_sticks = result.riichi_sticks;
+        let dealer = self.dealer();
+        let renchan = match &result.end {
+            RoundEnd::Win(wins) => wins.iter().any(|win| win.winner == dealer),
+            RoundEnd::ExhaustiveDraw(settlement) => settlement.dealer_keeps_seat,
+            RoundEnd::AbortiveDraw(abortive) => abortive.dealer_keeps_seat,
+        };
+        // 子の和了でのみ本場がなくなる
+        self.honba = match &result.end {
+            RoundEnd::Win(_) if !renchan => 0,
+            RoundEnd::AbortiveDraw(abortive) => abortive.next_honba,
+            _ => self.honba + 1,
+        };
+        let stoppable = !matches!(result.end, RoundEnd::AbortiveDraw(_));
+        self.finished = self.is_over(renchan, stoppable, players);
+        if !renchan {
+            self.round_index += 1;
+        }
+        self.results.push(result);
+    }
+
+    /// 局の後に対局を終えるか否かを返す
+    ///
+    /// `stoppable`は親が和了・聴牌して和了止めできる局か否か。
+    /// 延長は1場までで、延長中は誰かが`target_points`に達した時点で終える。
+    fn is_over(&self, renchan: bool, stoppable: bool, players: &mut [Box<dyn Player>; 4]) -> bool {
+        if self.ruleset.tobi && self.scores.iter().any(|&score| score < 0) {
+            return true;
+        }
+        let last = self.ruleset.game_length.rounds();
+        if self.round_index + 1 < last {
+            return false;
+        }
+        let target = self.ruleset.target_points;
+        let reached = self.scores.iter().any(|&score| score >= target);
+        if self.round_index + 1 > last && reached {
+            return true;
+        }
+        if renchan {
+            let dealer = self.dealer();
+            if !stoppable || self.ranking()[0] != dealer || self.scores[dealer] < target {
+                return false;
+            }
+            return self.ruleset.agari_yame
+                && players[dealer].decide_agari_yame(dealer, &self.scores);
+        }
+        if reached || !self.ruleset.extension_round {
+            return true;
+        }
+        return self.round_index + 1 >= last + 4;
+    }
+
+    /// 対局の結果を返す
+    ///
+    /// 対局の途中でも、その時点で終えたものとして計算する。
+    pub fn result(&self) -> GameResult {
+        let ranking = self.ranking();
+        let mut scores = self.scores;
+        scores[ranking[0]] += self.riichi_sticks as i32 * 1000;
+        let mut points = [0; 4];
+        for (rank, &seat) in ranking.iter().enumerate() {
+            points[seat] = scores[seat] - self.ruleset.return_points + self.ruleset.uma[rank];
+        }
+        points[ranking[0]] += self.ruleset.oka();
+        return GameResult {
+            scores,
+            ranking,
+            points,
+        };
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_info::exhaustive_draw::ExhaustiveDrawSettlement;
+    use crate::rule::GameLength;
+
+    /// 和了も鳴きもせずにツモ切りし、和了止めしないプレイヤー
+    struct Tsumogiri;
+
+    impl Player for Tsumogiri {
+        fn decide_turn(
+            &mut self,
+            round: &Round,
+            seat: usize,
+            options: &[TurnAction],
+        ) -> TurnAction {
+            return match round.hand(seat).drawn() {
+                Some(tile) => TurnAction::Discard(tile),
+                None => options[0],
+            };
+        }
+
+        fn decide_call(&mut self, _: &Round, _: usize, _: Tile, _: &[CallAction]) -> CallAction {
+            return CallAction::Pass;
+        }
+
+        fn decide_agari_yame(&mut self, _: usize, _: &[i32; 4]) -> bool {
+            return false;
+        }
+    }
+
+    fn players() -> [Box<dyn Player>; 4] {
+        return [
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+        ];
+    }
+
+    /// 荒牌平局の結果
+    fn draw(dealer_keeps_seat: bool, deltas: [i32; 4]) -> RoundResult {
+        return RoundResult {
+            end: RoundEnd::ExhaustiveDraw(ExhaustiveDrawSettlement {
+                nagashi: Vec::new(),
+                tenpai: [false; 4],
+                deltas,
+                dealer_keeps_seat,
+            }),
+            deltas,
+            riichi_sticks: 0,
+        };
+    }
+
+    #[test]
+    /// 親が連荘すれば本場が増え、流局では親が流れても本場が増える
+    fn renchan_on_draw() {
+        let mut game = Game::new(&Ruleset::default());
+        game.apply(draw(true, [0; 4]), &mut players());
+        assert_eq!(game.dealer(), 0);
+        assert_eq!(game.honba(), 1);
+        game.apply(draw(false, [0; 4]), &mut players());
+        assert_eq!(game.dealer(), 1);
+        assert_eq!(game.honba(), 2);
+    }
+
+    #[test]
+    /// 途中流局では本場が増え、親が連荘するかはルールに従う
+    fn abortive_draw() {
+        let abort = |dealer_keeps_seat: bool| RoundResult {
+            end: RoundEnd::AbortiveDraw(AbortiveDrawResult {
+                kind: AbortiveDraw::FourWinds,
+                dealer_keeps_seat,
+                next_honba: 1,
+            }),
+            deltas: [0; 4],
+            riichi_sticks: 0,
+        };
+        let mut game = Game::new(&Ruleset::default());
+        game.apply(abort(true), &mut players());
+        assert_eq!(game.dealer(), 0);
+        assert_eq!(game.honba(), 1);
+        let mut game = Game::new(&Ruleset::default());
+        game.apply(abort(false), &mut players());
+        assert_eq!(game.dealer(), 1);
+        assert_eq!(game.honba(), 1);
+    }
+
+    #[test]
+    /// 持ち点が0点未満になれば飛びで終わる
+    fn tobi() {
+        let mut game = Game::new(&Ruleset::default());
+        game.apply(draw(false, [26000, -26000, 0, 0]), &mut players());
+        assert!(game.is_finished());
+        assert_eq!(game.result().ranking, [0, 2, 3, 1]);
+
+        let mut game = Game::new(&Ruleset::m_league());
+        game.apply(draw(false, [26000, -26000, 0, 0]), &mut players());
+        assert!(!game.is_finished());
+    }
+
+    #[test]
+    /// 誰も返し点に達していなければ次の場まで延長し、その4局目で終わる
+    fn extension_round() {
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.game_length = GameLength::East;
+        let mut game = Game::new(&ruleset);
+        for _ in 0..4 {
+            game.apply(draw(false, [0; 4]), &mut players());
+        }
+        // 東風戦の延長は南場
+        assert!(!game.is_finished());
+        assert_eq!(game.round_wind(), Wind::South);
+        game.apply(draw(false, [3000, -1000, -1000, -1000]), &mut players());
+        assert!(!game.is_finished());
+        game.apply(draw(false, [3000, -1000, -1000, -1000]), &mut players());
+        // 30000点に達したので終わる
+        assert!(game.is_finished());
+
+        ruleset.extension_round = false;
+        let mut game = Game::new(&ruleset);
+        for _ in 0..4 {
+            game.apply(draw(false, [0; 4]), &mut players());
+        }
+        assert!(game.is_finished());
+    }
+
+    #[test]
+    /// 延長戦では親が連荘しても、子が`target_points`に達した時点で終わる
+    fn extension_round_ends_on_renchan() {
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.game_length = GameLength::East;
+        let mut game = Game::new(&ruleset);
+        for _ in 0..4 {
+            game.apply(draw(false, [0; 4]), &mut players());
+        }
+        assert_eq!(game.round_wind(), Wind::South);
+        assert_eq!(game.dealer(), 0);
+        game.apply(draw(true, [1000, 6000, -3500, -3500]), &mut players());
+        assert!(game.is_finished());
+        assert_eq!(game.result().ranking[0], 1);
+    }
+
+    #[test]
+    /// オーラスで親がトップなら和了止め・聴牌止めできる
+    fn agari_yame() {
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.game_length = GameLength::East;
+        let mut game = Game::new(&ruleset);
+        for _ in 0..3 {
+            game.apply(draw(false, [0; 4]), &mut players());
+        }
+        assert_eq!(game.dealer(), 3);
+        // 和了止めしないプレイヤーなら続ける
+        game.apply(draw(true, [-2000, -2000, -2000, 6000]), &mut players());
+        assert!(!game.is_finished());
+        assert_eq!(game.dealer(), 3);
+        let mut stopping: [Box<dyn Player>; 4] = [
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+            Box::new(AgariYame),
+        ];
+        game.apply(draw(true, [0; 4]), &mut stopping);
+        assert!(game.is_finished());
+        let result = game.result();
+        assert_eq!(result.scores, [23000, 23000, 23000, 31000]);
+        assert_eq!(result.ranking, [3, 0, 1, 2]);
+        // 31000 - 30000 + 20000（ウマ）+ 20000（オカ）
+        assert_eq!(result.points[3], 41000);
+        assert_eq!(result.points.iter().sum::<i32>(), 0);
+    }
+
+    /// 和了止めするプレイヤー
+    struct AgariYame;
+
+    impl Player for AgariYame {
+        fn decide_turn(&mut self, _: &Round, _: usize, options: &[TurnAction]) -> TurnAction {
+            return options[0];
+        }
+
+        fn decide_call(&mut self, _: &Round, _: usize, _: Tile, _: &[CallAction]) -> CallAction {
+            return CallAction::Pass;
+        }
+    }
+
+    #[test]
+    /// 東風戦を最後まで進める
+    fn run_east() {
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.game_length = GameLength::East;
+        ruleset.extension_round = false;
+        let mut game = Game::new(&ruleset);
+        let result = game.run(&mut RandomShuffler::seeded(3), &mut players());
+        assert!(game.is_finished());
+        assert!(game.results().len() >= 4);
+        assert_eq!(result.scores.iter().sum::<i32>(), 100000);
+    }
+}
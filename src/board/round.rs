@@ -0,0 +1,1510 @@
+use crate::board::wall::*;
+use crate::hand::Hand;
+use crate::hand_info::exhaustive_draw::*;
+use crate::hand_info::furiten::*;
+use crate::hand_info::hand_analyzer::HandAnalyzer;
+use crate::hand_info::opened::*;
+use crate::hand_info::report::{win_deltas, WinSeats};
+use crate::hand_info::river::River;
+use crate::hand_info::scorer::*;
+use crate::hand_info::win_context::*;
+use crate::rule::{MultipleRon, Ruleset};
+use crate::tile::*;
+
+/// 局の開始時の状況
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RoundSetup {
+    /// 場風
+    pub round_wind: Wind,
+    /// 親の席（`0`〜`3`、席の番号は手番の順）
+    pub dealer: usize,
+    /// 本場
+    pub honba: u32,
+    /// 前の局から持ち越した立直棒の本数
+    pub riichi_sticks: u32,
+    /// 局の開始時の各席の持ち点
+    pub scores: [i32; 4],
+}
+
+/// 自分の手番（ツモ・鳴きの後）に選べる行動
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TurnAction {
+    /// 牌を捨てる
+    Discard(Tile),
+    /// 立直を宣言して牌を捨てる
+    Riichi(Tile),
+    /// ツモ和了
+    Tsumo,
+    /// 暗槓
+    ClosedKan(TileType),
+    /// 加槓
+    AddedKan(TileType),
+    /// 九種九牌で流局する
+    NineTerminals,
+}
+
+/// 他家の打牌・加槓に対して選べる行動
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CallAction {
+    /// 何もしない
+    Pass,
+    /// ロン和了
+    Ron,
+    /// ポン
+    Pon,
+    /// 大明槓
+    OpenKan,
+    /// チー。値は順子の最初の牌の種類
+    Chi(TileType),
+}
+
+/// 局に参加するプレイヤー
+///
+/// 選択肢は全て合法な行動で、選択肢にない行動を返したときは
+/// 手番なら最初の打牌を、鳴きなら`CallAction::Pass`を選んだものとする。
+pub trait Player {
+    /// 自分の手番での行動を選ぶ
+    fn decide_turn(&mut self, round: &Round, seat: usize, options: &[TurnAction]) -> TurnAction;
+    /// 他家の打牌・加槓した牌`tile`に対する行動を選ぶ
+    fn decide_call(
+        &mut self,
+        round: &Round,
+        seat: usize,
+        tile: Tile,
+        options: &[CallAction],
+    ) -> CallAction;
+    /// 荒牌平局で聴牌しているとき、聴牌を宣言して手牌を公開するか否かを選ぶ
+    ///
+    /// `Ruleset::tenpai_declaration`のときだけ、立直していなければ確認する。既定では宣言する。
+    fn declare_tenpai(&mut self, round: &Round, seat: usize) -> bool {
+        let _ = (round, seat);
+        return true;
+    }
+    /// オーラスで親としてトップのとき、和了止め・聴牌止めで対局を終えるか否かを選ぶ
+    ///
+    /// 既定では終える。
+    fn decide_agari_yame(&mut self, seat: usize, scores: &[i32; 4]) -> bool {
+        let _ = (seat, scores);
+        return true;
+    }
+}
+
+/// 1人の和了
+#[derive(Debug, Clone)]
+pub struct Win {
+    /// 和了者の席
+    pub winner: usize,
+    /// 放銃者の席（ツモなら`None`）
+    pub discarder: Option<usize>,
+    /// 和了牌をツモ牌とした手牌
+    pub hand: Hand,
+    /// 和了したときの状況
+    pub context: WinContext,
+    /// 点数
+    pub score: ScoreResult,
+    /// この和了による各席の点数の増減（本場・供託を含む）
+    pub deltas: [i32; 4],
+}
+
+/// 途中流局の種類
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AbortiveDraw {
+    /// 九種九牌（第一ツモの時点で么九牌が9種類以上あり、流局を選んだ）
+    NineTerminals,
+    /// 四風連打（鳴きのない第一打で4人が同じ風牌を捨てた）
+    FourWinds,
+    /// 四家立直（4人目の立直宣言牌が通った）
+    FourRiichi,
+    /// 四開槓（2人以上で合わせて4回槓した後の打牌が通った）
+    FourKans,
+    /// 三家和（3人が同じ牌でロンした）
+    ThreeRons,
+}
+
+/// 途中流局の結果
+///
+/// 点数の移動はなく、供託の立直棒は次の局に持ち越す。
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AbortiveDrawResult {
+    /// 途中流局の種類
+    pub kind: AbortiveDraw,
+    /// 親が連荘するか
+    pub dealer_keeps_seat: bool,
+    /// 次の局の本場
+    pub next_honba: u32,
+}
+
+/// 局の終わり方
+#[derive(Debug, Clone)]
+pub enum RoundEnd {
+    /// 和了（ダブロン・トリロンなら複数）
+    Win(Vec<Win>),
+    /// 荒牌平局（流し満貫・ノーテン罰符の精算）
+    ExhaustiveDraw(ExhaustiveDrawSettlement),
+    /// 途中流局
+    AbortiveDraw(AbortiveDrawResult),
+}
+
+/// 局の結果
+#[derive(Debug, Clone)]
+pub struct RoundResult {
+    /// 局の終わり方
+    pub end: RoundEnd,
+    /// 各席の点数の増減（立直棒の供託と和了による収支を含む）
+    pub deltas: [i32; 4],
+    /// 局の終了後に場に残っている立直棒の本数
+    pub riichi_sticks: u32,
+}
+
+/// 手番の終わり方
+enum TurnOutcome {
+    /// 牌を捨てた
+    Discarded { tile: Tile, riichi: bool },
+    /// 局が終わった
+    End(RoundEnd),
+}
+
+/// 打牌に対する鳴きの結果
+enum CallOutcome {
+    /// 誰も鳴かなかった
+    None,
+    /// ロンされた
+    Ron(Vec<Win>),
+    /// ポン・チー・大明槓された
+    Called { seat: usize, action: CallAction },
+}
+
+/// 1局の進行
+///
+/// 配牌から和了・荒牌平局（ノーテン罰符の精算を含む）・途中流局までを進める。ツモ・打牌のたびに鳴き（ロン・ポン・大明槓・チー）を確認し、
+/// 優先順位はロン、ポン・大明槓、チーの順。立直の宣言と供託、一発の消滅、嶺上牌のツモとドラ表示牌、搶槓も扱う。
+/// 行動の合法性は`HandAnalyzer`と点数計算で判定する。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::board::round::*;
+/// use mahjong_rs::board::wall::*;
+/// use mahjong_rs::rule::*;
+/// use mahjong_rs::tile::*;
+///
+/// let setup = RoundSetup {
+///     round_wind: Wind::East,
+///     dealer: 0,
+///     honba: 0,
+///     riichi_sticks: 0,
+///     scores: [25000; 4],
+/// };
+/// let wall = Wall::new(&mut RandomShuffler::seeded(1), [1, 1, 1]);
+/// let round = Round::new(setup, wall, &Ruleset::default());
+/// // 親は14枚目をまだツモっていない
+/// assert_eq!(round.hand(0).tiles().len(), 13);
+/// assert_eq!(round.wall().remaining(), 122 - 52);
+/// ```
+pub struct Round {
+    ruleset: Ruleset,
+    setup: RoundSetup,
+    wall: Wall,
+    hands: [Hand; 4],
+    rivers: [River; 4],
+    riichi: [RiichiState; 4],
+    /// 一発が有効か（立直が成立してから自分の次の打牌まで、鳴きが入らなければ有効）
+    ippatsu: [bool; 4],
+    missed: [MissedTiles; 4],
+    /// 槓した席（槓した順）
+    kan_seats: Vec<usize>,
+    /// めくっていない明槓・加槓のドラ表示牌の数（次の打牌か嶺上開花でめくる）
+    pending_indicators: usize,
+    /// 鳴き（暗槓を含む）が入ったか
+    called: bool,
+    riichi_sticks: u32,
+    deltas: [i32; 4],
+}
+
+impl Round {
+    /// 牌山から配牌して局を始める
+    ///
+    /// 親から順に4枚ずつ3回、最後に1枚ずつ配る。
+    pub fn new(setup: RoundSetup, mut wall: Wall, ruleset: &Ruleset) -> Round {
+        let mut tiles: [Vec<Tile>; 4] = Default::default();
+        for count in [4, 4, 4, 1].iter() {
+            for i in 0..4 {
+                let seat = (setup.dealer + i) % 4;
+                for _ in 0..*count {
+                    tiles[seat].push(wall.draw().expect("a new wall has enough tiles to deal"));
+                }
+            }
+        }
+        let hands = tiles.map(|mut t| {
+            t.sort();
+            Hand::new(t, None)
+        });
+        return Round {
+            ruleset: ruleset.clone(),
+            setup,
+            wall,
+            hands,
+            rivers: Default::default(),
+            riichi: [RiichiState::None; 4],
+            ippatsu: [false; 4],
+            missed: Default::default(),
+            kan_seats: Vec::new(),
+            pending_indicators: 0,
+            called: false,
+            riichi_sticks: setup.riichi_sticks,
+            deltas: [0; 4],
+        };
+    }
+
+    /// 局の開始時の状況を返す
+    pub fn setup(&self) -> &RoundSetup {
+        return &self.setup;
+    }
+
+    /// 牌山を返す
+    pub fn wall(&self) -> &Wall {
+        return &self.wall;
+    }
+
+    /// 手牌を返す
+    pub fn hand(&self, seat: usize) -> &Hand {
+        return &self.hands[seat];
+    }
+
+    /// 河を返す
+    pub fn river(&self, seat: usize) -> &River {
+        return &self.rivers[seat];
+    }
+
+    /// 立直の状態を返す
+    pub fn riichi(&self, seat: usize) -> RiichiState {
+        return self.riichi[seat];
+    }
+
+    /// 現在の持ち点（供託した立直棒を差し引いたもの）を返す
+    pub fn score(&self, seat: usize) -> i32 {
+        return self.setup.scores[seat] + self.deltas[seat];
+    }
+
+    /// 場に出ている立直棒の本数を返す
+    pub fn riichi_sticks(&self) -> u32 {
+        return self.riichi_sticks;
+    }
+
+    /// 自風を返す
+    pub fn seat_wind(&self, seat: usize) -> Wind {
+        return match (seat + 4 - self.setup.dealer) % 4 {
+            0 => Wind::East,
+            1 => Wind::South,
+            2 => Wind::West,
+            _ => Wind::North,
+        };
+    }
+
+    /// 局を最後まで進める
+    ///
+    /// `players`は席の順に並べる。
+    pub fn run(&mut self, players: &mut [Box<dyn Player>; 4]) -> RoundResult {
+        let mut seat = self.setup.dealer;
+        let mut outcome = None;
+        loop {
+            let turn = match outcome.take() {
+                // 鳴いた後はツモらずに打牌する
+                Some((CallAction::OpenKan, _)) => {
+                    self.draw_rinshan(seat);
+                    self.take_turn(seat, None, true, players)
+                }
+                Some((_, forbidden)) => self.take_turn(seat, Some(forbidden), false, players),
+                None => {
+                    if self.wall.is_exhausted() {
+                        return self.exhaustive_draw(players);
+                    }
+                    let tile = self.wall.draw().expect("the wall is not exhausted");
+                    self.hands[seat].draw(tile);
+                    self.take_turn(seat, None, false, players)
+                }
+            };
+            let (tile, riichi) = match turn {
+                TurnOutcome::Discarded { tile, riichi } => (tile, riichi),
+                TurnOutcome::End(end) => return self.finish(end),
+            };
+            let call = self.call_window(seat, tile, riichi, players);
+            if let CallOutcome::Ron(wins) = call {
+                return self.finish(self.end_with_wins(wins));
+            }
+            if riichi {
+                self.establish_riichi(seat);
+            }
+            if let Some(kind) = self.abortive_draw_after_discard() {
+                return self.finish(self.abort(kind));
+            }
+            seat = match call {
+                CallOutcome::Called {
+                    seat: caller,
+                    action,
+                } => {
+                    let forbidden = self.call(caller, seat, tile, action);
+                    outcome = Some((action, forbidden));
+                    caller
+                }
+                _ => (seat + 1) % 4,
+            };
+        }
+    }
+
+    /// ツモ（または鳴き）の後、牌を捨てるか和了するまで手番を進める
+    ///
+    /// `forbidden`は鳴いた直後に捨てられない牌（喰い替え）。鳴いていなければ`None`。
+    fn take_turn(
+        &mut self,
+        seat: usize,
+        forbidden: Option<Vec<TileType>>,
+        mut after_kan: bool,
+        players: &mut [Box<dyn Player>; 4],
+    ) -> TurnOutcome {
+        loop {
+            let options = self.turn_options(seat, forbidden.as_deref(), after_kan);
+            let action = players[seat].decide_turn(self, seat, &options);
+            let action = if options.contains(&action) {
+                action
+            } else {
+                *options
+                    .iter()
+                    .find(|o| matches!(o, TurnAction::Discard(_)))
+                    .expect("there is always a tile to discard")
+            };
+            match action {
+                TurnAction::Tsumo => {
+                    self.reveal_pending_indicators();
+                    let tile = self.hands[seat].drawn().expect("tsumo needs a drawn tile");
+                    let context = self.context(seat, tile, WinMethod::Tsumo, after_kan);
+                    let score = score(&self.hands[seat], &context, &self.ruleset)
+                        .expect("tsumo is offered only for a scoring hand");
+                    let hand = self.hands[seat].clone();
+                    let win = self.make_win(seat, None, hand, context, score, self.riichi_sticks);
+                    return TurnOutcome::End(RoundEnd::Win(vec![win]));
+                }
+                TurnAction::NineTerminals => {
+                    return TurnOutcome::End(self.abort(AbortiveDraw::NineTerminals));
+                }
+                TurnAction::ClosedKan(kind) => {
+                    let tiles: Vec<Tile> = self
+                        .concealed(seat)
+                        .into_iter()
+                        .filter(|t| t.get() == kind)
+                        .collect();
+                    let open = OpenTiles {
+                        tiles: kan_tiles(&tiles),
+                        category: OpenType::Kan,
+                        from: OpenFrom::Myself,
+                        kan: Some(KanType::Closed),
+                    };
+                    self.hands[seat].open(open, &tiles);
+                    self.interrupt();
+                    self.kan_seats.push(seat);
+                    self.draw_rinshan(seat);
+                    // 暗槓のドラ表示牌はすぐにめくる
+                    self.reveal_pending_indicators();
+                    after_kan = true;
+                }
+                TurnAction::AddedKan(kind) => {
+                    let tile = *self
+                        .concealed(seat)
+                        .iter()
+                        .find(|t| t.get() == kind)
+                        .expect("added kan needs the fourth tile");
+                    self.hands[seat].add_kan(tile);
+                    let wins = self.chankan_window(seat, tile, players);
+                    if !wins.is_empty() {
+                        return TurnOutcome::End(self.end_with_wins(wins));
+                    }
+                    self.interrupt();
+                    self.kan_seats.push(seat);
+                    self.draw_rinshan(seat);
+                    after_kan = true;
+                }
+                TurnAction::Discard(tile) | TurnAction::Riichi(tile) => {
+                    let riichi = matches!(action, TurnAction::Riichi(_));
+                    if riichi {
+                        self.riichi[seat] = if self.rivers[seat].is_empty() && !self.called {
+                            RiichiState::DoubleRiichi
+                        } else {
+                            RiichiState::Riichi
+                        };
+                        self.rivers[seat].discard_with_riichi(tile);
+                    } else {
+                        self.rivers[seat].discard(tile);
+                    }
+                    self.hands[seat].discard(tile);
+                    self.ippatsu[seat] = false;
+                    self.missed[seat].since_last_discard.clear();
+                    // 明槓・加槓のドラ表示牌は打牌の後にめくる
+                    self.reveal_pending_indicators();
+                    return TurnOutcome::Discarded { tile, riichi };
+                }
+            }
+        }
+    }
+
+    /// 手番で選べる行動を求める
+    fn turn_options(
+        &self,
+        seat: usize,
+        forbidden: Option<&[TileType]>,
+        after_kan: bool,
+    ) -> Vec<TurnAction> {
+        let hand = &self.hands[seat];
+        let mut result = Vec::new();
+        let concealed = self.concealed(seat);
+        if forbidden.is_none() {
+            let tile = hand
+                .drawn()
+                .expect("a turn without a call starts with a draw");
+            let context = self.context(seat, tile, WinMethod::Tsumo, after_kan);
+            if is_complete(hand, &self.ruleset) && score(hand, &context, &self.ruleset).is_some() {
+                result.push(TurnAction::Tsumo);
+            }
+            if self.can_kan() {
+                let summarized = hand.summarize_tiles();
+                for kind in 0..Tile::LEN as TileType {
+                    let in_hand = concealed.iter().filter(|t| t.get() == kind).count();
+                    if in_hand == 4 && self.can_closed_kan_in_riichi(seat, kind) {
+                        result.push(TurnAction::ClosedKan(kind));
+                    }
+                    let pon = hand
+                        .opened()
+                        .iter()
+                        .any(|o| o.category == OpenType::Pon && o.tiles[0].get() == kind);
+                    if pon && summarized[kind as usize] == 4 && in_hand == 1 {
+                        result.push(TurnAction::AddedKan(kind));
+                    }
+                }
+            }
+            if self.can_abort_with_nine_terminals(seat) {
+                result.push(TurnAction::NineTerminals);
+            }
+        }
+        if self.riichi[seat] != RiichiState::None {
+            result.push(TurnAction::Discard(
+                hand.drawn().expect("riichi hand has a drawn tile"),
+            ));
+            return result;
+        }
+        let mut candidates = concealed;
+        candidates.sort();
+        candidates.dedup();
+        let forbidden = forbidden.unwrap_or(&[]);
+        candidates.retain(|t| !forbidden.contains(&t.get()));
+        for tile in candidates.iter() {
+            result.push(TurnAction::Discard(*tile));
+        }
+        if forbidden.is_empty() && self.can_declare_riichi(seat) {
+            for tile in candidates.iter() {
+                let mut after = hand.clone();
+                after.discard(*tile);
+                if HandAnalyzer::calc_with_rule(&after, &self.ruleset).shanten == 0 {
+                    result.push(TurnAction::Riichi(*tile));
+                }
+            }
+        }
+        return result;
+    }
+
+    /// 他家の打牌`tile`に対して選べる行動を求める（`CallAction::Pass`は必ず含む）
+    fn call_options(
+        &self,
+        seat: usize,
+        discarder: usize,
+        tile: Tile,
+        riichi: bool,
+    ) -> Vec<CallAction> {
+        let mut result = vec![CallAction::Pass];
+        if self.ron(seat, discarder, tile, false, riichi).is_some() {
+            result.push(CallAction::Ron);
+        }
+        // 河底牌は鳴けない
+        if self.riichi[seat] != RiichiState::None || self.wall.is_exhausted() {
+            return result;
+        }
+        let hand = self.hands[seat].tiles();
+        let count = |kind: TileType| hand.iter().filter(|t| t.get() == kind).count();
+        let kind = tile.get();
+        if count(kind) >= 2 && has_discard_after_call(hand, &[kind, kind], &[kind]) {
+            result.push(CallAction::Pon);
+        }
+        if count(kind) >= 3 && self.can_kan() {
+            result.push(CallAction::OpenKan);
+        }
+        if seat == (discarder + 1) % 4 && !tile.is_honor() {
+            for first in chi_starts(kind) {
+                let others: Vec<TileType> = (first..first + 3).filter(|&k| k != kind).collect();
+                if others.iter().all(|&k| count(k) >= 1)
+                    && has_discard_after_call(hand, &others, &forbidden_after_chi(kind, first))
+                {
+                    result.push(CallAction::Chi(first));
+                }
+            }
+        }
+        return result;
+    }
+
+    /// 打牌`tile`に対する鳴きを確認する
+    fn call_window(
+        &mut self,
+        discarder: usize,
+        tile: Tile,
+        riichi: bool,
+        players: &mut [Box<dyn Player>; 4],
+    ) -> CallOutcome {
+        let mut decisions = Vec::new();
+        for i in 1..4 {
+            let seat = (discarder + i) % 4;
+            let options = self.call_options(seat, discarder, tile, riichi);
+            if options.len() > 1 {
+                let action = players[seat].decide_call(self, seat, tile, &options);
+                if options.contains(&action) {
+                    decisions.push((seat, action));
+                }
+            }
+        }
+        let mut wins = Vec::new();
+        for &(seat, action) in decisions.iter() {
+            if action != CallAction::Ron {
+                continue;
+            }
+            let (hand, context, score) = self
+                .ron(seat, discarder, tile, false, riichi)
+                .expect("ron is offered only for a scoring hand");
+            let sticks = if wins.is_empty() {
+                self.riichi_sticks
+            } else {
+                0
+            };
+            wins.push(self.make_win(seat, Some(discarder), hand, context, score, sticks));
+        }
+        if !wins.is_empty() {
+            return CallOutcome::Ron(wins);
+        }
+        self.miss(discarder, tile);
+        let priority = |action: &CallAction| match action {
+            CallAction::Pon | CallAction::OpenKan => 2,
+            CallAction::Chi(_) => 1,
+            _ => 0,
+        };
+        return match decisions
+            .into_iter()
+            .filter(|(_, a)| priority(a) > 0)
+            .max_by_key(|(_, a)| priority(a))
+        {
+            Some((seat, action)) => CallOutcome::Called { seat, action },
+            None => CallOutcome::None,
+        };
+    }
+
+    /// 加槓した牌`tile`に対する搶槓を確認する
+    fn chankan_window(
+        &mut self,
+        kan_seat: usize,
+        tile: Tile,
+        players: &mut [Box<dyn Player>; 4],
+    ) -> Vec<Win> {
+        let mut wins = Vec::new();
+        for i in 1..4 {
+            let seat = (kan_seat + i) % 4;
+            if let Some((hand, context, score)) = self.ron(seat, kan_seat, tile, true, false) {
+                let options = [CallAction::Pass, CallAction::Ron];
+                if players[seat].decide_call(self, seat, tile, &options) == CallAction::Ron {
+                    let sticks = if wins.is_empty() {
+                        self.riichi_sticks
+                    } else {
+                        0
+                    };
+                    wins.push(self.make_win(seat, Some(kan_seat), hand, context, score, sticks));
+                }
+            }
+        }
+        if wins.is_empty() {
+            self.miss(kan_seat, tile);
+        }
+        return wins;
+    }
+
+    /// 打牌`tile`でロンできれば、和了した手牌・状況・点数を返す
+    fn ron(
+        &self,
+        seat: usize,
+        discarder: usize,
+        tile: Tile,
+        robbing_kan: bool,
+        riichi_declaration: bool,
+    ) -> Option<(Hand, WinContext, ScoreResult)> {
+        if seat == discarder {
+            return None;
+        }
+        let hand = self.hands[seat].with_drawn(tile);
+        if !is_complete(&hand, &self.ruleset) {
+            return None;
+        }
+        let furiten = Furiten::check(
+            &self.hands[seat],
+            &self.rivers[seat].tiles(),
+            &self.missed[seat],
+        );
+        if furiten.is_furiten() {
+            return None;
+        }
+        let mut context = self.context(seat, tile, WinMethod::Ron, false);
+        context.robbing_kan = robbing_kan;
+        context.last_tile = context.last_tile && !robbing_kan;
+        context.on_riichi_declaration = riichi_declaration;
+        let score = score(&hand, &context, &self.ruleset)?;
+        return Some((hand, context, score));
+    }
+
+    /// 和了したときの状況を作る
+    fn context(&self, seat: usize, tile: Tile, method: WinMethod, after_kan: bool) -> WinContext {
+        let mut context =
+            WinContext::new(tile, method, self.seat_wind(seat), self.setup.round_wind);
+        context.riichi = self.riichi[seat];
+        context.ippatsu = self.ippatsu[seat];
+        context.after_kan = after_kan;
+        context.last_tile = self.wall.is_exhausted() && !after_kan;
+        context.first_turn = !self.called && self.rivers[seat].is_empty();
+        context.dora_indicators = self.wall.dora_indicators();
+        context.ura_dora_indicators = self.wall.ura_dora_indicators();
+        context.honba = self.setup.honba;
+        return context;
+    }
+
+    /// 和了を作る
+    ///
+    /// 供託の立直棒は、放銃者から順に数えて最初の和了者が受け取る（`riichi_sticks`で渡す）。
+    fn make_win(
+        &self,
+        seat: usize,
+        discarder: Option<usize>,
+        hand: Hand,
+        context: WinContext,
+        score: ScoreResult,
+        riichi_sticks: u32,
+    ) -> Win {
+        let seats = WinSeats {
+            winner: seat,
+            dealer: self.setup.dealer,
+            discarder,
+        };
+        let deltas = win_deltas(&hand, &context, &score, &seats, riichi_sticks);
+        return Win {
+            winner: seat,
+            discarder,
+            hand,
+            context,
+            score,
+            deltas,
+        };
+    }
+
+    /// 鳴いて副露し、直後に捨てられない牌の種類（喰い替え）を返す
+    fn call(
+        &mut self,
+        seat: usize,
+        discarder: usize,
+        tile: Tile,
+        action: CallAction,
+    ) -> Vec<TileType> {
+        self.rivers[discarder].mark_last_called();
+        self.interrupt();
+        let kind = tile.get();
+        let (kinds, category, forbidden) = match action {
+            CallAction::Pon => (vec![kind, kind], OpenType::Pon, vec![kind]),
+            CallAction::OpenKan => (vec![kind, kind, kind], OpenType::Kan, Vec::new()),
+            CallAction::Chi(first) => (
+                (first..first + 3).filter(|&k| k != kind).collect(),
+                OpenType::Chi,
+                forbidden_after_chi(kind, first),
+            ),
+            CallAction::Pass | CallAction::Ron => unreachable!("not a call"),
+        };
+        let mut rest = self.hands[seat].tiles().to_vec();
+        let mut from_hand = Vec::new();
+        for k in kinds {
+            let i = rest
+                .iter()
+                .position(|t| t.get() == k)
+                .expect("call needs the tiles");
+            from_hand.push(rest.remove(i));
+        }
+        let mut tiles = from_hand.clone();
+        tiles.push(tile);
+        let tiles = if category == OpenType::Kan {
+            kan_tiles(&tiles)
+        } else {
+            tiles.sort();
+            [tiles[0], tiles[1], tiles[2]]
+        };
+        let open = OpenTiles {
+            tiles,
+            category,
+            from: open_from(seat, discarder),
+            kan: if category == OpenType::Kan {
+                Some(KanType::Open)
+            } else {
+                None
+            },
+        };
+        self.hands[seat].open(open, &from_hand);
+        if category == OpenType::Kan {
+            self.kan_seats.push(seat);
+        }
+        return forbidden;
+    }
+
+    /// 立直を成立させ、立直棒を供託する
+    fn establish_riichi(&mut self, seat: usize) {
+        self.deltas[seat] -= 1000;
+        self.riichi_sticks += 1;
+        self.ippatsu[seat] = true;
+        self.missed[seat].since_riichi = Some(Vec::new());
+    }
+
+    /// 他家の見逃した牌に`tile`を加える
+    fn miss(&mut self, discarder: usize, tile: Tile) {
+        for i in 1..4 {
+            let missed = &mut self.missed[(discarder + i) % 4];
+            missed.since_last_discard.push(tile);
+            if let Some(since_riichi) = missed.since_riichi.as_mut() {
+                since_riichi.push(tile);
+            }
+        }
+    }
+
+    /// 鳴き（暗槓を含む）が入ったことを記録し、全員の一発を消す
+    fn interrupt(&mut self) {
+        self.called = true;
+        self.ippatsu = [false; 4];
+    }
+
+    /// 嶺上牌をツモる（ドラ表示牌は`reveal_pending_indicators`でめくる）
+    fn draw_rinshan(&mut self, seat: usize) {
+        let tile = self
+            .wall
+            .draw_rinshan()
+            .expect("kan is offered only while rinshan tiles remain");
+        self.hands[seat].draw(tile);
+        self.pending_indicators += 1;
+    }
+
+    /// めくっていないドラ表示牌をめくる
+    fn reveal_pending_indicators(&mut self) {
+        for _ in 0..self.pending_indicators {
+            self.wall.reveal_dora_indicator().ok();
+        }
+        self.pending_indicators = 0;
+    }
+
+    /// 手牌とツモ牌を返す
+    fn concealed(&self, seat: usize) -> Vec<Tile> {
+        let hand = &self.hands[seat];
+        let mut result = hand.tiles().to_vec();
+        result.extend(hand.drawn());
+        return result;
+    }
+
+    /// 槓できる（5回目の槓でも海底牌での槓でもない）か否かを返す
+    fn can_kan(&self) -> bool {
+        return self.wall.kans() < Wall::MAX_KANS && !self.wall.is_exhausted();
+    }
+
+    /// 立直していれば、待ちの変わらないツモ牌での暗槓か否かを返す（立直していなければ`true`）
+    fn can_closed_kan_in_riichi(&self, seat: usize, kind: TileType) -> bool {
+        if self.riichi[seat] == RiichiState::None {
+            return true;
+        }
+        let hand = &self.hands[seat];
+        if hand.drawn().map(|t| t.get()) != Some(kind) {
+            return false;
+        }
+        let tiles: Vec<Tile> = self
+            .concealed(seat)
+            .into_iter()
+            .filter(|t| t.get() == kind)
+            .collect();
+        let mut after = hand.clone();
+        after.open(
+            OpenTiles {
+                tiles: kan_tiles(&tiles),
+                category: OpenType::Kan,
+                from: OpenFrom::Myself,
+                kan: Some(KanType::Closed),
+            },
+            &tiles,
+        );
+        return wait_kinds(hand) == wait_kinds(&after);
+    }
+
+    /// 立直を宣言できる（門前で1000点以上持ち、ツモが4枚以上残っている）か否かを返す
+    fn can_declare_riichi(&self, seat: usize) -> bool {
+        return self.riichi[seat] == RiichiState::None
+            && self.hands[seat].is_closed()
+            && self.score(seat) >= 1000
+            && self.wall.remaining() >= 4;
+    }
+
+    /// 九種九牌で流局できるか否かを返す
+    fn can_abort_with_nine_terminals(&self, seat: usize) -> bool {
+        if !self.ruleset.abortive_draws.kyuushu_kyuuhai
+            || self.called
+            || !self.rivers[seat].is_empty()
+        {
+            return false;
+        }
+        let mut kinds: Vec<TileType> = self
+            .concealed(seat)
+            .iter()
+            .filter(|t| t.is_1_9_honor())
+            .map(|t| t.get())
+            .collect();
+        kinds.sort();
+        kinds.dedup();
+        return kinds.len() >= 9;
+    }
+
+    /// 打牌が通った後の途中流局（四風連打・四家立直・四開槓）を判定する
+    fn abortive_draw_after_discard(&self) -> Option<AbortiveDraw> {
+        let rules = &self.ruleset.abortive_draws;
+        let first = self.rivers[0].discards().first().map(|d| d.tile);
+        let four_winds = !self.called
+            && first.is_some_and(|t| t.is_wind())
+            && self
+                .rivers
+                .iter()
+                .all(|r| r.len() == 1 && Some(r.discards()[0].tile) == first);
+        if rules.suufon_renda && four_winds {
+            return Some(AbortiveDraw::FourWinds);
+        }
+        let four_riichi = self.riichi.iter().all(|&r| r != RiichiState::None);
+        if rules.suucha_riichi && four_riichi {
+            return Some(AbortiveDraw::FourRiichi);
+        }
+        let four_kans = self.wall.kans() == Wall::MAX_KANS
+            && self.kan_seats.iter().any(|&s| s != self.kan_seats[0]);
+        if rules.suukaikan && four_kans {
+            return Some(AbortiveDraw::FourKans);
+        }
+        return None;
+    }
+
+    /// ロンした和了で局を終える（三家和なら途中流局）
+    ///
+    /// `wins`は放銃者から順に並んでいる。頭跳ねなら最初の1人だけが和了する。
+    fn end_with_wins(&self, mut wins: Vec<Win>) -> RoundEnd {
+        if wins.len() == 3 && self.ruleset.abortive_draws.sanchahou {
+            return self.abort(AbortiveDraw::ThreeRons);
+        }
+        if self.ruleset.multiple_ron == MultipleRon::FirstInTurnOrder {
+            wins.truncate(1);
+        }
+        return RoundEnd::Win(wins);
+    }
+
+    /// 途中流局の結果を作る（本場は必ず増える）
+    fn abort(&self, kind: AbortiveDraw) -> RoundEnd {
+        return RoundEnd::AbortiveDraw(AbortiveDrawResult {
+            kind,
+            dealer_keeps_seat: self.ruleset.abortive_draws.dealer_keeps_seat,
+            next_honba: self.setup.honba + 1,
+        });
+    }
+
+    /// 荒牌平局で局を終える
+    fn exhaustive_draw(&mut self, players: &mut [Box<dyn Player>; 4]) -> RoundResult {
+        let mut declared = [false; 4];
+        for seat in 0..4 {
+            declared[seat] = is_tenpai(&self.hands[seat], &self.ruleset)
+                && (!self.ruleset.tenpai_declaration
+                    || self.riichi[seat] != RiichiState::None
+                    || players[seat].declare_tenpai(self, seat));
+        }
+        let settlement = settle_exhaustive_draw(
+            &self.hands,
+            &self.rivers,
+            declared,
+            self.setup.dealer,
+            &self.ruleset,
+        );
+        return self.finish(RoundEnd::ExhaustiveDraw(settlement));
+    }
+
+    /// 局の結果を作る
+    fn finish(&self, end: RoundEnd) -> RoundResult {
+        let mut deltas = self.deltas;
+        let mut riichi_sticks = self.riichi_sticks;
+        let mut add = |other: &[i32; 4]| {
+            for (d, o) in deltas.iter_mut().zip(other.iter()) {
+                *d += o;
+            }
+        };
+        match &end {
+            RoundEnd::Win(wins) => {
+                for win in wins.iter() {
+                    add(&win.deltas);
+                }
+                riichi_sticks = 0;
+            }
+            RoundEnd::ExhaustiveDraw(settlement) => add(&settlement.deltas),
+            RoundEnd::AbortiveDraw(_) => {}
+        }
+        return RoundResult {
+            end,
+            deltas,
+            riichi_sticks,
+        };
+    }
+}
+
+/// 和了形か否かを返す
+fn is_complete(hand: &Hand, ruleset: &Ruleset) -> bool {
+    return HandAnalyzer::calc_with_rule(hand, ruleset).shanten == -1;
+}
+
+/// 待ち牌の種類を返す
+fn wait_kinds(hand: &Hand) -> Vec<TileType> {
+    let mut result: Vec<TileType> = HandAnalyzer::calc_waiting_tiles(hand)
+        .iter()
+        .map(|t| t.get())
+        .collect();
+    result.sort();
+    result.dedup();
+    return result;
+}
+
+/// 槓子として副露に残す3枚を選ぶ（赤ドラを優先する）
+fn kan_tiles(tiles: &[Tile]) -> [Tile; 3] {
+    let mut tiles = tiles.to_vec();
+    tiles.sort_by_key(|t| !t.is_red_dora());
+    return [tiles[0], tiles[1], tiles[2]];
+}
+
+/// `seat`から見て`other`の席がどこかを返す
+fn open_from(seat: usize, other: usize) -> OpenFrom {
+    return match (other + 4 - seat) % 4 {
+        1 => OpenFrom::Following,
+        2 => OpenFrom::Opposite,
+        3 => OpenFrom::Previous,
+        _ => OpenFrom::Myself,
+    };
+}
+
+/// `kind`を含む順子の最初の牌の種類を返す
+fn chi_starts(kind: TileType) -> Vec<TileType> {
+    return (0..3)
+        .filter_map(|d| kind.checked_sub(d))
+        .filter(|&first| first / 9 == kind / 9 && first % 9 <= 6)
+        .rev()
+        .collect();
+}
+
+/// チーした直後に捨てられない牌の種類（現物と筋の喰い替え）を返す
+fn forbidden_after_chi(kind: TileType, first: TileType) -> Vec<TileType> {
+    let mut result = vec![kind];
+    if kind == first && first % 9 <= 5 {
+        result.push(first + 3);
+    }
+    if kind == first + 2 && first % 9 >= 1 {
+        result.push(first - 1);
+    }
+    return result;
+}
+
+/// 手牌から`used`の牌を除いて鳴いた後に、`forbidden`以外の捨てられる牌が残るか否かを返す
+fn has_discard_after_call(hand: &[Tile], used: &[TileType], forbidden: &[TileType]) -> bool {
+    let mut rest: Vec<TileType> = hand.iter().map(|t| t.get()).collect();
+    for kind in used {
+        if let Some(i) = rest.iter().position(|k| k == kind) {
+            rest.remove(i);
+        }
+    }
+    return rest.iter().any(|k| !forbidden.contains(k));
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_info::yaku::Yaku;
+
+    /// 和了できれば和了し、それ以外はツモ切りするプレイヤー
+    #[derive(Default)]
+    struct AutoPlayer {
+        /// 立直できれば立直する
+        riichi: bool,
+        /// 槓できれば槓する
+        kan: bool,
+        /// この種類の牌が捨てられたら1度だけ鳴く
+        call_on: Option<TileType>,
+        /// 九種九牌で流局できれば流局する
+        abort: bool,
+    }
+
+    impl Player for AutoPlayer {
+        fn decide_turn(
+            &mut self,
+            round: &Round,
+            seat: usize,
+            options: &[TurnAction],
+        ) -> TurnAction {
+            let drawn = round.hand(seat).drawn();
+            for option in options.iter() {
+                match option {
+                    TurnAction::Tsumo => return *option,
+                    TurnAction::ClosedKan(_) | TurnAction::AddedKan(_) if self.kan => {
+                        return *option
+                    }
+                    _ => {}
+                }
+            }
+            if self.abort && options.contains(&TurnAction::NineTerminals) {
+                return TurnAction::NineTerminals;
+            }
+            if self.riichi {
+                if let Some(tile) = drawn {
+                    if options.contains(&TurnAction::Riichi(tile)) {
+                        return TurnAction::Riichi(tile);
+                    }
+                }
+                let riichi = options.iter().find(|o| matches!(o, TurnAction::Riichi(_)));
+                if let Some(option) = riichi {
+                    return *option;
+                }
+            }
+            if let Some(tile) = drawn {
+                if options.contains(&TurnAction::Discard(tile)) {
+                    return TurnAction::Discard(tile);
+                }
+            }
+            return *options
+                .iter()
+                .find(|o| matches!(o, TurnAction::Discard(_)))
+                .unwrap();
+        }
+
+        fn decide_call(
+            &mut self,
+            _round: &Round,
+            _seat: usize,
+            tile: Tile,
+            options: &[CallAction],
+        ) -> CallAction {
+            if options.contains(&CallAction::Ron) {
+                return CallAction::Ron;
+            }
+            if self.call_on == Some(tile.get()) {
+                self.call_on = None;
+                return options[1];
+            }
+            return CallAction::Pass;
+        }
+    }
+
+    /// 和了も鳴きもせずにツモ切りするプレイヤー
+    struct Tsumogiri;
+
+    impl Player for Tsumogiri {
+        fn decide_turn(
+            &mut self,
+            round: &Round,
+            seat: usize,
+            options: &[TurnAction],
+        ) -> TurnAction {
+            return match round.hand(seat).drawn() {
+                Some(tile) => TurnAction::Discard(tile),
+                None => options[0],
+            };
+        }
+
+        fn decide_call(&mut self, _: &Round, _: usize, _: Tile, _: &[CallAction]) -> CallAction {
+            return CallAction::Pass;
+        }
+    }
+
+    /// 東1局0本場、親は席0
+    fn setup() -> RoundSetup {
+        return RoundSetup {
+            round_wind: Wind::East,
+            dealer: 0,
+            honba: 0,
+            riichi_sticks: 0,
+            scores: [25000; 4],
+        };
+    }
+
+    /// 席0から順に`hands`を配り、その後に`draws`をツモる牌山を作る
+    ///
+    /// `dead`を指定すれば王牌にし、残りの牌は牌の種類の順に並べる。
+    fn arranged_wall(hands: [&str; 4], draws: &str, dead: &str) -> Wall {
+        let hands: Vec<Vec<Tile>> = hands
+            .iter()
+            .map(|s| Hand::from(s).tiles().to_vec())
+            .collect();
+        let mut tiles = Vec::new();
+        for &(start, count) in [(0, 4), (4, 4), (8, 4), (12, 1)].iter() {
+            for hand in hands.iter() {
+                tiles.extend_from_slice(&hand[start..start + count]);
+            }
+        }
+        tiles.extend_from_slice(Hand::from(draws).tiles());
+        let dead = Hand::from(dead).tiles().to_vec();
+        let mut rest = Wall::full_set([0, 0, 0]);
+        for tile in tiles.iter().chain(dead.iter()) {
+            let i = rest.iter().position(|t| t == tile).unwrap();
+            rest.remove(i);
+        }
+        tiles.extend(rest);
+        tiles.extend(dead);
+        return Wall::from_tiles(tiles);
+    }
+
+    /// 聴牌している席0〜2と、バラバラの席3
+    const HANDS: [&str; 4] = [
+        "123m456p789s1122z",
+        "456m789p123s3344z",
+        "789m123p456s5566z",
+        "2468m2468p2468s7z",
+    ];
+
+    /// ドラ表示牌と裏ドラ表示牌が1mの王牌（`HANDS`の誰のドラにもならない）
+    const DEAD: &str = "777z9s11m99s111s333m";
+
+    fn players(players: [AutoPlayer; 4]) -> [Box<dyn Player>; 4] {
+        return players.map(|p| Box::new(p) as Box<dyn Player>);
+    }
+
+    fn yaku_of(win: &Win) -> Vec<Yaku> {
+        return win.score.chosen.yaku.iter().map(|a| a.yaku).collect();
+    }
+
+    #[test]
+    /// 親の配牌とツモで和了すれば天和
+    fn heavenly_hand() {
+        let mut round = Round::new(
+            setup(),
+            arranged_wall(HANDS, "2z", DEAD),
+            &Ruleset::default(),
+        );
+        let result = round.run(&mut players(Default::default()));
+        match result.end {
+            RoundEnd::Win(wins) => {
+                assert_eq!(wins.len(), 1);
+                assert_eq!(wins[0].winner, 0);
+                assert_eq!(wins[0].discarder, None);
+                assert!(yaku_of(&wins[0]).contains(&Yaku::HeavenlyHand));
+            }
+            _ => panic!("dealer must win"),
+        }
+        assert_eq!(result.deltas, [48000, -16000, -16000, -16000]);
+    }
+
+    #[test]
+    /// 立直の宣言牌が通れば供託し、1巡以内のロンは一発になる（第一打での立直はダブル立直）
+    fn riichi_and_one_shot() {
+        let wall = arranged_wall(HANDS, "9m9p3z", DEAD);
+        let mut round = Round::new(setup(), wall, &Ruleset::default());
+        let mut p = players([
+            AutoPlayer::default(),
+            AutoPlayer {
+                riichi: true,
+                ..Default::default()
+            },
+            AutoPlayer::default(),
+            AutoPlayer::default(),
+        ]);
+        let result = round.run(&mut p);
+        match &result.end {
+            RoundEnd::Win(wins) => {
+                assert_eq!(wins[0].winner, 1);
+                assert_eq!(wins[0].discarder, Some(2));
+                let yaku = yaku_of(&wins[0]);
+                assert!(yaku.contains(&Yaku::DoubleReady));
+                assert!(yaku.contains(&Yaku::OneShot));
+            }
+            _ => panic!("seat 1 must win"),
+        }
+        assert!(round.river(1).discards()[0].riichi);
+        assert_eq!(round.riichi(1), RiichiState::DoubleRiichi);
+        // 立直棒の1000点は和了者に戻る（40符3翻 5200点）
+        assert_eq!(result.deltas, [0, 5200, -5200, 0]);
+        assert_eq!(result.riichi_sticks, 0);
+    }
+
+    #[test]
+    /// ポンはチーより優先される
+    fn pon_over_chi() {
+        let hands = [
+            "123m456p789s1122z",
+            "456m789p346s3344z",
+            "789m123p55s5566z7z",
+            "2468m2468p2468s7z",
+        ];
+        let mut round = Round::new(setup(), arranged_wall(hands, "5s", ""), &Ruleset::default());
+        let mut p = players([
+            AutoPlayer::default(),
+            AutoPlayer {
+                call_on: Some(Tile::S5),
+                ..Default::default()
+            },
+            AutoPlayer {
+                call_on: Some(Tile::S5),
+                ..Default::default()
+            },
+            AutoPlayer::default(),
+        ]);
+        round.run(&mut p);
+        assert!(round.river(0).discards()[0].called);
+        assert!(round.hand(1).opened().is_empty());
+        let pon = &round.hand(2).opened()[0];
+        assert_eq!(pon.category, OpenType::Pon);
+        assert_eq!(pon.from, OpenFrom::Opposite);
+        // 喰い替えになる5sは捨てない
+        assert_ne!(round.river(2).discards()[0].tile.get(), Tile::S5);
+    }
+
+    #[test]
+    /// 暗槓すると嶺上牌をツモり、ドラ表示牌をすぐにめくる
+    fn closed_kan() {
+        let hands = [
+            "111z234m567p89s22z",
+            "456m789p123s3344z",
+            "789m123p456s5566z",
+            "2468m2468p2468s7z",
+        ];
+        let mut round = Round::new(
+            setup(),
+            arranged_wall(hands, "1z", DEAD),
+            &Ruleset::default(),
+        );
+        let mut p = players([
+            AutoPlayer {
+                kan: true,
+                ..Default::default()
+            },
+            AutoPlayer::default(),
+            AutoPlayer::default(),
+            AutoPlayer::default(),
+        ]);
+        round.run(&mut p);
+        let kan = &round.hand(0).opened()[0];
+        assert_eq!(kan.category, OpenType::Kan);
+        assert_eq!(kan.from, OpenFrom::Myself);
+        assert!(round.wall().kans() >= 1);
+        assert_eq!(
+            round.wall().dora_indicators().len(),
+            round.wall().kans() + 1
+        );
+    }
+
+    #[test]
+    /// 加槓した牌でロンすれば搶槓
+    fn robbing_a_quad() {
+        let mut round = Round::new(
+            setup(),
+            arranged_wall(HANDS, "5m", DEAD),
+            &Ruleset::default(),
+        );
+        round.hands[0] = Hand::from("123p456p789s1z 555m");
+        round.hands[1] = Hand::from("46m789p123s33z444z");
+        let mut p = players([
+            AutoPlayer {
+                kan: true,
+                ..Default::default()
+            },
+            AutoPlayer::default(),
+            AutoPlayer::default(),
+            AutoPlayer::default(),
+        ]);
+        let result = round.run(&mut p);
+        match &result.end {
+            RoundEnd::Win(wins) => {
+                assert_eq!(wins[0].winner, 1);
+                assert_eq!(wins[0].discarder, Some(0));
+                assert!(wins[0].context.robbing_kan);
+                assert!(yaku_of(&wins[0]).contains(&Yaku::RobbingAQuad));
+            }
+            _ => panic!("seat 1 must rob the kan"),
+        }
+        // 40符1翻 1300点
+        assert_eq!(result.deltas, [-1300, 1300, 0, 0]);
+        // 搶槓されたので嶺上牌はツモっていない
+        assert_eq!(round.wall().kans(), 0);
+    }
+
+    #[test]
+    /// 誰も和了しなければツモれる牌が尽きて荒牌平局
+    fn exhaustive_draw() {
+        let wall = Wall::new(&mut RandomShuffler::seeded(7), [1, 1, 1]);
+        let mut round = Round::new(setup(), wall, &Ruleset::default());
+        let mut p: [Box<dyn Player>; 4] = [
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+        ];
+        let result = round.run(&mut p);
+        let settlement = match &result.end {
+            RoundEnd::ExhaustiveDraw(settlement) => settlement,
+            _ => panic!("the wall must run out"),
+        };
+        assert!(round.wall().is_exhausted());
+        let discards: usize = (0..4).map(|seat| round.river(seat).len()).sum();
+        assert_eq!(discards, 122 - 52);
+        assert_eq!(result.deltas, settlement.deltas);
+        assert_eq!(result.deltas.iter().sum::<i32>(), 0);
+    }
+
+    #[test]
+    /// チーできる順子と喰い替えになる牌
+    fn chi_helpers() {
+        assert_eq!(chi_starts(Tile::M1), vec![Tile::M1]);
+        assert_eq!(chi_starts(Tile::P5), vec![Tile::P3, Tile::P4, Tile::P5]);
+        assert_eq!(chi_starts(Tile::S9), vec![Tile::S7]);
+        assert_eq!(
+            forbidden_after_chi(Tile::M4, Tile::M4),
+            vec![Tile::M4, Tile::M7]
+        );
+        assert_eq!(
+            forbidden_after_chi(Tile::M6, Tile::M4),
+            vec![Tile::M6, Tile::M3]
+        );
+        assert_eq!(forbidden_after_chi(Tile::M5, Tile::M4), vec![Tile::M5]);
+    }
+
+    /// 途中流局の結果を取り出す
+    fn abortive(result: &RoundResult) -> AbortiveDrawResult {
+        return match result.end {
+            RoundEnd::AbortiveDraw(r) => r,
+            _ => panic!("round must end in an abortive draw"),
+        };
+    }
+
+    #[test]
+    /// 第一ツモで么九牌が9種類以上あれば九種九牌を選べる
+    fn nine_terminals() {
+        let hands = ["1559m159p19s1234z", HANDS[1], HANDS[2], HANDS[3]];
+        let dead = "777z999s66p555s33m4s";
+        let mut ruleset = Ruleset::tenhou();
+        let mut round = Round::new(setup(), arranged_wall(hands, "6m", dead), &ruleset);
+        let mut p = players([
+            AutoPlayer {
+                abort: true,
+                ..Default::default()
+            },
+            AutoPlayer::default(),
+            AutoPlayer::default(),
+            AutoPlayer::default(),
+        ]);
+        let result = round.run(&mut p);
+        assert_eq!(
+            abortive(&result),
+            AbortiveDrawResult {
+                kind: AbortiveDraw::NineTerminals,
+                dealer_keeps_seat: true,
+                next_honba: 1,
+            }
+        );
+        assert_eq!(result.deltas, [0; 4]);
+
+        ruleset.abortive_draws.kyuushu_kyuuhai = false;
+        let mut round = Round::new(setup(), arranged_wall(hands, "6m", dead), &ruleset);
+        let tile = round.wall.draw().unwrap();
+        round.hands[0].draw(tile);
+        let options = round.turn_options(0, None, false);
+        assert!(!options.contains(&TurnAction::NineTerminals));
+    }
+
+    #[test]
+    /// 第一打で4人が同じ風牌を捨てれば四風連打
+    fn four_winds() {
+        let hands = [HANDS[3]; 4];
+        let dead = "111m333m999p555s22z";
+        let wall = arranged_wall(hands, "1z1z1z1z", dead);
+        let mut round = Round::new(setup(), wall, &Ruleset::tenhou());
+        let mut p: [Box<dyn Player>; 4] = [
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+            Box::new(Tsumogiri),
+        ];
+        let result = round.run(&mut p);
+        assert_eq!(abortive(&result).kind, AbortiveDraw::FourWinds);
+
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.abortive_draws.suufon_renda = false;
+        let wall = arranged_wall(hands, "1z1z1z1z", dead);
+        let mut round = Round::new(setup(), wall, &ruleset);
+        let result = round.run(&mut p);
+        assert!(!matches!(result.end, RoundEnd::AbortiveDraw(_)));
+    }
+
+    #[test]
+    /// 4人目の立直の宣言牌が通れば四家立直（立直棒は供託に残る）
+    fn four_riichi() {
+        let hands = [HANDS[0], HANDS[1], HANDS[2], "111m999p111s999s7z"];
+        let wall = arranged_wall(hands, "9m9m8m8m", "333m66p555p555s777z");
+        let mut ruleset = Ruleset::m_league();
+        ruleset.abortive_draws.dealer_keeps_seat = false;
+        let mut round = Round::new(setup(), wall, &ruleset);
+        let riichi = || AutoPlayer {
+            riichi: true,
+            ..Default::default()
+        };
+        let result = round.run(&mut players([riichi(), riichi(), riichi(), riichi()]));
+        assert_eq!(
+            abortive(&result),
+            AbortiveDrawResult {
+                kind: AbortiveDraw::FourRiichi,
+                dealer_keeps_seat: false,
+                next_honba: 1,
+            }
+        );
+        assert_eq!(result.riichi_sticks, 4);
+        assert_eq!(result.deltas, [-1000; 4]);
+    }
+
+    #[test]
+    /// 3人が同じ牌でロンすれば三家和（ルールで無効にすれば3人とも和了、頭跳ねなら下家だけが和了）
+    fn three_rons() {
+        let hands = [
+            "2468m2468s1234z9p",
+            "123m456m789m123p5z",
+            "123s456s789s456p5z",
+            "666z777z123p789p5z",
+        ];
+        let dead = "111z222z333z444z99m";
+        let wall = arranged_wall(hands, "5z", dead);
+        let mut round = Round::new(setup(), wall, &Ruleset::tenhou());
+        let result = round.run(&mut players(Default::default()));
+        assert_eq!(abortive(&result).kind, AbortiveDraw::ThreeRons);
+        assert_eq!(result.deltas, [0; 4]);
+
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.abortive_draws.sanchahou = false;
+        let wall = arranged_wall(hands, "5z", dead);
+        let mut round = Round::new(setup(), wall, &ruleset);
+        let result = round.run(&mut players(Default::default()));
+        match &result.end {
+            RoundEnd::Win(wins) => assert_eq!(wins.len(), 3),
+            _ => panic!("three players must win"),
+        }
+
+        let wall = arranged_wall(hands, "5z", dead);
+        let mut round = Round::new(setup(), wall, &Ruleset::m_league());
+        let result = round.run(&mut players(Default::default()));
+        match &result.end {
+            RoundEnd::Win(wins) => {
+                assert_eq!(wins.len(), 1);
+                assert_eq!(wins[0].winner, 1);
+            }
+            _ => panic!("only the next player must win"),
+        }
+        assert_eq!(result.deltas.iter().filter(|&&d| d > 0).count(), 1);
+        assert!(result.deltas[1] > 0);
+    }
+}
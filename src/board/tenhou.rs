@@ -0,0 +1,506 @@
+use crate::board::wall::*;
+use crate::tile::*;
+use std::fmt;
+
+/// 天鳳の牌山生成のシード文字列の接頭辞
+const SEED_PREFIX: &str = "mt19937ar-sha512-n288-base64,";
+
+/// 1局の牌山を作るのに使う乱数の数
+const RANDOM_WORDS: usize = 288;
+
+/// 天鳳のシード文字列を解釈できなかった理由
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TenhouSeedError {
+    /// `mt19937ar-sha512-n288-base64,`で始まらない
+    InvalidPrefix,
+    /// Base64として解釈できない
+    InvalidBase64,
+    /// 復号したシードが2496バイト（624語）でない
+    InvalidLength(usize),
+}
+
+impl fmt::Display for TenhouSeedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            TenhouSeedError::InvalidPrefix => write!(f, "seed must start with {}", SEED_PREFIX),
+            TenhouSeedError::InvalidBase64 => write!(f, "seed is not valid base64"),
+            TenhouSeedError::InvalidLength(len) => {
+                write!(f, "seed must be 2496 bytes, but was {} bytes", len)
+            }
+        };
+    }
+}
+
+impl std::error::Error for TenhouSeedError {}
+
+/// 天鳳と同じ手順で牌山を並べる
+///
+/// 牌譜に記録されたシード文字列からMT19937を初期化し、1局ごとに288語の乱数を取り出す。
+/// 乱数を128バイトずつSHA-512にかけた値で牌を入れ替え、同じシードからは天鳳と同じ牌山を作る。
+/// 局を進めるたびに`Wall::new`に渡すと、次の局の牌山になる。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::board::tenhou::*;
+/// use mahjong_rs::board::wall::*;
+///
+/// let seed = format!("mt19937ar-sha512-n288-base64,{}", "AAAA".repeat(832));
+/// let mut shuffler = TenhouShuffler::from_seed(&seed).unwrap();
+/// let first = Wall::new(&mut shuffler, [1, 1, 1]);
+/// let second = Wall::new(&mut shuffler, [1, 1, 1]);
+/// assert_ne!(first, second);
+/// assert!(shuffler.dice().is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TenhouShuffler {
+    mt: Mt19937,
+    dice: Option<(u32, u32)>,
+}
+
+impl TenhouShuffler {
+    /// 牌譜のシード文字列から作る
+    pub fn from_seed(seed: &str) -> Result<TenhouShuffler, TenhouSeedError> {
+        let encoded = seed
+            .trim()
+            .strip_prefix(SEED_PREFIX)
+            .ok_or(TenhouSeedError::InvalidPrefix)?;
+        let bytes = decode_base64(encoded).ok_or(TenhouSeedError::InvalidBase64)?;
+        if bytes.len() != Mt19937::N * 4 {
+            return Err(TenhouSeedError::InvalidLength(bytes.len()));
+        }
+        let key: Vec<u32> = bytes
+            .chunks(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        return Ok(TenhouShuffler {
+            mt: Mt19937::from_key(&key),
+            dice: None,
+        });
+    }
+
+    /// 直前に並べた局のサイコロの目（`0`〜`5`）を返す
+    pub fn dice(&self) -> Option<(u32, u32)> {
+        return self.dice;
+    }
+
+    /// 次の局の並び（天鳳の牌番号`0`〜`135`）を返す
+    ///
+    /// 天鳳では並びの末尾から配牌を取り、先頭の14枚が王牌になる。
+    fn next_yama(&mut self) -> [usize; Wall::LEN] {
+        let mut src = Vec::with_capacity(RANDOM_WORDS * 4);
+        for _ in 0..RANDOM_WORDS {
+            src.extend_from_slice(&self.mt.next_u32().to_le_bytes());
+        }
+        let rnd: Vec<u32> = src
+            .chunks(128)
+            .flat_map(|chunk| {
+                let digest = sha512(chunk);
+                return (0..16)
+                    .map(|i| {
+                        let c = &digest[i * 4..i * 4 + 4];
+                        return u32::from_le_bytes([c[0], c[1], c[2], c[3]]);
+                    })
+                    .collect::<Vec<u32>>();
+            })
+            .collect();
+
+        let mut yama = [0; Wall::LEN];
+        for (i, id) in yama.iter_mut().enumerate() {
+            *id = i;
+        }
+        for (i, &r) in rnd.iter().enumerate().take(Wall::LEN - 1) {
+            yama.swap(i, i + (r as usize) % (Wall::LEN - i));
+        }
+        self.dice = Some((rnd[135] % 6, rnd[136] % 6));
+        return yama;
+    }
+}
+
+impl Shuffler for TenhouShuffler {
+    /// 天鳳の牌番号は牌の種類×4＋何枚目かで、赤五は各色の五の1枚目。
+    /// `Wall`の並びに合わせて、ツモ順に並べた後に嶺上牌・ドラ表示牌の順の王牌を置く。
+    fn shuffle(&mut self, tiles: &mut [Tile]) {
+        let yama = self.next_yama();
+        let sorted: Vec<Tile> = tiles.to_vec();
+        let live = (Wall::DEAD_WALL_LEN..Wall::LEN).rev();
+        // 王牌は上段（奇数）・下段（偶数）の順に、嶺上牌・ドラ表示牌と裏ドラ表示牌が並ぶ
+        let dead = (0..Wall::DEAD_WALL_LEN).map(|i| i ^ 1);
+        for (tile, position) in tiles.iter_mut().zip(live.chain(dead)) {
+            *tile = sorted[yama[position]];
+        }
+    }
+}
+
+/// Base64を復号する
+fn decode_base64(s: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| -> Option<u32> {
+        return match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a') as u32 + 26),
+            b'0'..=b'9' => Some((c - b'0') as u32 + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        };
+    };
+    let s = s.trim_end_matches('=').as_bytes();
+    let mut result = Vec::with_capacity(s.len() * 3 / 4);
+    let mut buffer = 0;
+    let mut bits = 0;
+    for &c in s {
+        buffer = (buffer << 6) | value(c)?;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    return Some(result);
+}
+
+/// 乱数生成器MT19937（32ビット版）
+#[derive(Debug, Clone)]
+struct Mt19937 {
+    state: [u32; Mt19937::N],
+    index: usize,
+}
+
+impl Mt19937 {
+    const N: usize = 624;
+    const M: usize = 397;
+
+    /// `init_genrand`で初期化する
+    fn new(seed: u32) -> Mt19937 {
+        let mut state = [0; Mt19937::N];
+        state[0] = seed;
+        for i in 1..Mt19937::N {
+            state[i] = 1812433253u32
+                .wrapping_mul(state[i - 1] ^ (state[i - 1] >> 30))
+                .wrapping_add(i as u32);
+        }
+        return Mt19937 {
+            state,
+            index: Mt19937::N,
+        };
+    }
+
+    /// `init_by_array`で初期化する
+    fn from_key(key: &[u32]) -> Mt19937 {
+        let mut mt = Mt19937::new(19650218);
+        let s = &mut mt.state;
+        let mut i = 1;
+        let mut j = 0;
+        for _ in 0..Mt19937::N.max(key.len()) {
+            s[i] = (s[i] ^ (s[i - 1] ^ (s[i - 1] >> 30)).wrapping_mul(1664525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= Mt19937::N {
+                s[0] = s[Mt19937::N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..Mt19937::N - 1 {
+            s[i] = (s[i] ^ (s[i - 1] ^ (s[i - 1] >> 30)).wrapping_mul(1566083941))
+                .wrapping_sub(i as u32);
+            i += 1;
+            if i >= Mt19937::N {
+                s[0] = s[Mt19937::N - 1];
+                i = 1;
+            }
+        }
+        s[0] = 0x80000000;
+        return mt;
+    }
+
+    /// `genrand_int32`で次の乱数を返す
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= Mt19937::N {
+            for i in 0..Mt19937::N {
+                let y =
+                    (self.state[i] & 0x80000000) | (self.state[(i + 1) % Mt19937::N] & 0x7fffffff);
+                let mut next = self.state[(i + Mt19937::M) % Mt19937::N] ^ (y >> 1);
+                if y & 1 != 0 {
+                    next ^= 0x9908b0df;
+                }
+                self.state[i] = next;
+            }
+            self.index = 0;
+        }
+        let mut y = self.state[self.index];
+        self.index += 1;
+        y ^= y >> 11;
+        y ^= (y << 7) & 0x9d2c5680;
+        y ^= (y << 15) & 0xefc60000;
+        y ^= y >> 18;
+        return y;
+    }
+}
+
+/// SHA-512のラウンド定数
+const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+/// SHA-512のハッシュ値を返す
+fn sha512(message: &[u8]) -> [u8; 64] {
+    let mut h: [u64; 8] = [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ];
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 128 != 112 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((message.len() as u128) * 8).to_be_bytes());
+
+    for block in padded.chunks(128) {
+        let mut w = [0u64; 80];
+        for (i, word) in block.chunks(8).enumerate() {
+            w[i] = u64::from_be_bytes([
+                word[0], word[1], word[2], word[3], word[4], word[5], word[6], word[7],
+            ]);
+        }
+        for i in 16..80 {
+            let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+            let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..80 {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA512_K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (x, y) in h.iter_mut().zip([a, b, c, d, e, f, g, hh].iter()) {
+            *x = x.wrapping_add(*y);
+        }
+    }
+
+    let mut result = [0; 64];
+    for (i, x) in h.iter().enumerate() {
+        result[i * 8..i * 8 + 8].copy_from_slice(&x.to_be_bytes());
+    }
+    return result;
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// すべて0のシードの文字列
+    fn zero_seed() -> String {
+        return format!("{}{}", SEED_PREFIX, "AAAA".repeat(832));
+    }
+
+    #[test]
+    /// MT19937の参照実装（mt19937ar.c）の出力と一致する
+    fn mt19937() {
+        let mut mt = Mt19937::from_key(&[0x123, 0x234, 0x345, 0x456]);
+        let expected = [1067595299, 955945823, 477289528, 4107218783, 4228976476];
+        for &e in expected.iter() {
+            assert_eq!(mt.next_u32(), e);
+        }
+    }
+
+    #[test]
+    /// SHA-512の標準のテストベクタと一致する
+    fn sha512_abc() {
+        let digest: String = sha512(b"abc")
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(
+            digest,
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+    }
+
+    #[test]
+    /// Base64の復号
+    fn base64() {
+        assert_eq!(decode_base64("TWFu"), Some(b"Man".to_vec()));
+        assert_eq!(decode_base64("TWE="), Some(b"Ma".to_vec()));
+        assert_eq!(decode_base64("T!=="), None);
+    }
+
+    #[test]
+    /// 不正なシード文字列
+    fn invalid_seed() {
+        assert_eq!(
+            TenhouShuffler::from_seed("AAAA").err(),
+            Some(TenhouSeedError::InvalidPrefix)
+        );
+        assert_eq!(
+            TenhouShuffler::from_seed(&format!("{}AAAA", SEED_PREFIX)).err(),
+            Some(TenhouSeedError::InvalidLength(3))
+        );
+    }
+
+    #[test]
+    /// 同じシードからは同じ牌山になり、全ての牌を1枚ずつ含む
+    fn same_seed_same_wall() {
+        let mut a = TenhouShuffler::from_seed(&zero_seed()).unwrap();
+        let mut b = TenhouShuffler::from_seed(&zero_seed()).unwrap();
+        let wall_a = Wall::new(&mut a, [1, 1, 1]);
+        assert_eq!(wall_a, Wall::new(&mut b, [1, 1, 1]));
+        assert_eq!(a.dice(), b.dice());
+
+        let mut tiles = Wall::full_set([1, 1, 1]);
+        let mut shuffled = tiles.clone();
+        a.shuffle(&mut shuffled);
+        shuffled.sort();
+        tiles.sort();
+        assert_eq!(shuffled, tiles);
+    }
+
+    #[test]
+    /// 天鳳の並び（牌番号）を牌山に置く位置
+    ///
+    /// 配牌とツモは並びの末尾から取り、嶺上牌は`yama[1]`・`yama[0]`・`yama[3]`・`yama[2]`の順、
+    /// ドラ表示牌は`yama[5]`から2枚おき、裏ドラ表示牌は`yama[4]`から2枚おきになる。
+    /// 赤五は牌番号16・52・88。
+    fn yama_layout() {
+        let mut shuffler = TenhouShuffler::from_seed(&zero_seed()).unwrap();
+        let yama = shuffler.clone().next_yama();
+        let sorted = Wall::full_set([1, 1, 1]);
+        let mut tiles = sorted.clone();
+        shuffler.shuffle(&mut tiles);
+        let of = |position: usize| sorted[yama[position]];
+
+        let mut wall = Wall::from_tiles(tiles.clone());
+        for i in 0..Wall::LEN - Wall::DEAD_WALL_LEN - Wall::MAX_KANS {
+            assert_eq!(wall.draw(), Ok(of(Wall::LEN - 1 - i)));
+        }
+        assert_eq!(wall.dora_indicators(), vec![of(5)]);
+        assert_eq!(wall.ura_dora_indicators(), vec![of(4)]);
+        for &position in [1, 0, 3, 2].iter() {
+            assert_eq!(wall.draw_rinshan(), Ok(of(position)));
+            wall.reveal_dora_indicator().unwrap();
+        }
+        assert_eq!(
+            wall.dora_indicators(),
+            vec![of(5), of(7), of(9), of(11), of(13)]
+        );
+        assert_eq!(
+            wall.ura_dora_indicators(),
+            vec![of(4), of(6), of(8), of(10), of(12)]
+        );
+
+        for &id in [16, 52, 88].iter() {
+            assert!(sorted[id].is_red_dora());
+        }
+        assert_eq!(tiles.iter().filter(|t| t.is_red_dora()).count(), 3);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::tile::*;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+
+/// 牌山を並べる方法
+pub trait Shuffler {
+    /// 牌を牌山の順に並べ替える
+    ///
+    /// `tiles`は牌の種類の順（同じ種類の中では赤五が先）に並んだ136枚で渡される。
+    fn shuffle(&mut self, tiles: &mut [Tile]);
+}
+
+/// 乱数で牌山を並べる
+pub struct RandomShuffler<R: Rng> {
+    rng: R,
+}
+
+impl<R: Rng> RandomShuffler<R> {
+    /// 乱数生成器から作る
+    pub fn new(rng: R) -> RandomShuffler<R> {
+        return RandomShuffler { rng };
+    }
+}
+
+impl RandomShuffler<ChaCha8Rng> {
+    /// シード値から作る（生成器はChaCha8に固定し、同じシード値なら同じ牌山になる）
+    pub fn seeded(seed: u64) -> RandomShuffler<ChaCha8Rng> {
+        return RandomShuffler::new(ChaCha8Rng::seed_from_u64(seed));
+    }
+}
+
+impl<R: Rng> Shuffler for RandomShuffler<R> {
+    fn shuffle(&mut self, tiles: &mut [Tile]) {
+        tiles.shuffle(&mut self.rng);
+    }
+}
+
+/// 牌山の操作に失敗した理由
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WallError {
+    /// ツモれる牌が残っていない
+    Exhausted,
+    /// 既に4回槓している
+    KanLimit,
+    /// めくれるドラ表示牌が残っていない（槓の回数より多くはめくれない）
+    NoMoreIndicators,
+}
+
+impl fmt::Display for WallError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            WallError::Exhausted => write!(f, "no tiles left in the live wall"),
+            WallError::KanLimit => write!(f, "no more than four kans can be made"),
+            WallError::NoMoreIndicators => write!(f, "no more dora indicators to reveal"),
+        };
+    }
+}
+
+impl std::error::Error for WallError {}
+
+/// 牌山
+///
+/// 136枚のうち最後の14枚を王牌とし、残りの122枚を先頭から順にツモる。
+/// 王牌は先頭の4枚が嶺上牌で、その後にドラ表示牌と裏ドラ表示牌が交互に5枚ずつ並ぶ。
+/// 槓するたびに嶺上牌を1枚ツモり、王牌を14枚に保つためにツモれる牌が1枚減る。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::board::wall::*;
+///
+/// let mut wall = Wall::new(&mut RandomShuffler::seeded(1), [1, 1, 1]);
+/// assert_eq!(wall.remaining(), 122);
+/// wall.draw().unwrap();
+/// wall.draw_rinshan().unwrap();
+/// wall.reveal_dora_indicator().unwrap();
+/// assert_eq!(wall.remaining(), 120);
+/// assert_eq!(wall.dora_indicators().len(), 2);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Wall {
+    /// 牌山の全ての牌（最後の14枚が王牌）
+    tiles: Vec<Tile>,
+    /// ツモった牌の数
+    drawn: usize,
+    /// 槓した（嶺上牌をツモった）回数
+    kans: usize,
+    /// めくったドラ表示牌の数
+    revealed: usize,
+}
+
+impl Wall {
+    /// 牌の総数
+    pub const LEN: usize = 136;
+    /// 王牌の枚数
+    pub const DEAD_WALL_LEN: usize = 14;
+    /// 槓できる回数の上限
+    pub const MAX_KANS: usize = 4;
+
+    /// 牌を並べて牌山を作る
+    ///
+    /// `red_fives`は萬子・筒子・索子それぞれの赤五の枚数。
+    pub fn new(shuffler: &mut impl Shuffler, red_fives: [u32; 3]) -> Wall {
+        let mut tiles = Wall::full_set(red_fives);
+        shuffler.shuffle(&mut tiles);
+        return Wall::from_tiles(tiles);
+    }
+
+    /// 牌山の順に並んだ牌から牌山を作る
+    ///
+    /// 136枚でなければ panic する。
+    pub fn from_tiles(tiles: Vec<Tile>) -> Wall {
+        if tiles.len() != Wall::LEN {
+            panic!("A wall must have 136 tiles!");
+        }
+        return Wall {
+            tiles,
+            drawn: 0,
+            kans: 0,
+            revealed: 1,
+        };
+    }
+
+    /// 牌の種類の順に並んだ136枚を返す
+    ///
+    /// 同じ種類の4枚のうち、先頭から`red_fives`で指定した枚数を赤五にする。
+    pub fn full_set(red_fives: [u32; 3]) -> Vec<Tile> {
+        let mut result = Vec::with_capacity(Wall::LEN);
+        for i in 0..Tile::LEN as TileType {
+            for copy in 0..4 {
+                let red = match i {
+                    Tile::M5 | Tile::P5 | Tile::S5 => copy < red_fives[(i / 9) as usize],
+                    _ => false,
+                };
+                result.push(if red { Tile::new_red(i) } else { Tile::new(i) });
+            }
+        }
+        return result;
+    }
+
+    /// ツモれる牌の残り枚数を返す
+    pub fn remaining(&self) -> usize {
+        return Wall::LEN - Wall::DEAD_WALL_LEN - self.drawn - self.kans;
+    }
+
+    /// ツモれる牌が残っていないか否かを返す
+    ///
+    /// 残っていなければ、直前のツモが海底牌、次の打牌が河底牌になる。
+    pub fn is_exhausted(&self) -> bool {
+        return self.remaining() == 0;
+    }
+
+    /// 槓した回数を返す
+    pub fn kans(&self) -> usize {
+        return self.kans;
+    }
+
+    /// 牌をツモる
+    pub fn draw(&mut self) -> Result<Tile, WallError> {
+        if self.is_exhausted() {
+            return Err(WallError::Exhausted);
+        }
+        let tile = self.tiles[self.drawn];
+        self.drawn += 1;
+        return Ok(tile);
+    }
+
+    /// 槓して嶺上牌をツモる
+    ///
+    /// 5回目の槓や、ツモれる牌が残っていないとき（海底牌での槓）はできない。
+    /// ドラ表示牌は`reveal_dora_indicator`で別にめくる。
+    pub fn draw_rinshan(&mut self) -> Result<Tile, WallError> {
+        if self.kans >= Wall::MAX_KANS {
+            return Err(WallError::KanLimit);
+        }
+        if self.is_exhausted() {
+            return Err(WallError::Exhausted);
+        }
+        let tile = self.dead_wall()[self.kans];
+        self.kans += 1;
+        return Ok(tile);
+    }
+
+    /// 次のドラ表示牌をめくる
+    ///
+    /// 最初のドラ表示牌は牌山を作った時点でめくられており、槓した回数より多くはめくれない。
+    pub fn reveal_dora_indicator(&mut self) -> Result<Tile, WallError> {
+        if self.revealed > self.kans {
+            return Err(WallError::NoMoreIndicators);
+        }
+        self.revealed += 1;
+        return Ok(self.dead_wall()[Wall::MAX_KANS + (self.revealed - 1) * 2]);
+    }
+
+    /// めくったドラ表示牌を返す
+    pub fn dora_indicators(&self) -> Vec<Tile> {
+        return (0..self.revealed)
+            .map(|i| self.dead_wall()[Wall::MAX_KANS + i * 2])
+            .collect();
+    }
+
+    /// めくったドラ表示牌に対応する裏ドラ表示牌を返す
+    pub fn ura_dora_indicators(&self) -> Vec<Tile> {
+        return (0..self.revealed)
+            .map(|i| self.dead_wall()[Wall::MAX_KANS + i * 2 + 1])
+            .collect();
+    }
+
+    /// 王牌を返す
+    fn dead_wall(&self) -> &[Tile] {
+        return &self.tiles[Wall::LEN - Wall::DEAD_WALL_LEN..];
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 牌の種類の順に並べたままの牌山を作る
+    fn sorted_wall() -> Wall {
+        return Wall::from_tiles(Wall::full_set([1, 1, 1]));
+    }
+
+    #[test]
+    /// 赤五を含む136枚
+    fn full_set() {
+        let tiles = Wall::full_set([1, 2, 0]);
+        assert_eq!(tiles.len(), Wall::LEN);
+        let reds: Vec<TileType> = tiles
+            .iter()
+            .filter(|t| t.is_red_dora())
+            .map(|t| t.get())
+            .collect();
+        assert_eq!(reds, vec![Tile::M5, Tile::P5, Tile::P5]);
+    }
+
+    #[test]
+    /// 同じシード値なら同じ牌山になる
+    fn seeded() {
+        let a = Wall::new(&mut RandomShuffler::seeded(42), [1, 1, 1]);
+        let b = Wall::new(&mut RandomShuffler::seeded(42), [1, 1, 1]);
+        let c = Wall::new(&mut RandomShuffler::seeded(43), [1, 1, 1]);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    /// ツモれる牌が尽きたらツモれない
+    fn exhausted() {
+        let mut wall = sorted_wall();
+        for _ in 0..122 {
+            assert!(wall.draw().is_ok());
+        }
+        assert!(wall.is_exhausted());
+        assert_eq!(wall.draw(), Err(WallError::Exhausted));
+        assert_eq!(wall.draw_rinshan(), Err(WallError::Exhausted));
+    }
+
+    #[test]
+    /// 嶺上牌とドラ表示牌の順
+    fn dead_wall_order() {
+        let mut wall = sorted_wall();
+        // 王牌は4z×2・5z×4・6z×4・7z×4の最後の14枚
+        assert_eq!(wall.dora_indicators(), vec![Tile::new(Tile::Z5)]);
+        assert_eq!(wall.draw_rinshan(), Ok(Tile::new(Tile::Z4)));
+        assert_eq!(wall.reveal_dora_indicator(), Ok(Tile::new(Tile::Z6)));
+        assert_eq!(
+            wall.reveal_dora_indicator(),
+            Err(WallError::NoMoreIndicators)
+        );
+        assert_eq!(wall.ura_dora_indicators().len(), 2);
+    }
+
+    #[test]
+    /// 5回目の槓はできない
+    fn kan_limit() {
+        let mut wall = sorted_wall();
+        for _ in 0..Wall::MAX_KANS {
+            assert!(wall.draw_rinshan().is_ok());
+            assert!(wall.reveal_dora_indicator().is_ok());
+        }
+        assert_eq!(wall.draw_rinshan(), Err(WallError::KanLimit));
+        assert_eq!(wall.remaining(), 118);
+        assert_eq!(wall.dora_indicators().len(), 5);
+    }
+}
@@ -3,7 +3,7 @@ use crate::tile::*;
 use std::collections::VecDeque;
 
 /// 手牌
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Hand {
     /// 現在の手牌（副露がなければ13枚）
     tiles: Vec<Tile>,
@@ -24,12 +24,121 @@ impl Hand {
         }
     }
 
-    fn sort(&mut self) {
+    /// 副露を返す
+    pub fn opened(&self) -> &[OpenTiles] {
+        return &self.opened;
+    }
+
+    /// 門前（暗槓以外の副露がない）か否かを返す
+    pub fn is_closed(&self) -> bool {
+        return self
+            .opened
+            .iter()
+            .all(|open| open.category == OpenType::Kan && open.from == OpenFrom::Myself);
+    }
+
+    /// 手牌（副露・ツモ牌を除く）を返す
+    pub fn tiles(&self) -> &[Tile] {
+        return &self.tiles;
+    }
+
+    /// ツモってきた牌を返す
+    pub fn drawn(&self) -> Option<Tile> {
+        return self.drawn;
+    }
+
+    /// 牌をツモる
+    ///
+    /// 既にツモ牌があれば、それを手牌に加えてから新しいツモ牌にする。
+    pub fn draw(&mut self, tile: Tile) {
+        self.merge_drawn();
+        self.drawn = Some(tile);
+    }
+
+    /// 牌を捨てる
+    ///
+    /// ツモ牌か手牌から`tile`（赤ドラも区別する）を除き、残ったツモ牌を手牌に加えて並べる。
+    /// 持っていない牌なら何もせず`false`を返す。
+    pub fn discard(&mut self, tile: Tile) -> bool {
+        if self.drawn == Some(tile) {
+            self.drawn = None;
+        } else if let Some(i) = self.tiles.iter().position(|&t| t == tile) {
+            self.tiles.remove(i);
+        } else {
+            return false;
+        }
+        self.merge_drawn();
+        return true;
+    }
+
+    /// 手牌（ツモ牌を含む）から`from_hand`の牌を除いて副露を加える（鳴き・暗槓）
+    ///
+    /// `from_hand`の牌を全て持っていなければ何もせず`false`を返す。
+    pub fn open(&mut self, open: OpenTiles, from_hand: &[Tile]) -> bool {
+        let mut tiles = self.tiles.clone();
+        tiles.extend(self.drawn);
+        for tile in from_hand {
+            match tiles.iter().position(|t| t == tile) {
+                Some(i) => tiles.remove(i),
+                None => return false,
+            };
+        }
+        tiles.sort();
+        self.tiles = tiles;
+        self.drawn = None;
+        self.opened.push(open);
+        return true;
+    }
+
+    /// ポンした牌と同じ牌を手牌（ツモ牌を含む）から加えて加槓する
+    ///
+    /// 赤ドラを加えたときは、副露の牌の1枚を赤ドラに置き換える。
+    /// 副露の位置は変えず、加槓した時点の副露の数を`KanType::Added`に記録する。
+    /// 同じ種類のポンがないか、牌を持っていなければ何もせず`false`を返す。
+    pub fn add_kan(&mut self, tile: Tile) -> bool {
+        let pon = self
+            .opened
+            .iter()
+            .position(|open| open.category == OpenType::Pon && open.tiles[0].get() == tile.get());
+        let pon = match pon {
+            Some(i) => i,
+            None => return false,
+        };
+        let mut tiles = self.tiles.clone();
+        tiles.extend(self.drawn);
+        match tiles.iter().position(|&t| t == tile) {
+            Some(i) => tiles.remove(i),
+            None => return false,
+        };
+        tiles.sort();
+        self.tiles = tiles;
+        self.drawn = None;
+        self.opened[pon].category = OpenType::Kan;
+        self.opened[pon].kan = Some(KanType::Added {
+            melds: self.opened.len(),
+        });
+        if tile.is_red_dora() {
+            self.opened[pon].tiles[2] = tile;
+        }
+        return true;
+    }
+
+    /// ツモ牌を手牌に加えて並べる
+    fn merge_drawn(&mut self) {
+        if let Some(tile) = self.drawn.take() {
+            self.tiles.push(tile);
+        }
         self.tiles.sort();
     }
+
+    /// ツモってきた牌を差し替えた手牌を返す
+    pub fn with_drawn(&self, drawn: Tile) -> Hand {
+        return Hand::new_with_opened(self.tiles.clone(), self.opened.clone(), Some(drawn));
+    }
+
     /// 種類別に各牌の数をカウントする
     pub fn summarize_tiles(&self) -> TileSummarize {
-        let mut result: TileSummarize = [0; Tile::LEN as usize];
+        let mut result: TileSummarize = [0; Tile::LEN];
 
         // 通常の手牌をカウント
         for i in 0..self.tiles.len() {
@@ -51,6 +160,18 @@ impl Hand {
         return result;
     }
 
+    /// 手牌（副露・ツモ牌を含む）に含まれる赤ドラの数を返す
+    pub fn count_red_dora(&self) -> u32 {
+        let opened = self.opened.iter().flat_map(|open| open.tiles.iter());
+        return self
+            .tiles
+            .iter()
+            .chain(opened)
+            .chain(self.drawn.iter())
+            .filter(|tile| tile.is_red_dora())
+            .count() as u32;
+    }
+
     /// 絵文字として出力する
     pub fn to_emoji(&self) -> String {
         let mut result = String::new();
@@ -91,7 +212,7 @@ impl Hand {
             ));
             // カンなら4枚目を追加する
             if self.opened[i].category == OpenType::Kan {
-                result.push_str(&format!("{}", self.opened[i].tiles[0].to_string(),));
+                result.push_str(&self.opened[i].tiles[0].to_string());
             }
         }
 
@@ -103,10 +224,10 @@ impl Hand {
 
     /// `Vec<Tile>`から連続した牌の種類を圧縮した文字列を返す
     fn make_short_str(mut tiles: Vec<Tile>) -> String {
-        if tiles.len() == 0 {
+        if tiles.is_empty() {
             return String::from("");
         } else if tiles.len() == 1 {
-            return tiles[1].to_string();
+            return tiles[0].to_string();
         }
         tiles.sort();
         let mut result = String::new();
@@ -154,8 +275,7 @@ impl Hand {
     fn str_to_tiles(hand_str: &str) -> Vec<Tile> {
         let mut result: Vec<Tile> = Vec::new();
         let mut stack: VecDeque<char> = VecDeque::new();
-        let mut itr = hand_str.chars();
-        while let Some(c) = itr.next() {
+        for c in hand_str.chars() {
             if matches!(c, '1'..='9') {
                 stack.push_back(c);
             } else if matches!(c, 'm' | 'p' | 's' | 'z') {
@@ -178,37 +298,39 @@ impl Hand {
         let mut opened: Vec<OpenTiles> = Vec::new();
         let mut drawn: Option<Tile> = None;
 
-        while let Some(tile_str) = itr.next() {
+        for tile_str in itr {
             let tile_vec = Hand::str_to_tiles(tile_str);
             match tile_vec.len() {
                 1 => {
-                    let t = *tile_vec.get(0).unwrap();
+                    let t = *tile_vec.first().unwrap();
                     drawn = Some(t);
                 }
                 3 => {
                     opened.push(OpenTiles {
                         tiles: [
-                            *tile_vec.get(0).unwrap(),
+                            *tile_vec.first().unwrap(),
                             *tile_vec.get(1).unwrap(),
                             *tile_vec.get(2).unwrap(),
                         ],
-                        category: if *tile_vec.get(0).unwrap() == *tile_vec.get(1).unwrap() {
+                        category: if *tile_vec.first().unwrap() == *tile_vec.get(1).unwrap() {
                             OpenType::Pon
                         } else {
                             OpenType::Chi
                         },
                         from: OpenFrom::Unknown,
+                        kan: None,
                     });
                 }
                 4 => {
                     opened.push(OpenTiles {
                         tiles: [
-                            *tile_vec.get(0).unwrap(),
+                            *tile_vec.first().unwrap(),
                             *tile_vec.get(1).unwrap(),
                             *tile_vec.get(2).unwrap(),
                         ],
                         category: OpenType::Kan,
                         from: OpenFrom::Unknown,
+                        kan: Some(KanType::Open),
                     });
                 }
                 _ => {}
@@ -220,8 +342,8 @@ impl Hand {
     pub fn from_summarized(sum: &TileSummarize) -> Hand {
         let mut result: Vec<Tile> = Vec::new();
 
-        for i in Tile::M1 as usize..Tile::LEN {
-            for j in 0..sum[i] {
+        for (i, &count) in sum.iter().enumerate() {
+            for _ in 0..count {
                 result.push(Tile::new(i as TileType));
             }
         }
@@ -340,4 +462,30 @@ mod tests {
         assert_eq!(test.drawn, Some(Tile::new(Tile::Z5)));
         assert_eq!(test.to_short_string(), test_str);
     }
+
+    #[test]
+    /// ツモ・打牌・副露・加槓
+    fn mutation_test() {
+        let mut test = Hand::from("123m456p789s1122z");
+        test.draw(Tile::new(Tile::Z5));
+        assert!(!test.discard(Tile::new(Tile::Z7)));
+        assert!(test.discard(Tile::new(Tile::M1)));
+        assert_eq!(test.drawn, None);
+        assert_eq!(test.to_short_string(), "23m456p789s11225z");
+
+        let pon = OpenTiles {
+            tiles: [Tile::new(Tile::Z1); 3],
+            category: OpenType::Pon,
+            from: OpenFrom::Previous,
+            kan: None,
+        };
+        assert!(test.open(pon, &[Tile::new(Tile::Z1), Tile::new(Tile::Z1)]));
+        assert_eq!(test.tiles().len(), 11);
+        test.draw(Tile::new(Tile::Z1));
+        assert!(test.add_kan(Tile::new(Tile::Z1)));
+        assert_eq!(test.opened[0].category, OpenType::Kan);
+        assert_eq!(test.opened[0].kan, Some(KanType::Added { melds: 1 }));
+        assert_eq!(test.tiles().len(), 11);
+        assert!(!test.add_kan(Tile::new(Tile::Z2)));
+    }
 }
@@ -2,3 +2,19 @@ pub mod opened;
 pub mod hand_analyzer;
 pub mod winning_hand;
 pub mod block;
+pub mod furiten;
+pub mod incremental_analyzer;
+pub mod win_estimator;
+pub mod yaku_shanten;
+pub mod win_context;
+pub mod yaku;
+pub mod decomposition;
+pub mod fu;
+pub mod score;
+pub mod scorer;
+pub mod local_yaku;
+pub mod river;
+pub mod nagashi;
+pub mod exhaustive_draw;
+pub mod pao;
+pub mod report;
@@ -1,8 +1,8 @@
-use crate::tile::Tile;
-/*
+use crate::tile::*;
+
 /// ブロック（対子、順子、刻子）の振る舞いを定義する
-trait BlockProperty {
-    /// 么九牌が含まれているか
+pub trait BlockProperty {
+    /// 老頭牌が含まれているか
     fn has_1_or_9(&self) -> bool;
     /// 字牌が含まれているか
     fn has_honor(&self) -> bool;
@@ -12,14 +12,19 @@ trait BlockProperty {
     fn is_circle(&self) -> bool;
     /// 索子のブロックか
     fn is_bamboo(&self) -> bool;
+    /// 么九牌（老頭牌＋字牌）が含まれているか
+    fn has_1_9_honor(&self) -> bool {
+        return self.has_1_or_9() || self.has_honor();
+    }
 }
 
 /// 対子（同じ2枚）
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct Same2 {
     tiles: (Tile, Tile),
 }
 impl Same2 {
-    fn new(&mut self, tile1: Tile, tile2: Tile) -> Same2 {
+    pub fn new(tile1: Tile, tile2: Tile) -> Same2 {
         if tile1.get() != tile2.get() {
             panic!("Not same tiles in `Same2`!");
         }
@@ -27,6 +32,10 @@ impl Same2 {
             tiles: (tile1, tile2),
         };
     }
+    /// 牌の種類を返す
+    pub fn get(&self) -> TileType {
+        return self.tiles.0.get();
+    }
 }
 impl BlockProperty for Same2 {
     fn has_1_or_9(&self) -> bool {
@@ -48,19 +57,31 @@ impl BlockProperty for Same2 {
         return self.tiles.0.is_bamboo();
     }
 }
-/// 刻子（同じ3枚）
+/// 刻子（同じ3枚）。槓子も含む
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct Same3 {
     tiles: (Tile, Tile, Tile),
+    /// 暗刻（暗槓を含む）か。ロンで完成した刻子は明刻として扱う
+    pub concealed: bool,
+    /// 槓子か
+    pub kan: bool,
 }
 impl Same3 {
-    fn new(&mut self, tile1: Tile, tile2: Tile, tile3: Tile) -> Same3 {
+    /// 暗刻を作る
+    pub fn new(tile1: Tile, tile2: Tile, tile3: Tile) -> Same3 {
         if tile1.get() != tile2.get() || tile2.get() != tile3.get() {
             panic!("Not same tiles in `Same3`!");
         }
         return Same3 {
             tiles: (tile1, tile2, tile3),
+            concealed: true,
+            kan: false,
         };
     }
+    /// 牌の種類を返す
+    pub fn get(&self) -> TileType {
+        return self.tiles.0.get();
+    }
 }
 impl BlockProperty for Same3 {
     fn has_1_or_9(&self) -> bool {
@@ -83,18 +104,35 @@ impl BlockProperty for Same3 {
 }
 
 /// 順子（連続した3枚）
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct Sequential3 {
     tiles: (Tile, Tile, Tile),
+    /// 門前の順子（チーしていない）か
+    pub concealed: bool,
 }
 impl Sequential3 {
-    fn new(&mut self, tile1: Tile, tile2: Tile, tile3: Tile) -> Sequential3 {
-        if !tile1.is_sequential_to(tile2) || !tile2.is_sequential_to(tile3) {
-            panic!("Not same tiles in `Sequential3`!");
+    /// 門前の順子を作る
+    pub fn new(tile1: Tile, tile2: Tile, tile3: Tile) -> Sequential3 {
+        if tile1.get() + 1 != tile2.get()
+            || !tile1.is_sequential_to(tile2)
+            || tile2.get() + 1 != tile3.get()
+            || !tile2.is_sequential_to(tile3)
+        {
+            panic!("Not sequential tiles in `Sequential3`!");
         }
         return Sequential3 {
             tiles: (tile1, tile2, tile3),
+            concealed: true,
         };
     }
+    /// 最初（最も小さい）の牌の種類を返す
+    pub fn get(&self) -> TileType {
+        return self.tiles.0.get();
+    }
+    /// 指定した牌の種類を含むか
+    pub fn contains(&self, tile: TileType) -> bool {
+        return (self.get()..self.get() + 3).contains(&tile);
+    }
 }
 impl BlockProperty for Sequential3 {
     fn has_1_or_9(&self) -> bool {
@@ -115,4 +153,48 @@ impl BlockProperty for Sequential3 {
         return self.tiles.0.is_bamboo();
     }
 }
-*/
\ No newline at end of file
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 順子の属性
+    fn sequential3() {
+        let block = Sequential3::new(
+            Tile::new(Tile::S7),
+            Tile::new(Tile::S8),
+            Tile::new(Tile::S9),
+        );
+        assert!(block.has_1_or_9());
+        assert!(block.is_bamboo());
+        assert!(block.contains(Tile::S8));
+        assert!(!block.contains(Tile::S6));
+    }
+
+    #[test]
+    #[should_panic]
+    /// 色をまたぐ順子は作れない
+    fn sequential3_across_suits() {
+        Sequential3::new(
+            Tile::new(Tile::M8),
+            Tile::new(Tile::M9),
+            Tile::new(Tile::P1),
+        );
+    }
+
+    #[test]
+    /// 刻子の属性
+    fn same3() {
+        let block = Same3::new(
+            Tile::new(Tile::Z5),
+            Tile::new(Tile::Z5),
+            Tile::new(Tile::Z5),
+        );
+        assert!(block.has_honor());
+        assert!(block.has_1_9_honor());
+        assert!(block.concealed);
+        assert!(!block.kan);
+    }
+}
@@ -0,0 +1,388 @@
+use crate::hand::Hand;
+use crate::hand_info::block::*;
+use crate::hand_info::opened::*;
+use crate::hand_info::win_context::WinMethod;
+use crate::hand_info::winning_hand::WinningHandForm;
+use crate::rule::Ruleset;
+use crate::tile::*;
+
+/// 和了牌の待ちの形
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Wait {
+    /// 両面待ち
+    Ryanmen,
+    /// 嵌張待ち
+    Kanchan,
+    /// 辺張待ち
+    Penchan,
+    /// 双碰待ち
+    Shanpon,
+    /// 単騎待ち
+    Tanki,
+}
+
+/// 和了形の解釈（面子・雀頭への分け方と、和了牌の待ちの形）
+///
+/// 同じ和了形でも複数の解釈ができることがある。
+/// 例えば`111222333m`は3つの刻子とも3つの同じ順子とも読め、和了牌がどの面子に入るかで待ちの形も変わる。
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Decomposition {
+    /// 和了の形
+    pub form: WinningHandForm,
+    /// 対子（通常形・国士無双では雀頭1つ、七対子では7つ）
+    pub pairs: Vec<Same2>,
+    /// 刻子・槓子（副露を含む）
+    pub same3: Vec<Same3>,
+    /// 順子（副露を含む）
+    pub sequential3: Vec<Sequential3>,
+    /// 和了牌の待ちの形
+    pub wait: Wait,
+    /// 門前（暗槓以外の副露がない）か
+    pub closed: bool,
+    /// 手牌全体（副露・和了牌を含む）の集計。槓子は4枚として数える
+    pub tiles: TileSummarize,
+}
+
+impl Decomposition {
+    /// 和了した手牌の解釈を全て返す
+    ///
+    /// 和了牌`winning_tile`は手牌（ツモ牌を含む）に含まれている必要がある。
+    /// 和了形でなければ空を返す。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::decomposition::*;
+    /// use mahjong_rs::hand_info::win_context::*;
+    /// use mahjong_rs::tile::*;
+    ///
+    /// // 3つの暗刻とも、3つの同じ順子とも読める
+    /// let hand = Hand::from("111222333m456p9s 9s");
+    /// let result = Decomposition::enumerate(&hand, Tile::new(Tile::S9), WinMethod::Tsumo);
+    /// assert_eq!(result.len(), 2);
+    /// ```
+    pub fn enumerate(hand: &Hand, winning_tile: Tile, method: WinMethod) -> Vec<Decomposition> {
+        return Decomposition::enumerate_by_rule(hand, winning_tile, method, &Ruleset::default());
+    }
+
+    /// ルールに従って和了した手牌の解釈を全て返す
+    ///
+    /// `enumerate`と同じだが、七対子で同じ牌4枚を2つの対子として扱うかをルールに従う。
+    pub fn enumerate_by_rule(
+        hand: &Hand,
+        winning_tile: Tile,
+        method: WinMethod,
+        ruleset: &Ruleset,
+    ) -> Vec<Decomposition> {
+        let mut tiles = hand.summarize_tiles();
+        let mut concealed = tiles;
+        let mut opened_same3: Vec<Same3> = Vec::new();
+        let mut opened_sequential3: Vec<Sequential3> = Vec::new();
+        for open in hand.opened() {
+            for tile in open.tiles.iter() {
+                concealed[tile.get() as usize] -= 1;
+            }
+            match open.category {
+                OpenType::Chi => {
+                    let mut sorted = open.tiles;
+                    sorted.sort();
+                    let mut block = Sequential3::new(sorted[0], sorted[1], sorted[2]);
+                    block.concealed = false;
+                    opened_sequential3.push(block);
+                }
+                OpenType::Pon | OpenType::Kan => {
+                    let mut block = Same3::new(open.tiles[0], open.tiles[1], open.tiles[2]);
+                    block.kan = open.category == OpenType::Kan;
+                    block.concealed = block.kan && open.from == OpenFrom::Myself;
+                    if block.kan {
+                        tiles[block.get() as usize] += 1;
+                    }
+                    opened_same3.push(block);
+                }
+            }
+        }
+        let closed = hand.is_closed();
+        let w = winning_tile.get();
+        let mut result: Vec<Decomposition> = Vec::new();
+        if concealed[w as usize] == 0 {
+            return result;
+        }
+
+        // 国士無双・七対子
+        if hand.opened().is_empty() {
+            let orphans = is_thirteen_orphans(&concealed);
+            if orphans || is_seven_pairs(&concealed, ruleset.seven_pairs_four_identical) {
+                let pairs = (Tile::M1..=Tile::Z7)
+                    .flat_map(|i| vec![i; concealed[i as usize] as usize / 2])
+                    .map(|i| Same2::new(Tile::new(i), Tile::new(i)))
+                    .collect();
+                result.push(Decomposition {
+                    form: if orphans {
+                        WinningHandForm::ThirteenOrphens
+                    } else {
+                        WinningHandForm::SevenPairs
+                    },
+                    pairs,
+                    same3: Vec::new(),
+                    sequential3: Vec::new(),
+                    wait: Wait::Tanki,
+                    closed,
+                    tiles,
+                });
+            }
+        }
+
+        // 通常形
+        let sets = 4 - hand.opened().len();
+        for head in Tile::M1..=Tile::Z7 {
+            if concealed[head as usize] < 2 {
+                continue;
+            }
+            concealed[head as usize] -= 2;
+            let mut found: Vec<(Vec<Same3>, Vec<Sequential3>)> = Vec::new();
+            split_sets(
+                &mut concealed,
+                0,
+                &mut Vec::new(),
+                &mut Vec::new(),
+                &mut found,
+            );
+            concealed[head as usize] += 2;
+
+            for (same3, sequential3) in found {
+                if same3.len() + sequential3.len() != sets {
+                    continue;
+                }
+                let base = Decomposition {
+                    form: WinningHandForm::Normal,
+                    pairs: vec![Same2::new(Tile::new(head), Tile::new(head))],
+                    same3: [&same3[..], &opened_same3[..]].concat(),
+                    sequential3: [&sequential3[..], &opened_sequential3[..]].concat(),
+                    wait: Wait::Tanki,
+                    closed,
+                    tiles,
+                };
+                let mut readings: Vec<Decomposition> = Vec::new();
+                // 雀頭で待った
+                if head == w {
+                    readings.push(base.clone());
+                }
+                // 刻子で待った（ロンなら明刻になる）
+                for (i, block) in same3.iter().enumerate() {
+                    if block.get() == w {
+                        let mut reading = base.clone();
+                        reading.wait = Wait::Shanpon;
+                        reading.same3[i].concealed = method == WinMethod::Tsumo;
+                        readings.push(reading);
+                    }
+                }
+                // 順子で待った
+                for block in sequential3.iter() {
+                    if block.contains(w) {
+                        let mut reading = base.clone();
+                        reading.wait = sequential_wait(block, w);
+                        readings.push(reading);
+                    }
+                }
+                for reading in readings {
+                    if !result.contains(&reading) {
+                        result.push(reading);
+                    }
+                }
+            }
+        }
+        return result;
+    }
+
+    /// 手牌全体に含まれる牌の種類を昇順で返す
+    pub fn kinds(&self) -> Vec<TileType> {
+        return (Tile::M1..=Tile::Z7)
+            .filter(|&i| self.tiles[i as usize] > 0)
+            .collect();
+    }
+
+    /// 雀頭を返す（七対子では最初の対子）
+    pub fn head(&self) -> Same2 {
+        return self.pairs[0];
+    }
+}
+
+/// `idx`番目以降の牌を刻子・順子に分ける方法を全て探す
+fn split_sets(
+    t: &mut TileSummarize,
+    idx: usize,
+    same3: &mut Vec<Same3>,
+    sequential3: &mut Vec<Sequential3>,
+    found: &mut Vec<(Vec<Same3>, Vec<Sequential3>)>,
+) {
+    let mut i = idx;
+    while i < Tile::LEN && t[i] == 0 {
+        i += 1;
+    }
+    if i == Tile::LEN {
+        found.push((same3.clone(), sequential3.clone()));
+        return;
+    }
+    let tile = Tile::new(i as TileType);
+    if t[i] >= 3 {
+        t[i] -= 3;
+        same3.push(Same3::new(tile, tile, tile));
+        split_sets(t, i, same3, sequential3, found);
+        same3.pop();
+        t[i] += 3;
+    }
+    if i < Tile::Z1 as usize && i % 9 <= 6 && t[i + 1] > 0 && t[i + 2] > 0 {
+        t[i] -= 1;
+        t[i + 1] -= 1;
+        t[i + 2] -= 1;
+        sequential3.push(Sequential3::new(
+            tile,
+            Tile::new(i as TileType + 1),
+            Tile::new(i as TileType + 2),
+        ));
+        split_sets(t, i, same3, sequential3, found);
+        sequential3.pop();
+        t[i] += 1;
+        t[i + 1] += 1;
+        t[i + 2] += 1;
+    }
+}
+
+/// 順子のどの位置で和了したかから待ちの形を返す
+fn sequential_wait(block: &Sequential3, tile: TileType) -> Wait {
+    let first = block.get();
+    if tile == first + 1 {
+        return Wait::Kanchan;
+    }
+    if (tile == first && first % 9 == 6) || (tile == first + 2 && first % 9 == 0) {
+        return Wait::Penchan;
+    }
+    return Wait::Ryanmen;
+}
+
+/// 国士無双の形か否かを返す
+fn is_thirteen_orphans(t: &TileSummarize) -> bool {
+    let mut pair = false;
+    for i in Tile::M1..=Tile::Z7 {
+        let tile = Tile::new(i);
+        match (tile.is_1_9_honor(), t[i as usize]) {
+            (true, 1) => {}
+            (true, 2) if !pair => pair = true,
+            (false, 0) => {}
+            _ => return false,
+        }
+    }
+    return pair;
+}
+
+/// 七対子の形（異なる7種類の対子）か否かを返す
+///
+/// `four_identical`なら同じ牌4枚を2つの対子として扱う。
+fn is_seven_pairs(t: &TileSummarize, four_identical: bool) -> bool {
+    if four_identical {
+        return t.iter().all(|&c| c % 2 == 0) && t.iter().sum::<u32>() == 14;
+    }
+    return t.iter().all(|&c| c == 0 || c == 2) && t.iter().filter(|&&c| c == 2).count() == 7;
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 待ちの形を判定する
+    fn waits() {
+        let cases = [
+            ("123m456p789s11z23m 4m", Tile::M4, Wait::Ryanmen),
+            ("789m456p789s11z24m 3m", Tile::M3, Wait::Kanchan),
+            ("123m456p789s11z12m 3m", Tile::M3, Wait::Penchan),
+            ("123m456p789s11z99m 9m", Tile::M9, Wait::Shanpon),
+            ("123m456p789s111z9m 9m", Tile::M9, Wait::Tanki),
+        ];
+        for &(hand_str, tile, wait) in cases.iter() {
+            let hand = Hand::from(hand_str);
+            let result = Decomposition::enumerate(&hand, Tile::new(tile), WinMethod::Ron);
+            assert_eq!(result.len(), 1, "{}", hand_str);
+            assert_eq!(result[0].wait, wait, "{}", hand_str);
+        }
+    }
+
+    #[test]
+    /// 両面にも単騎にも読める
+    fn multiple_waits() {
+        let hand = Hand::from("4566m123456789p 6m");
+        let result = Decomposition::enumerate(&hand, Tile::new(Tile::M6), WinMethod::Tsumo);
+        let waits: Vec<Wait> = result.iter().map(|d| d.wait).collect();
+        assert_eq!(waits.len(), 2);
+        assert!(waits.contains(&Wait::Ryanmen));
+        assert!(waits.contains(&Wait::Tanki));
+    }
+
+    #[test]
+    /// ロンで完成した刻子は明刻になる
+    fn shanpon_by_ron() {
+        let hand = Hand::from("123m456p789s11z99m 9m");
+        let result = Decomposition::enumerate(&hand, Tile::new(Tile::M9), WinMethod::Ron);
+        assert!(!result[0].same3[0].concealed);
+        let result = Decomposition::enumerate(&hand, Tile::new(Tile::M9), WinMethod::Tsumo);
+        assert!(result[0].same3[0].concealed);
+    }
+
+    #[test]
+    /// 七対子と二盃口の両方に読める
+    fn seven_pairs_or_normal() {
+        let hand = Hand::from("112233m445566p7z 7z");
+        let result = Decomposition::enumerate(&hand, Tile::new(Tile::Z7), WinMethod::Tsumo);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].form, WinningHandForm::SevenPairs);
+        assert_eq!(result[1].form, WinningHandForm::Normal);
+        assert_eq!(result[1].sequential3.len(), 4);
+    }
+
+    #[test]
+    /// 副露と槓子
+    fn opened() {
+        let hand = Hand::from("123m99m55z 456p 7777s 5z");
+        let result = Decomposition::enumerate(&hand, Tile::new(Tile::Z5), WinMethod::Ron);
+        assert_eq!(result.len(), 1);
+        assert!(!result[0].closed);
+        assert_eq!(result[0].tiles[Tile::S7 as usize], 4);
+        assert!(result[0].same3.iter().any(|b| b.kan && b.get() == Tile::S7));
+        assert!(result[0]
+            .sequential3
+            .iter()
+            .any(|b| !b.concealed && b.get() == Tile::P4));
+    }
+
+    #[test]
+    /// 国士無双
+    fn thirteen_orphans() {
+        let hand = Hand::from("19m19p19s1234567z 1m");
+        let result = Decomposition::enumerate(&hand, Tile::new(Tile::M1), WinMethod::Tsumo);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].form, WinningHandForm::ThirteenOrphens);
+        assert_eq!(result[0].head().get(), Tile::M1);
+    }
+
+    #[test]
+    /// 和了形でなければ空
+    fn not_winning() {
+        let hand = Hand::from("123m456p789s123z 4z");
+        assert!(Decomposition::enumerate(&hand, Tile::new(Tile::Z3), WinMethod::Tsumo).is_empty());
+    }
+
+    #[test]
+    /// 同じ牌4枚を2つの対子とする七対子はルールによる
+    fn seven_pairs_with_four_identical() {
+        let hand = Hand::from("1111m2233p4455s6z 6z");
+        let tile = Tile::new(Tile::Z6);
+        assert!(Decomposition::enumerate(&hand, tile, WinMethod::Ron).is_empty());
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.seven_pairs_four_identical = true;
+        let result = Decomposition::enumerate_by_rule(&hand, tile, WinMethod::Ron, &ruleset);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].form, WinningHandForm::SevenPairs);
+        assert_eq!(result[0].pairs.len(), 7);
+    }
+}
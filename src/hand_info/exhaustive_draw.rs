@@ -0,0 +1,214 @@
+use crate::hand::Hand;
+use crate::hand_info::hand_analyzer::{AnalyzerInput, HandAnalyzer};
+use crate::hand_info::nagashi::*;
+use crate::hand_info::opened::OpenType;
+use crate::hand_info::river::River;
+use crate::rule::*;
+
+/// ノーテン罰符の総額
+pub const NOTEN_PENALTY: i32 = 3000;
+
+/// 荒牌平局の精算
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExhaustiveDrawSettlement {
+    /// 流し満貫の支払い。成立していればノーテン罰符の代わりに支払う
+    pub nagashi: Vec<NagashiPayment>,
+    /// 聴牌と認められた（聴牌を宣言して手牌を公開した）か
+    pub tenpai: [bool; 4],
+    /// 各席の点数の増減
+    pub deltas: [i32; 4],
+    /// 親が連荘するか
+    pub dealer_keeps_seat: bool,
+}
+
+/// 空聴（自分の手牌で4枚とも使っている牌だけを待つ聴牌）か否かを返す
+///
+/// 聴牌していなければ`false`を返す。槓子は4枚として数える。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand::*;
+/// use mahjong_rs::hand_info::exhaustive_draw::*;
+///
+/// // 1mを4枚使っている1m単騎
+/// assert!(is_karaten(&Hand::from("1111m234p567p789s")));
+/// assert!(!is_karaten(&Hand::from("1112m234p567p789s")));
+/// ```
+pub fn is_karaten(hand: &Hand) -> bool {
+    let waits = HandAnalyzer::calc_waiting_tiles(hand);
+    let mut held = hand.summarize_without_drawn();
+    for open in hand.opened().iter() {
+        if open.category == OpenType::Kan {
+            held[open.tiles[0].get() as usize] += 1;
+        }
+    }
+    return !waits.is_empty() && waits.iter().all(|w| held[w.get() as usize] >= 4);
+}
+
+/// 荒牌平局で聴牌と認められる手牌か否かを返す
+///
+/// 空聴は`Ruleset::karaten`に従う。
+pub fn is_tenpai(hand: &Hand, ruleset: &Ruleset) -> bool {
+    if HandAnalyzer::calc_with_rule(hand, ruleset).shanten != 0 {
+        return false;
+    }
+    return ruleset.karaten == Karaten::Tenpai || !is_karaten(hand);
+}
+
+/// 荒牌平局を精算する
+///
+/// 席は手番の順に`0`〜`3`で、`dealer`は親の席。`declared`は聴牌を宣言した（手牌を公開した）か。
+/// 聴牌していない手牌での宣言は認めない。
+/// 流し満貫が成立していればその支払いだけを行い、成立していなければ聴牌者がノーテン者から3000点を分け合う。
+/// 親は聴牌していれば（`Ruleset::tenpai_renchan`のとき）連荘する。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand::*;
+/// use mahjong_rs::hand_info::exhaustive_draw::*;
+/// use mahjong_rs::hand_info::river::*;
+/// use mahjong_rs::rule::*;
+/// use mahjong_rs::tile::*;
+///
+/// let hands = [
+///     Hand::from("123m456p789s1122z"),
+///     Hand::from("1357m2468p1357s1z"),
+///     Hand::from("1357m2468p1357s1z"),
+///     Hand::from("1357m2468p1357s1z"),
+/// ];
+/// let mut rivers: [River; 4] = Default::default();
+/// for river in rivers.iter_mut() {
+///     river.discard(Tile::new(Tile::M5));
+/// }
+/// let result = settle_exhaustive_draw(&hands, &rivers, [true; 4], 0, &Ruleset::default());
+/// assert_eq!(result.tenpai, [true, false, false, false]);
+/// assert_eq!(result.deltas, [3000, -1000, -1000, -1000]);
+/// assert!(result.dealer_keeps_seat);
+/// ```
+pub fn settle_exhaustive_draw(
+    hands: &[Hand; 4],
+    rivers: &[River; 4],
+    declared: [bool; 4],
+    dealer: usize,
+    ruleset: &Ruleset,
+) -> ExhaustiveDrawSettlement {
+    let mut tenpai = [false; 4];
+    for (seat, t) in tenpai.iter_mut().enumerate() {
+        *t = declared[seat] && is_tenpai(&hands[seat], ruleset);
+    }
+    let nagashi = settle_nagashi_mangan(rivers, dealer, true, ruleset);
+    let mut deltas = [0; 4];
+    if !nagashi.is_empty() {
+        for payment in nagashi.iter() {
+            for (d, p) in deltas.iter_mut().zip(payment.deltas(dealer).iter()) {
+                *d += p;
+            }
+        }
+    } else {
+        let count = tenpai.iter().filter(|&&t| t).count() as i32;
+        if 0 < count && count < 4 {
+            for (d, &t) in deltas.iter_mut().zip(tenpai.iter()) {
+                *d = if t {
+                    NOTEN_PENALTY / count
+                } else {
+                    -NOTEN_PENALTY / (4 - count)
+                };
+            }
+        }
+    }
+    return ExhaustiveDrawSettlement {
+        nagashi,
+        tenpai,
+        deltas,
+        dealer_keeps_seat: ruleset.tenpai_renchan && tenpai[dealer],
+    };
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::*;
+
+    fn noten() -> Hand {
+        return Hand::from("1357m2468p1357s1z");
+    }
+
+    fn rivers() -> [River; 4] {
+        let mut rivers: [River; 4] = Default::default();
+        for river in rivers.iter_mut() {
+            river.discard(Tile::new(Tile::M5));
+        }
+        return rivers;
+    }
+
+    #[test]
+    /// 聴牌者の人数に応じて3000点を分け合う
+    fn noten_payments() {
+        let tenpai = Hand::from("123m456p789s1122z");
+        let ruleset = Ruleset::tenhou();
+        let hands = [tenpai.clone(), tenpai.clone(), noten(), noten()];
+        let result = settle_exhaustive_draw(&hands, &rivers(), [true; 4], 2, &ruleset);
+        assert_eq!(result.deltas, [1500, 1500, -1500, -1500]);
+        assert!(!result.dealer_keeps_seat);
+
+        let hands = [tenpai.clone(), tenpai.clone(), tenpai.clone(), noten()];
+        let result = settle_exhaustive_draw(&hands, &rivers(), [true; 4], 2, &ruleset);
+        assert_eq!(result.deltas, [1000, 1000, 1000, -3000]);
+        assert!(result.dealer_keeps_seat);
+        let mut no_renchan = Ruleset::tenhou();
+        no_renchan.tenpai_renchan = false;
+        let result = settle_exhaustive_draw(&hands, &rivers(), [true; 4], 2, &no_renchan);
+        assert!(!result.dealer_keeps_seat);
+
+        let hands = [tenpai.clone(), tenpai.clone(), tenpai.clone(), tenpai];
+        let result = settle_exhaustive_draw(&hands, &rivers(), [true; 4], 2, &ruleset);
+        assert_eq!(result.deltas, [0; 4]);
+
+        let hands = [noten(), noten(), noten(), noten()];
+        let result = settle_exhaustive_draw(&hands, &rivers(), [true; 4], 2, &ruleset);
+        assert_eq!(result.deltas, [0; 4]);
+    }
+
+    #[test]
+    /// 聴牌を宣言しなければノーテンとして扱う
+    fn undeclared_tenpai() {
+        let tenpai = Hand::from("123m456p789s1122z");
+        let hands = [tenpai.clone(), tenpai, noten(), noten()];
+        let declared = [false, true, true, true];
+        let result = settle_exhaustive_draw(&hands, &rivers(), declared, 0, &Ruleset::wrc());
+        assert_eq!(result.tenpai, [false, true, false, false]);
+        assert_eq!(result.deltas, [-1000, 3000, -1000, -1000]);
+        assert!(!result.dealer_keeps_seat);
+    }
+
+    #[test]
+    /// 空聴の扱いはルールに従う
+    fn karaten() {
+        let hand = Hand::from("1111m234p567p789s");
+        let hands = [hand.clone(), noten(), noten(), noten()];
+        let mut ruleset = Ruleset::tenhou();
+        assert!(!is_tenpai(&hand, &ruleset));
+        let result = settle_exhaustive_draw(&hands, &rivers(), [true; 4], 0, &ruleset);
+        assert_eq!(result.deltas, [0; 4]);
+
+        ruleset.karaten = Karaten::Tenpai;
+        assert!(is_tenpai(&hand, &ruleset));
+        let result = settle_exhaustive_draw(&hands, &rivers(), [true; 4], 0, &ruleset);
+        assert_eq!(result.deltas, [3000, -1000, -1000, -1000]);
+    }
+
+    #[test]
+    /// 流し満貫が成立すればノーテン罰符は支払わない
+    fn nagashi_first() {
+        let mut rivers = rivers();
+        rivers[1] = River::new();
+        rivers[1].discard(Tile::new(Tile::Z1));
+        let tenpai = Hand::from("123m456p789s1122z");
+        let hands = [tenpai, noten(), noten(), noten()];
+        let result = settle_exhaustive_draw(&hands, &rivers, [true; 4], 0, &Ruleset::tenhou());
+        assert_eq!(result.nagashi.len(), 1);
+        assert_eq!(result.deltas, [-4000, 8000, -2000, -2000]);
+        assert!(result.dealer_keeps_seat);
+    }
+}
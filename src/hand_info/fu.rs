@@ -0,0 +1,245 @@
+use crate::hand_info::block::*;
+use crate::hand_info::decomposition::*;
+use crate::hand_info::win_context::*;
+use crate::hand_info::winning_hand::WinningHandForm;
+use crate::locale::*;
+use crate::tile::*;
+
+/// 符の内訳の項目
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FuItem {
+    /// 副底（20符）
+    Base,
+    /// 門前ロン（10符）
+    ClosedRon,
+    /// ツモ（2符）
+    Tsumo,
+    /// 刻子・槓子
+    Triplet {
+        /// 牌の種類
+        tile: TileType,
+        /// 暗刻・暗槓か
+        concealed: bool,
+        /// 槓子か
+        kan: bool,
+    },
+    /// 役牌の雀頭
+    HonorPair(TileType),
+    /// 待ち（嵌張・辺張・単騎は2符）
+    Wait(Wait),
+    /// 副露した平和形（符がつかなければ30符とする）
+    OpenNoPoints,
+    /// 七対子（25符固定）
+    SevenPairs,
+}
+
+impl FuItem {
+    /// 指定した言語での名前を返す
+    pub fn name(&self, locale: Locale) -> &'static str {
+        return fu_item_name(*self, locale);
+    }
+}
+
+/// 符の計算結果
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Fu {
+    /// 切り上げた符
+    pub value: u32,
+    /// 切り上げる前の符（内訳の合計）
+    pub raw: u32,
+    /// 内訳
+    pub items: Vec<(FuItem, u32)>,
+}
+
+impl Fu {
+    /// 連風牌（自風と場風が同じ）の雀頭の既定の符
+    pub const DOUBLE_WIND_PAIR_FU: u32 = 4;
+
+    /// 和了形の解釈と和了の状況から符を計算する
+    ///
+    /// `double_wind_pair_fu`は連風牌の雀頭の符（2符か4符かはルールによる）。
+    /// 七対子は25符固定、副露した平和形は30符固定とし、それ以外は10符単位に切り上げる。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::decomposition::*;
+    /// use mahjong_rs::hand_info::fu::*;
+    /// use mahjong_rs::hand_info::win_context::*;
+    /// use mahjong_rs::tile::*;
+    ///
+    /// // 門前ロン・中の暗刻・嵌張待ち
+    /// let hand = Hand::from("777z456p789s11m13m 2m");
+    /// let context = WinContext::new(Tile::new(Tile::M2), WinMethod::Ron, Wind::South, Wind::East);
+    /// let d = &Decomposition::enumerate(&hand, context.winning_tile, context.method)[0];
+    /// let fu = Fu::calc(d, &context, Fu::DOUBLE_WIND_PAIR_FU);
+    /// assert_eq!(fu.raw, 40);
+    /// assert_eq!(fu.value, 40);
+    /// ```
+    pub fn calc(d: &Decomposition, context: &WinContext, double_wind_pair_fu: u32) -> Fu {
+        if d.form == WinningHandForm::SevenPairs {
+            return Fu::from_items(vec![(FuItem::SevenPairs, 25)]);
+        }
+
+        let mut items = vec![(FuItem::Base, 20)];
+        // 面子・雀頭・待ちの符
+        let mut blocks: Vec<(FuItem, u32)> = Vec::new();
+        for block in d.same3.iter() {
+            let mut value = if block.has_1_9_honor() { 4 } else { 2 };
+            if block.concealed {
+                value *= 2;
+            }
+            if block.kan {
+                value *= 4;
+            }
+            blocks.push((
+                FuItem::Triplet {
+                    tile: block.get(),
+                    concealed: block.concealed,
+                    kan: block.kan,
+                },
+                value,
+            ));
+        }
+        if d.form == WinningHandForm::Normal {
+            let head = d.head().get();
+            let pair = pair_fu(head, context, double_wind_pair_fu);
+            if pair > 0 {
+                blocks.push((FuItem::HonorPair(head), pair));
+            }
+            if matches!(d.wait, Wait::Kanchan | Wait::Penchan | Wait::Tanki) {
+                blocks.push((FuItem::Wait(d.wait), 2));
+            }
+        }
+        let no_points = blocks.is_empty() && d.wait == Wait::Ryanmen;
+        items.append(&mut blocks);
+
+        match context.method {
+            WinMethod::Ron if d.closed => items.push((FuItem::ClosedRon, 10)),
+            // 門前の平和ツモはツモの符をつけない
+            WinMethod::Tsumo if !(d.closed && no_points) => items.push((FuItem::Tsumo, 2)),
+            _ => {}
+        }
+        if !d.closed && items.len() == 1 {
+            items.push((FuItem::OpenNoPoints, 10));
+        }
+        return Fu::from_items(items);
+    }
+
+    /// 内訳から合計と切り上げた符を求める
+    fn from_items(items: Vec<(FuItem, u32)>) -> Fu {
+        let raw: u32 = items.iter().map(|&(_, value)| value).sum();
+        let value = if items.iter().any(|&(item, _)| item == FuItem::SevenPairs) {
+            raw
+        } else {
+            raw.div_ceil(10) * 10
+        };
+        return Fu { value, raw, items };
+    }
+}
+
+/// 雀頭の符を返す
+///
+/// 三元牌・自風牌・場風牌は2符、連風牌は`double_wind_pair_fu`。
+fn pair_fu(tile: TileType, context: &WinContext, double_wind_pair_fu: u32) -> u32 {
+    if matches!(tile, Tile::Z5..=Tile::Z7) {
+        return 2;
+    }
+    let seat = tile == context.seat_wind as TileType;
+    let round = tile == context.round_wind as TileType;
+    return match (seat, round) {
+        (true, true) => double_wind_pair_fu,
+        (true, false) | (false, true) => 2,
+        _ => 0,
+    };
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand::Hand;
+
+    /// 最初の解釈の符を返す
+    fn fu_of(hand_str: &str, tile: TileType, method: WinMethod, seat: Wind) -> Fu {
+        let hand = Hand::from(hand_str);
+        let context = WinContext::new(Tile::new(tile), method, seat, Wind::East);
+        let d = &Decomposition::enumerate(&hand, context.winning_tile, context.method)[0];
+        return Fu::calc(d, &context, Fu::DOUBLE_WIND_PAIR_FU);
+    }
+
+    #[test]
+    /// 平和ツモは20符、平和ロンは30符
+    fn no_points_hand() {
+        let tsumo = fu_of(
+            "123m456p789s55p23m 4m",
+            Tile::M4,
+            WinMethod::Tsumo,
+            Wind::South,
+        );
+        assert_eq!(tsumo.value, 20);
+        assert_eq!(tsumo.items, vec![(FuItem::Base, 20)]);
+        let ron = fu_of(
+            "123m456p789s55p23m 4m",
+            Tile::M4,
+            WinMethod::Ron,
+            Wind::South,
+        );
+        assert_eq!(ron.value, 30);
+    }
+
+    #[test]
+    /// 副露した平和形は30符
+    fn open_no_points() {
+        let fu = fu_of(
+            "456p789s55p23m 123m 4m",
+            Tile::M4,
+            WinMethod::Ron,
+            Wind::South,
+        );
+        assert_eq!(fu.raw, 30);
+        assert_eq!(fu.value, 30);
+        assert_eq!(fu.items[1], (FuItem::OpenNoPoints, 10));
+    }
+
+    #[test]
+    /// 七対子は25符
+    fn seven_pairs() {
+        let fu = fu_of(
+            "1122m3344p5566s1z 1z",
+            Tile::Z1,
+            WinMethod::Tsumo,
+            Wind::South,
+        );
+        assert_eq!(fu.value, 25);
+    }
+
+    #[test]
+    /// 刻子・槓子の符
+    fn triplets() {
+        // 明槓の中（16符）・明刻の二萬（2符）・ツモ（2符）・単騎（2符）
+        let fu = fu_of(
+            "456p789s9s 222m 7777z 9s",
+            Tile::S9,
+            WinMethod::Tsumo,
+            Wind::South,
+        );
+        assert_eq!(fu.raw, 42);
+        assert_eq!(fu.value, 50);
+    }
+
+    #[test]
+    /// 連風牌の雀頭
+    fn double_wind_pair() {
+        // 東家が東の雀頭でロン（門前ロン10符・雀頭4符・単騎2符）
+        let fu = fu_of(
+            "123m456p789s789m1z 1z",
+            Tile::Z1,
+            WinMethod::Ron,
+            Wind::East,
+        );
+        assert_eq!(fu.raw, 36);
+        assert_eq!(fu.value, 40);
+        assert!(fu.items.contains(&(FuItem::HonorPair(Tile::Z1), 4)));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::hand::Hand;
+use crate::hand_info::hand_analyzer::HandAnalyzer;
+use crate::tile::*;
+
+/// 振聴の判定に使う、見逃した牌の記録
+#[derive(Debug, Clone, Default)]
+pub struct MissedTiles {
+    /// 自分の最後の打牌以降に見逃した（他家が捨てた・加槓した）牌
+    pub since_last_discard: Vec<Tile>,
+    /// 立直宣言以降に見逃した牌。立直していなければ`None`
+    pub since_riichi: Option<Vec<Tile>>,
+}
+
+/// 振聴の状態
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Furiten {
+    /// 捨て牌による振聴（自分の捨て牌に待ち牌が含まれている）
+    pub discard: bool,
+    /// 同巡内振聴（最後の打牌以降に待ち牌を見逃した）
+    pub temporary: bool,
+    /// 立直後振聴（立直宣言以降に待ち牌を見逃した）
+    pub riichi: bool,
+}
+
+impl Furiten {
+    /// 聴牌している手牌の振聴状態を判定する
+    ///
+    /// 待ち牌は`HandAnalyzer::calc_waiting_tiles`で求め、
+    /// 自分の捨て牌と見逃した牌に待ち牌が含まれるかどうかを調べる。
+    /// 聴牌していなければ待ち牌がないため、どの振聴にもならない。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::furiten::*;
+    /// use mahjong_rs::tile::*;
+    ///
+    /// // 一萬・四萬待ちで四萬を捨てている
+    /// let hand = Hand::from("23m456p789s11122z");
+    /// let discards = vec![Tile::new(Tile::M4)];
+    /// let furiten = Furiten::check(&hand, &discards, &MissedTiles::default());
+    /// assert!(furiten.discard);
+    /// assert!(furiten.is_furiten());
+    /// ```
+    pub fn check(hand: &Hand, discards: &[Tile], missed: &MissedTiles) -> Furiten {
+        let waiting = HandAnalyzer::calc_waiting_tiles(hand);
+        let is_waiting = |tile: &Tile| waiting.iter().any(|w| w.get() == tile.get());
+
+        return Furiten {
+            discard: discards.iter().any(is_waiting),
+            temporary: missed.since_last_discard.iter().any(is_waiting),
+            riichi: match &missed.since_riichi {
+                Some(tiles) => tiles.iter().any(is_waiting),
+                None => false,
+            },
+        };
+    }
+
+    /// いずれかの振聴状態にあるか（ロン和了できないか）を返す
+    pub fn is_furiten(&self) -> bool {
+        return self.discard || self.temporary || self.riichi;
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 振聴していない
+    fn not_furiten() {
+        let test = Hand::from("23m456p789s11122z");
+        let discards = vec![Tile::new(Tile::M5), Tile::new(Tile::Z3)];
+        let missed = MissedTiles {
+            since_last_discard: vec![Tile::new(Tile::M6)],
+            since_riichi: Some(vec![Tile::new(Tile::M7)]),
+        };
+        assert_eq!(
+            Furiten::check(&test, &discards, &missed),
+            Furiten::default()
+        );
+    }
+
+    #[test]
+    /// 多面張のうち1つでも捨てていれば捨て牌による振聴
+    fn discard_furiten_with_one_of_multiple_waits() {
+        let test = Hand::from("3456788m123p456s");
+        let discards = vec![Tile::new(Tile::Z1), Tile::new(Tile::M8)];
+        let result = Furiten::check(&test, &discards, &MissedTiles::default());
+        assert!(result.discard);
+        assert!(!result.temporary);
+        assert!(!result.riichi);
+    }
+
+    #[test]
+    /// 最後の打牌以降に待ち牌を見逃すと同巡内振聴
+    fn temporary_furiten() {
+        let test = Hand::from("23m456p789s11122z");
+        let missed = MissedTiles {
+            since_last_discard: vec![Tile::new(Tile::M1)],
+            since_riichi: None,
+        };
+        let result = Furiten::check(&test, &[], &missed);
+        assert!(!result.discard);
+        assert!(result.temporary);
+        assert!(!result.riichi);
+        assert!(result.is_furiten());
+    }
+
+    #[test]
+    /// 立直後に待ち牌を見逃すと立直後振聴
+    fn riichi_furiten() {
+        let test = Hand::from("23m456p789s11122z");
+        let missed = MissedTiles {
+            since_last_discard: Vec::new(),
+            since_riichi: Some(vec![Tile::new(Tile::Z5), Tile::new(Tile::M4)]),
+        };
+        let result = Furiten::check(&test, &[], &missed);
+        assert!(!result.temporary);
+        assert!(result.riichi);
+    }
+
+    #[test]
+    /// 聴牌していなければ振聴にならない
+    fn no_furiten_without_ready_hand() {
+        let test = Hand::from("25m456p789s11123z");
+        let discards = vec![Tile::new(Tile::M1), Tile::new(Tile::M4)];
+        assert!(!Furiten::check(&test, &discards, &MissedTiles::default()).is_furiten());
+    }
+}
@@ -1,35 +1,71 @@
 use std::cmp::*;
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
 use crate::hand::Hand;
 use crate::hand_info::winning_hand::WinningHandForm;
+use crate::rule::Ruleset;
 use crate::tile::*;
 
 /// 向聴数などの手牌に関する情報を計算する
-#[derive(Debug, Eq)]
+///
+/// 向聴数だけを比べるときは`cmp_shanten`・`is_same_shanten`を使う。
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct HandAnalyzer {
     /// 向聴数：あと牌を何枚交換すれば聴牌できるかの最小数。聴牌状態が`0`、和了が`-1`。
     pub shanten: i32,
     pub form: WinningHandForm,
 }
-impl Ord for HandAnalyzer {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.shanten.cmp(&other.shanten)
-    }
-}
 
-impl PartialOrd for HandAnalyzer {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+/// 和了形ごとの向聴数と、通常形での手牌の内訳
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AnalysisReport {
+    /// 七対子・国士無双・通常形それぞれの向聴数（この順に並ぶ）
+    pub by_form: Vec<HandAnalyzer>,
+    /// 最小の向聴数
+    pub shanten: i32,
+    /// 最小の向聴数になる和了形（同じ向聴数のものは全て含む）
+    pub min_forms: Vec<WinningHandForm>,
+    /// 通常形で最小の向聴数になる分け方での面子の数
+    pub complete_blocks: u32,
+    /// 通常形で最小の向聴数になる分け方での搭子（両面・辺張・嵌張）の数
+    ///
+    /// 向聴数と同じく、面子と搭子（雀頭以外の対子を含む）は合わせて4つまでしか数えない。
+    pub partial_blocks: u32,
+    /// 通常形で最小の向聴数になる分け方での対子（雀頭候補）の数
+    pub pair_candidates: u32,
+    /// 通常形で最小の向聴数になる分け方で、どのブロックにも使われない牌
+    ///
+    /// 4つを超えて数えなかった搭子の牌も含む。
+    pub isolated: Vec<Tile>,
 }
 
-impl PartialEq for HandAnalyzer {
-    fn eq(&self, other: &Self) -> bool {
-        self.shanten == other.shanten
+impl AnalysisReport {
+    /// 和了形を指定して向聴数を返す
+    pub fn shanten_of(&self, form: WinningHandForm) -> i32 {
+        return self
+            .by_form
+            .iter()
+            .find(|a| a.form == form)
+            .unwrap()
+            .shanten;
     }
 }
 
 impl HandAnalyzer {
+    /// 向聴数だけを比べる
+    ///
+    /// 和了形は比べないため、和了形が異なっていても向聴数が同じならば`Ordering::Equal`になる。
+    pub fn cmp_shanten(&self, other: &HandAnalyzer) -> Ordering {
+        return self.shanten.cmp(&other.shanten);
+    }
+
+    /// 向聴数が同じか否かを返す（和了形は比べない）
+    pub fn is_same_shanten(&self, other: &HandAnalyzer) -> bool {
+        return self.shanten == other.shanten;
+    }
+
     /// 向聴数を計算する
     ///
     /// 七対子・国士無双・通常の3つの和了形に対してそれぞれ向聴数を求め、最小のものを返す。
@@ -54,10 +90,112 @@ impl HandAnalyzer {
     /// );
     /// ```
     pub fn calc(hand: &Hand) -> HandAnalyzer {
-        let sp = HandAnalyzer::calc_by_form(hand, WinningHandForm::SevenPairs);
-        let to = HandAnalyzer::calc_by_form(hand, WinningHandForm::ThirteenOrphens);
-        let normal = HandAnalyzer::calc_by_form(hand, WinningHandForm::Normal);
-        return min(min(sp, to), normal);
+        return HandAnalyzer::calc_summarized(&hand.summarize_tiles());
+    }
+
+    /// ルールに従って向聴数を計算する
+    ///
+    /// `calc`と同じだが、七対子で同じ牌4枚を2つの対子として扱うかをルールに従う。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::hand_analyzer::*;
+    /// use mahjong_rs::rule::*;
+    ///
+    /// let test = Hand::from("1111m2233p4455s6z 6z");
+    /// // 同じ牌4枚を2つの対子として扱わなければ一向聴
+    /// assert_eq!(HandAnalyzer::calc(&test).shanten, 1);
+    /// let mut ruleset = Ruleset::tenhou();
+    /// ruleset.seven_pairs_four_identical = true;
+    /// assert_eq!(HandAnalyzer::calc_with_rule(&test, &ruleset).shanten, -1);
+    /// ```
+    pub fn calc_with_rule(hand: &Hand, ruleset: &Ruleset) -> HandAnalyzer {
+        let t = hand.summarize_tiles();
+        let mut result = HandAnalyzer::calc_summarized(&t);
+        let seven_pairs =
+            HandAnalyzer::calc_seven_pairs_by_rule(&t, ruleset.seven_pairs_four_identical);
+        if seven_pairs < result.shanten {
+            result = HandAnalyzer {
+                shanten: seven_pairs,
+                form: WinningHandForm::SevenPairs,
+            };
+        }
+        return result;
+    }
+
+    /// 和了形ごとの向聴数と手牌の内訳を計算する
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::hand_analyzer::*;
+    /// use mahjong_rs::hand_info::winning_hand::*;
+    ///
+    /// // 七対子なら一向聴、通常形なら二向聴
+    /// let test = Hand::from("112233m4455p9s1z 2z");
+    /// let report = HandAnalyzer::calc_report(&test);
+    /// assert_eq!(report.shanten_of(WinningHandForm::SevenPairs), 1);
+    /// assert_eq!(report.shanten_of(WinningHandForm::Normal), 2);
+    /// assert_eq!(report.min_forms, vec![WinningHandForm::SevenPairs]);
+    /// ```
+    pub fn calc_report(hand: &Hand) -> AnalysisReport {
+        let t = hand.summarize_tiles();
+        let mut buf = SearchBuffer::new();
+        let (normal_shanten, split) = HandAnalyzer::calc_normal_split(&t, &mut buf);
+        let by_form = vec![
+            HandAnalyzer::calc_summarized_by_form(&t, WinningHandForm::SevenPairs, &mut buf),
+            HandAnalyzer::calc_summarized_by_form(&t, WinningHandForm::ThirteenOrphens, &mut buf),
+            HandAnalyzer {
+                shanten: normal_shanten,
+                form: WinningHandForm::Normal,
+            },
+        ];
+        let shanten = by_form.iter().map(|a| a.shanten).min().unwrap();
+        let min_forms = by_form
+            .iter()
+            .filter(|a| a.shanten == shanten)
+            .map(|a| a.form)
+            .collect();
+
+        let mut rest = t;
+        let complete_blocks = split.block3.len() as u32;
+        for block in split.block3.iter() {
+            for &i in block {
+                rest[i as usize] -= 1;
+            }
+        }
+        let (pairs, sequentials): (Vec<[TileType; 2]>, Vec<[TileType; 2]>) =
+            split.block2.iter().partition(|block| block[0] == block[1]);
+        let pair_candidates = pairs.len() as u32;
+        // 雀頭以外の対子と搭子は、面子と合わせて4つまでしか数えない
+        let head = min(pair_candidates, 1);
+        let limit = (4 - min(complete_blocks, 4)).saturating_sub(pair_candidates - head);
+        let partial_blocks = min(sequentials.len() as u32, limit);
+        for block in pairs
+            .iter()
+            .chain(sequentials.iter().take(partial_blocks as usize))
+        {
+            for &i in block {
+                rest[i as usize] -= 1;
+            }
+        }
+        let mut isolated: Vec<Tile> = Vec::new();
+        for i in Tile::M1..=Tile::Z7 {
+            for _ in 0..rest[i as usize] {
+                isolated.push(Tile::new(i));
+            }
+        }
+
+        return AnalysisReport {
+            by_form,
+            shanten,
+            min_forms,
+            complete_blocks,
+            partial_blocks,
+            pair_candidates,
+            isolated,
+        };
     }
 
     /// 和了形を指定して向聴数を計算する
@@ -93,42 +231,225 @@ impl HandAnalyzer {
     /// );
     /// ```
     pub fn calc_by_form(hand: &Hand, form: WinningHandForm) -> HandAnalyzer {
+        return HandAnalyzer::calc_summarized_by_form(
+            &hand.summarize_tiles(),
+            form,
+            &mut SearchBuffer::new(),
+        );
+    }
+
+    /// 種類別に集計した牌から向聴数を計算する
+    ///
+    /// `calc`と同じく、3つの和了形のうち最小の向聴数を返す。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::hand_analyzer::*;
+    ///
+    /// let test = Hand::from("123m456p789s1122z");
+    /// assert_eq!(
+    ///   HandAnalyzer::calc_summarized(&test.summarize_tiles()).shanten,
+    ///   0
+    /// );
+    /// ```
+    pub fn calc_summarized(t: &TileSummarize) -> HandAnalyzer {
+        return HandAnalyzer::calc_summarized_with(t, &mut SearchBuffer::new());
+    }
+
+    /// 作業領域を使い回して、3つの和了形のうち最小の向聴数を計算する
+    pub(crate) fn calc_summarized_with(t: &TileSummarize, buf: &mut SearchBuffer) -> HandAnalyzer {
+        // 向聴数が同じならば七対子・国士無双・通常形の順に優先する
+        let mut result = HandAnalyzer::calc_summarized_by_form(t, WinningHandForm::SevenPairs, buf);
+        for form in [WinningHandForm::ThirteenOrphens, WinningHandForm::Normal].iter() {
+            let analyzer = HandAnalyzer::calc_summarized_by_form(t, *form, buf);
+            if analyzer.cmp_shanten(&result) == Ordering::Less {
+                result = analyzer;
+            }
+        }
+        return result;
+    }
+
+    /// 種類別に集計した牌から、和了形を指定して向聴数を計算する
+    fn calc_summarized_by_form(
+        t: &TileSummarize,
+        form: WinningHandForm,
+        buf: &mut SearchBuffer,
+    ) -> HandAnalyzer {
         return match form {
             WinningHandForm::SevenPairs => HandAnalyzer {
-                shanten: HandAnalyzer::calc_seven_pairs(hand),
+                shanten: HandAnalyzer::calc_seven_pairs(t),
                 form: WinningHandForm::SevenPairs,
             },
             WinningHandForm::ThirteenOrphens => HandAnalyzer {
-                shanten: HandAnalyzer::calc_thirteen_orphens(hand),
+                shanten: HandAnalyzer::calc_thirteen_orphens(t),
                 form: WinningHandForm::ThirteenOrphens,
             },
             WinningHandForm::Normal => HandAnalyzer {
-                shanten: HandAnalyzer::calc_normal_form(hand),
+                shanten: HandAnalyzer::calc_normal_form(t, buf),
                 form: WinningHandForm::Normal,
             },
         };
     }
 
+    /// 複数の手牌の向聴数をまとめて計算する
+    ///
+    /// `rayon`フィーチャーが有効な場合は並列に計算する。
+    /// 入力には`Hand`と`TileSummarize`のどちらのスライスも渡せる。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::hand_analyzer::*;
+    ///
+    /// let hands = vec![Hand::from("123m456p789s1122z"), Hand::from("19m19p19s1234567z 1m")];
+    /// let result = HandAnalyzer::calc_many(&hands);
+    /// assert_eq!(result[0].shanten, 0);
+    /// assert_eq!(result[1].shanten, -1);
+    /// ```
+    pub fn calc_many<T: AnalyzerInput>(hands: &[T]) -> Vec<HandAnalyzer> {
+        return map_with_buffer(hands, |buf, hand| {
+            HandAnalyzer::calc_summarized_with(&hand.summarize_all(), buf)
+        });
+    }
+
+    /// 聴牌している手牌の待ち牌を返す
+    ///
+    /// ツモ牌は含めず、残りの13枚に対して和了形になる牌の種類を昇順で返す。
+    /// 手の内で4枚使っている牌であっても、和了形になるならば待ち牌に含める。
+    /// 聴牌していなければ空の`Vec`を返す。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::hand_analyzer::*;
+    /// use mahjong_rs::tile::*;
+    ///
+    /// // 二萬・五萬・八萬の三面張
+    /// let test = Hand::from("3456788m123p456s");
+    /// assert_eq!(
+    ///   HandAnalyzer::calc_waiting_tiles(&test),
+    ///   vec![Tile::new(Tile::M2), Tile::new(Tile::M5), Tile::new(Tile::M8)]
+    /// );
+    /// ```
+    pub fn calc_waiting_tiles(hand: &Hand) -> Vec<Tile> {
+        return HandAnalyzer::calc_waiting_tiles_with(
+            &hand.summarize_without_drawn(),
+            &mut SearchBuffer::new(),
+        );
+    }
+
+    /// 複数の手牌の待ち牌をまとめて計算する
+    ///
+    /// `rayon`フィーチャーが有効な場合は並列に計算する。
+    pub fn calc_waiting_tiles_many<T: AnalyzerInput>(hands: &[T]) -> Vec<Vec<Tile>> {
+        return map_with_buffer(hands, |buf, hand| {
+            HandAnalyzer::calc_waiting_tiles_with(&hand.summarize_without_drawn(), buf)
+        });
+    }
+
+    /// 作業領域を使い回して待ち牌を計算する
+    pub(crate) fn calc_waiting_tiles_with(t: &TileSummarize, buf: &mut SearchBuffer) -> Vec<Tile> {
+        let mut t = *t;
+        let mut result: Vec<Tile> = Vec::new();
+        if HandAnalyzer::calc_summarized_with(&t, buf).shanten != 0 {
+            return result;
+        }
+        for i in Tile::M1..=Tile::Z7 {
+            t[i as usize] += 1;
+            if HandAnalyzer::calc_summarized_with(&t, buf).shanten == -1 {
+                result.push(Tile::new(i));
+            }
+            t[i as usize] -= 1;
+        }
+        return result;
+    }
+
+    /// 受け入れ（向聴数が進む牌）を計算する
+    ///
+    /// ツモ牌は含めず、残りの13枚に1枚加えたときに向聴数が減る牌の種類と、その残り枚数を返す。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::hand_analyzer::*;
+    /// use mahjong_rs::tile::*;
+    ///
+    /// // 二萬・五萬・八萬の三面張
+    /// let test = Hand::from("3456788m123p456s");
+    /// let ukeire = HandAnalyzer::calc_ukeire(&test);
+    /// assert_eq!(ukeire.shanten, 0);
+    /// assert_eq!(ukeire.tiles.len(), 3);
+    /// assert_eq!(ukeire.count, 9);
+    /// ```
+    pub fn calc_ukeire(hand: &Hand) -> Ukeire {
+        return HandAnalyzer::calc_ukeire_with(
+            &hand.summarize_without_drawn(),
+            &mut SearchBuffer::new(),
+        );
+    }
+
+    /// 複数の手牌の受け入れをまとめて計算する
+    ///
+    /// `rayon`フィーチャーが有効な場合は並列に計算する。
+    pub fn calc_ukeire_many<T: AnalyzerInput>(hands: &[T]) -> Vec<Ukeire> {
+        return map_with_buffer(hands, |buf, hand| {
+            HandAnalyzer::calc_ukeire_with(&hand.summarize_without_drawn(), buf)
+        });
+    }
+
+    /// 作業領域を使い回して受け入れを計算する
+    pub(crate) fn calc_ukeire_with(t: &TileSummarize, buf: &mut SearchBuffer) -> Ukeire {
+        let mut t = *t;
+        let shanten = HandAnalyzer::calc_summarized_with(&t, buf).shanten;
+        let mut result = Ukeire {
+            shanten,
+            tiles: Vec::new(),
+            count: 0,
+        };
+        for i in Tile::M1..=Tile::Z7 {
+            if t[i as usize] >= 4 {
+                continue;
+            }
+            t[i as usize] += 1;
+            if HandAnalyzer::calc_summarized_with(&t, buf).shanten < shanten {
+                result.tiles.push(Tile::new(i));
+                result.count += 4 - (t[i as usize] - 1);
+            }
+            t[i as usize] -= 1;
+        }
+        return result;
+    }
+
     /// 七対子への向聴数を計算する
-    fn calc_seven_pairs(hand: &Hand) -> i32 {
+    pub(crate) fn calc_seven_pairs(t: &TileSummarize) -> i32 {
+        return HandAnalyzer::calc_seven_pairs_by_rule(t, false);
+    }
+
+    /// 同じ牌4枚を2つの対子として扱うか否かを指定して、七対子への向聴数を計算する
+    pub(crate) fn calc_seven_pairs_by_rule(t: &TileSummarize, four_identical: bool) -> i32 {
         let mut pair: u32 = 0;
         let mut kind: u32 = 0;
-        let t = hand.summarize_tiles();
 
-        for i in 0..Tile::LEN {
-            if t[i] > 0 {
-                kind += 1;
-                if t[i] >= 2 {
-                    pair += 1;
+        for &count in t.iter() {
+            if count > 0 {
+                if four_identical {
+                    kind += count.div_ceil(2);
+                    pair += count / 2;
+                } else {
+                    kind += 1;
+                    if count >= 2 {
+                        pair += 1;
+                    }
                 }
             }
         }
-        let num_to_win: i32 = (7 - pair + if kind < 7 { 7 - kind } else { 0 }) as i32;
+        let num_to_win: i32 = (7 - pair + 7_u32.saturating_sub(kind)) as i32;
         return num_to_win - 1;
     }
 
     /// 国士無双への向聴数を計算する
-    fn calc_thirteen_orphens(hand: &Hand) -> i32 {
+    pub(crate) fn calc_thirteen_orphens(t: &TileSummarize) -> i32 {
         let to_tiles = [
             Tile::M1,
             Tile::M9,
@@ -146,11 +467,10 @@ impl HandAnalyzer {
         ];
         let mut pair: u32 = 0;
         let mut kind: u32 = 0;
-        let t = hand.summarize_tiles();
 
         for i in &to_tiles {
             if t[*i as usize] > 0 {
-                kind = kind + 1;
+                kind += 1;
                 if t[*i as usize] >= 2 {
                     pair += 1;
                 }
@@ -160,60 +480,44 @@ impl HandAnalyzer {
         return num_to_win - 1;
     }
 
+    /// 通常形の向聴数と、その向聴数になる分け方を計算する
+    fn calc_normal_split(t: &TileSummarize, buf: &mut SearchBuffer) -> (i32, NormalSplit) {
+        let shanten = HandAnalyzer::calc_normal_form(t, buf);
+        return (shanten, std::mem::take(&mut buf.best));
+    }
+
     /// 通常の役への向聴数を計算する
-    fn calc_normal_form(hand: &Hand) -> i32 {
-        let mut t = hand.summarize_tiles();
+    fn calc_normal_form(summarized_hand: &TileSummarize, buf: &mut SearchBuffer) -> i32 {
+        let mut t = *summarized_hand;
         let mut shanten: i32 = 100;
-
-        let mut same3: Vec<[TileType; 3]> = Vec::new();
-        let mut sequential3: Vec<[TileType; 3]> = Vec::new();
-        let mut same2: Vec<[TileType; 2]> = Vec::new();
-        let mut sequential2: Vec<[TileType; 2]> = Vec::new();
+        buf.clear();
 
         // 先に独立した牌を抜き出しておく
-        let independent_same3 = HandAnalyzer::count_independent_same_3(&mut t);
-        let independent_sequential3 = HandAnalyzer::count_independent_sequential_3(&mut t);
+        HandAnalyzer::count_independent_same_3(&mut t, &mut buf.independent_same3);
+        HandAnalyzer::count_independent_sequential_3(&mut t, &mut buf.independent_sequential3);
         //let independent_single = HandAnalyzer::count_independent_single(&mut t);
         HandAnalyzer::count_independent_single(&mut t);
 
         // 雀頭を抜き出す
         for i in Tile::M1..=Tile::Z7 {
             if t[i as usize] >= 2 {
-                same2.push([i; 2]);
+                buf.same2.push([i; 2]);
                 t[i as usize] -= 2;
-                shanten = count_normal_shanten_recursively(
-                    0,
-                    &independent_same3,
-                    &independent_sequential3,
-                    &mut same3,
-                    &mut sequential3,
-                    &mut same2,
-                    &mut sequential2,
-                    &mut t,
-                    &mut shanten,
-                );
+                shanten = buf.count_normal_shanten_recursively(0, &mut t, &mut shanten);
                 t[i as usize] += 2;
-                same2.pop();
+                buf.same2.pop();
             }
         }
 
         // 雀頭がない場合
-        shanten = count_normal_shanten_recursively(
-            0,
-            &independent_same3,
-            &independent_sequential3,
-            &mut same3,
-            &mut sequential3,
-            &mut same2,
-            &mut sequential2,
-            &mut t,
-            &mut shanten,
-        );
+        shanten = buf.count_normal_shanten_recursively(0, &mut t, &mut shanten);
         return shanten;
     }
-    /// 独立した（順子になり得ない）刻子の数を返す
-    fn count_independent_same_3(summarized_hand: &mut TileSummarize) -> Vec<[TileType; 3]> {
-        let mut result: Vec<[TileType; 3]> = Vec::new();
+    /// 独立した（順子になり得ない）刻子を抜き出す
+    fn count_independent_same_3(
+        summarized_hand: &mut TileSummarize,
+        result: &mut Vec<[TileType; 3]>,
+    ) {
         for i in Tile::M1..=Tile::Z7 {
             match i {
                 Tile::M1 | Tile::P1 | Tile::S1 => {
@@ -276,13 +580,14 @@ impl HandAnalyzer {
                 }
             }
         }
-        return result;
     }
 
-    /// 独立した（他の順子と複合し得ない）順子の数を返す
+    /// 独立した（他の順子と複合し得ない）順子を抜き出す
     /// i.e. xx567xxのような順子
-    fn count_independent_sequential_3(summarized_hand: &mut TileSummarize) -> Vec<[TileType; 3]> {
-        let mut result: Vec<[TileType; 3]> = Vec::new();
+    fn count_independent_sequential_3(
+        summarized_hand: &mut TileSummarize,
+        result: &mut Vec<[TileType; 3]>,
+    ) {
         // 先に一盃口の処理をしてから通常の処理
         for i in (1..=2).rev() {
             // 一萬、一筒、一索のインデックス位置
@@ -317,14 +622,13 @@ impl HandAnalyzer {
                         summarized_hand[l] -= i;
                         summarized_hand[l + 1] -= i;
                         summarized_hand[l + 2] -= i;
-                        for m in 0..i {
+                        for _ in 0..i {
                             result.push([l as TileType, (l + 1) as TileType, (l + 2) as TileType]);
                         }
                     }
                 }
             }
         }
-        return result;
     }
 
     /// 独立した（他の順子や刻子などと複合し得ない）牌の数を返す
@@ -396,222 +700,255 @@ impl HandAnalyzer {
     }
 }
 
-/// 再帰的にシャンテン数が最小のものを探す
-fn count_normal_shanten_recursively(
-    idx: TileType,
-    independent_same3: &Vec<[TileType; 3]>,
-    independent_sequential3: &Vec<[TileType; 3]>,
-    same3: &mut Vec<[TileType; 3]>,
-    sequential3: &mut Vec<[TileType; 3]>,
-    same2: &mut Vec<[TileType; 2]>,
-    sequential2: &mut Vec<[TileType; 2]>,
-    summarized_hand: &mut TileSummarize,
-    shanten_min: &mut i32,
-) -> i32 {
-    count_same_or_sequential_3(
-        idx,
-        independent_same3,
-        independent_sequential3,
-        same3,
-        sequential3,
-        same2,
-        sequential2,
-        summarized_hand,
-        shanten_min,
-    );
-    count_same_or_sequential_2(
-        idx,
-        independent_same3,
-        independent_sequential3,
-        same3,
-        sequential3,
-        same2,
-        sequential2,
-        summarized_hand,
-        shanten_min,
-    );
-    let shanten = calc_normal_shanten(
-        independent_same3,
-        independent_sequential3,
-        same3,
-        sequential3,
-        same2,
-        sequential2,
-    );
-    if shanten < *shanten_min {
-        *shanten_min = shanten;
-        //println!("shanten_min: {}",shanten_min);
-        //println!("hand: {}",Hand::from_summarized(&summarized_hand).to_short_string());
-    }
-    return *shanten_min;
+/// 受け入れ（向聴数が進む牌）
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Ukeire {
+    /// 現在の向聴数
+    pub shanten: i32,
+    /// 向聴数が進む牌の種類
+    pub tiles: Vec<Tile>,
+    /// 向聴数が進む牌の残り枚数（自分の手牌で使っている分は除く）
+    pub count: u32,
 }
 
-/// 面子（刻子および順子）の数を返す
-/// # returns
-/// (刻子, 順子)
-fn count_same_or_sequential_3(
-    idx: TileType,
-    independent_same3: &Vec<[TileType; 3]>,
-    independent_sequential3: &Vec<[TileType; 3]>,
-    same3: &mut Vec<[TileType; 3]>,
-    sequential3: &mut Vec<[TileType; 3]>,
-    same2: &mut Vec<[TileType; 2]>,
-    sequential2: &mut Vec<[TileType; 2]>,
-    summarized_hand: &mut TileSummarize,
-    shanten_min: &mut i32,
-) {
-    for i in idx..=Tile::Z7 {
-        // 刻子カウント
-        if summarized_hand[i as usize] >= 3 {
-            //block3 += 1;
-            same3.push([i; 3]);
-            summarized_hand[i as usize] -= 3;
-            *shanten_min = count_normal_shanten_recursively(
-                i,
-                independent_same3,
-                independent_sequential3,
-                same3,
-                sequential3,
-                same2,
-                sequential2,
-                summarized_hand,
-                shanten_min,
-            );
-            summarized_hand[i as usize] += 3;
-            same3.pop();
-        }
+/// 一括計算の入力として使える手牌の表現
+pub trait AnalyzerInput: Sync {
+    /// 向聴数の計算に使う集計（ツモ牌を含む）
+    fn summarize_all(&self) -> TileSummarize;
+    /// 待ち牌や受け入れの計算に使う集計（ツモ牌を除く）
+    fn summarize_without_drawn(&self) -> TileSummarize;
+}
 
-        //順子カウント
-        if ((i >= Tile::M1 && i <= Tile::M7)
-            || (i >= Tile::P1 && i <= Tile::P7)
-            || (i >= Tile::S1 && i <= Tile::S7))
-            && summarized_hand[i as usize] >= 1
-            && summarized_hand[i as usize + 1] >= 1
-            && summarized_hand[i as usize + 2] >= 1
-        {
-            //block3 += 1;
-            sequential3.push([i, i + 1, i + 2]);
-            summarized_hand[i as usize] -= 1;
-            summarized_hand[i as usize + 1] -= 1;
-            summarized_hand[i as usize + 2] -= 1;
-            *shanten_min = count_normal_shanten_recursively(
-                i,
-                independent_same3,
-                independent_sequential3,
-                same3,
-                sequential3,
-                same2,
-                sequential2,
-                summarized_hand,
-                shanten_min,
-            );
-            summarized_hand[i as usize] += 1;
-            summarized_hand[i as usize + 1] += 1;
-            summarized_hand[i as usize + 2] += 1;
-            sequential3.pop();
+impl AnalyzerInput for Hand {
+    fn summarize_all(&self) -> TileSummarize {
+        return self.summarize_tiles();
+    }
+    fn summarize_without_drawn(&self) -> TileSummarize {
+        let mut t = self.summarize_tiles();
+        if let Some(drawn) = self.drawn() {
+            t[drawn.get() as usize] -= 1;
         }
+        return t;
     }
-    //return (same3, sequential3);
 }
 
-/// 対子・塔子・嵌張カウント
-/// # returns
-/// (対子,塔子・嵌張)
-fn count_same_or_sequential_2(
-    idx: TileType,
-    independent_same3: &Vec<[TileType; 3]>,
-    independent_sequential3: &Vec<[TileType; 3]>,
-    same3: &mut Vec<[TileType; 3]>,
-    sequential3: &mut Vec<[TileType; 3]>,
-    same2: &mut Vec<[TileType; 2]>,
-    sequential2: &mut Vec<[TileType; 2]>,
-    summarized_hand: &mut TileSummarize,
-    shanten_min: &mut i32,
-) {
-    for i in idx..=Tile::Z7 {
-        // 対子
-        if summarized_hand[i as usize] == 2 {
-            same2.push([i; 2]);
-            summarized_hand[i as usize] -= 2;
-            *shanten_min = count_normal_shanten_recursively(
-                idx,
-                independent_same3,
-                independent_sequential3,
-                same3,
-                sequential3,
-                same2,
-                sequential2,
-                summarized_hand,
-                shanten_min,
-            );
-            summarized_hand[i as usize] += 2;
-            same2.pop();
+/// 集計済みの牌にはツモ牌の区別がないため、どちらも同じ集計を返す
+impl AnalyzerInput for TileSummarize {
+    fn summarize_all(&self) -> TileSummarize {
+        return *self;
+    }
+    fn summarize_without_drawn(&self) -> TileSummarize {
+        return *self;
+    }
+}
+
+/// 作業領域を1つずつ使い回しながら、各入力に`f`を適用する
+///
+/// `rayon`フィーチャーが有効な場合はスレッドごとに作業領域を用意して並列に処理する。
+fn map_with_buffer<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&mut SearchBuffer, &T) -> R + Sync + Send,
+{
+    #[cfg(feature = "rayon")]
+    {
+        return items
+            .par_iter()
+            .map_init(SearchBuffer::new, |buf, item| f(buf, item))
+            .collect();
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut buf = SearchBuffer::new();
+        return items.iter().map(|item| f(&mut buf, item)).collect();
+    }
+}
+
+/// 通常形の手牌の分け方
+#[derive(Debug, Clone, Default)]
+struct NormalSplit {
+    /// 面子
+    block3: Vec<[TileType; 3]>,
+    /// 対子・搭子
+    block2: Vec<[TileType; 2]>,
+}
+
+/// 通常形の向聴数を再帰的に探索するための作業領域
+///
+/// 探索中に面子や搭子を積む`Vec`をまとめて持ち、複数の手牌の計算で使い回す。
+#[derive(Debug, Default)]
+pub(crate) struct SearchBuffer {
+    independent_same3: Vec<[TileType; 3]>,
+    independent_sequential3: Vec<[TileType; 3]>,
+    same3: Vec<[TileType; 3]>,
+    sequential3: Vec<[TileType; 3]>,
+    same2: Vec<[TileType; 2]>,
+    sequential2: Vec<[TileType; 2]>,
+    /// 最小の向聴数になった分け方
+    best: NormalSplit,
+}
+
+impl SearchBuffer {
+    pub(crate) fn new() -> SearchBuffer {
+        return SearchBuffer::default();
+    }
+
+    /// 確保済みの領域は残したまま中身を空にする
+    fn clear(&mut self) {
+        self.independent_same3.clear();
+        self.independent_sequential3.clear();
+        self.same3.clear();
+        self.sequential3.clear();
+        self.same2.clear();
+        self.sequential2.clear();
+        self.best.block3.clear();
+        self.best.block2.clear();
+    }
+
+    /// 再帰的にシャンテン数が最小のものを探す
+    fn count_normal_shanten_recursively(
+        &mut self,
+        idx: TileType,
+        summarized_hand: &mut TileSummarize,
+        shanten_min: &mut i32,
+    ) -> i32 {
+        self.count_same_or_sequential_3(idx, summarized_hand, shanten_min);
+        self.count_same_or_sequential_2(idx, summarized_hand, shanten_min);
+        let shanten = self.calc_normal_shanten();
+        if shanten < *shanten_min {
+            *shanten_min = shanten;
+            self.save_best();
         }
-        //数牌
-        if i <= Tile::S9 && (i >= Tile::M1 && i <= Tile::M7)
-            || (i >= Tile::P1 && i <= Tile::P7)
-            || (i >= Tile::S1 && i <= Tile::S7)
-        {
-            // 塔子
-            if summarized_hand[i as usize] >= 1 && summarized_hand[i as usize + 1] >= 1 {
-                sequential2.push([i, i + 1]);
+        return *shanten_min;
+    }
+
+    /// 面子（刻子および順子）を抜き出して探索する
+    fn count_same_or_sequential_3(
+        &mut self,
+        idx: TileType,
+        summarized_hand: &mut TileSummarize,
+        shanten_min: &mut i32,
+    ) {
+        for i in idx..=Tile::Z7 {
+            // 刻子カウント
+            if summarized_hand[i as usize] >= 3 {
+                self.same3.push([i; 3]);
+                summarized_hand[i as usize] -= 3;
+                *shanten_min =
+                    self.count_normal_shanten_recursively(i, summarized_hand, shanten_min);
+                summarized_hand[i as usize] += 3;
+                self.same3.pop();
+            }
+
+            //順子カウント
+            if is_sequential_head(i)
+                && summarized_hand[i as usize] >= 1
+                && summarized_hand[i as usize + 1] >= 1
+                && summarized_hand[i as usize + 2] >= 1
+            {
+                self.sequential3.push([i, i + 1, i + 2]);
                 summarized_hand[i as usize] -= 1;
                 summarized_hand[i as usize + 1] -= 1;
-                *shanten_min = count_normal_shanten_recursively(
-                    idx,
-                    independent_same3,
-                    independent_sequential3,
-                    same3,
-                    sequential3,
-                    same2,
-                    sequential2,
-                    summarized_hand,
-                    shanten_min,
-                );
+                summarized_hand[i as usize + 2] -= 1;
+                *shanten_min =
+                    self.count_normal_shanten_recursively(i, summarized_hand, shanten_min);
                 summarized_hand[i as usize] += 1;
                 summarized_hand[i as usize + 1] += 1;
-                sequential2.pop();
+                summarized_hand[i as usize + 2] += 1;
+                self.sequential3.pop();
+            }
+        }
+    }
+
+    /// 対子・塔子・嵌張を抜き出して探索する
+    fn count_same_or_sequential_2(
+        &mut self,
+        idx: TileType,
+        summarized_hand: &mut TileSummarize,
+        shanten_min: &mut i32,
+    ) {
+        for i in idx..=Tile::Z7 {
+            // 対子
+            if summarized_hand[i as usize] == 2 {
+                self.same2.push([i; 2]);
+                summarized_hand[i as usize] -= 2;
+                *shanten_min =
+                    self.count_normal_shanten_recursively(idx, summarized_hand, shanten_min);
+                summarized_hand[i as usize] += 2;
+                self.same2.pop();
+            }
+            // 塔子（両面・辺張）
+            if is_sequential_2_head(i)
+                && summarized_hand[i as usize] >= 1
+                && summarized_hand[i as usize + 1] >= 1
+            {
+                self.sequential2.push([i, i + 1]);
+                summarized_hand[i as usize] -= 1;
+                summarized_hand[i as usize + 1] -= 1;
+                *shanten_min =
+                    self.count_normal_shanten_recursively(idx, summarized_hand, shanten_min);
+                summarized_hand[i as usize] += 1;
+                summarized_hand[i as usize + 1] += 1;
+                self.sequential2.pop();
             }
             //嵌張
-            if summarized_hand[i as usize] >= 1
+            if is_sequential_head(i)
+                && summarized_hand[i as usize] >= 1
                 && summarized_hand[i as usize + 1] == 0
                 && summarized_hand[i as usize + 2] >= 1
             {
-                sequential2.push([i, i + 2]);
+                self.sequential2.push([i, i + 2]);
                 summarized_hand[i as usize] -= 1;
                 summarized_hand[i as usize + 2] -= 1;
-                *shanten_min = count_normal_shanten_recursively(
-                    idx,
-                    independent_same3,
-                    independent_sequential3,
-                    same3,
-                    sequential3,
-                    same2,
-                    sequential2,
-                    summarized_hand,
-                    shanten_min,
-                );
+                *shanten_min =
+                    self.count_normal_shanten_recursively(idx, summarized_hand, shanten_min);
                 summarized_hand[i as usize] += 1;
                 summarized_hand[i as usize + 2] += 1;
-                sequential2.pop();
+                self.sequential2.pop();
             }
         }
     }
-    //return (same2, sequential2);
+
+    /// 現在の分け方を最小の向聴数になる分け方として覚えておく
+    fn save_best(&mut self) {
+        self.best.block3.clear();
+        self.best.block3.extend_from_slice(&self.independent_same3);
+        self.best
+            .block3
+            .extend_from_slice(&self.independent_sequential3);
+        self.best.block3.extend_from_slice(&self.same3);
+        self.best.block3.extend_from_slice(&self.sequential3);
+        self.best.block2.clear();
+        self.best.block2.extend_from_slice(&self.same2);
+        self.best.block2.extend_from_slice(&self.sequential2);
+    }
+
+    /// 面子・搭子の組み合わせから向聴数を計算する
+    ///
+    /// 対子があればそのうち1つを雀頭とし、面子と（雀頭以外の）搭子の合計は4つまでしか数えない。
+    fn calc_normal_shanten(&self) -> i32 {
+        let block3 = self.independent_same3.len()
+            + self.independent_sequential3.len()
+            + self.same3.len()
+            + self.sequential3.len();
+        let head = if self.same2.is_empty() { 0 } else { 1 };
+        let block2 = min(
+            self.same2.len() + self.sequential2.len() - head,
+            4 - min(block3, 4),
+        );
+        return 8 - (block3 * 2 + block2 + head) as i32;
+    }
+}
+
+/// 順子の先頭（一～七）になり得る数牌か否かを返す
+fn is_sequential_head(i: TileType) -> bool {
+    return matches!(i, Tile::M1..=Tile::M7 | Tile::P1..=Tile::P7 | Tile::S1..=Tile::S7);
 }
 
-fn calc_normal_shanten(
-    independent_same3: &Vec<[TileType; 3]>,
-    independent_sequential3: &Vec<[TileType; 3]>,
-    same3: &Vec<[TileType; 3]>,
-    sequential3: &Vec<[TileType; 3]>,
-    same2: &Vec<[TileType; 2]>,
-    sequential2: &Vec<[TileType; 2]>,
-) -> i32 {
-    let block3 =
-        independent_same3.len() + independent_sequential3.len() + same3.len() + sequential3.len();
-    let block2 = same2.len() + sequential2.len();
-    return 8 - (block3 * 2 + block2) as i32;
+/// 両面・辺張の搭子の先頭（一～八）になり得る数牌か否かを返す
+fn is_sequential_2_head(i: TileType) -> bool {
+    return matches!(i, Tile::M1..=Tile::M8 | Tile::P1..=Tile::P8 | Tile::S1..=Tile::S8);
 }
 
 /// ユニットテスト
@@ -714,6 +1051,178 @@ mod tests {
         );
     }
 
+    #[test]
+    /// 雀頭がなければ4面子と搭子があっても和了形ではない
+    fn four_sets_without_pair_is_not_winning() {
+        let test_str = "123456789m12345p";
+        let test = Hand::from(test_str);
+        assert_eq!(
+            HandAnalyzer::calc_by_form(&test, WinningHandForm::Normal).shanten,
+            0
+        );
+    }
+
+    #[test]
+    /// 面子と搭子の合計は4つまでしか数えない
+    fn too_many_blocks() {
+        let test_str = "123456789m1245p";
+        let test = Hand::from(test_str);
+        assert_eq!(
+            HandAnalyzer::calc_by_form(&test, WinningHandForm::Normal).shanten,
+            1
+        );
+    }
+
+    #[test]
+    /// 八九の辺張も搭子として数える
+    fn edge_wait_on_eight_nine() {
+        let test_str = "12389m456p789s11z";
+        let test = Hand::from(test_str);
+        assert_eq!(
+            HandAnalyzer::calc_by_form(&test, WinningHandForm::Normal).shanten,
+            0
+        );
+        assert_eq!(
+            HandAnalyzer::calc_waiting_tiles(&test),
+            vec![Tile::new(Tile::M7)]
+        );
+    }
+
+    #[test]
+    /// 待ち牌（両面待ち＋単騎待ちの複合）
+    fn waiting_tiles_of_mixed_wait() {
+        let test_str = "2345m456p789s111z";
+        let test = Hand::from(test_str);
+        assert_eq!(
+            HandAnalyzer::calc_waiting_tiles(&test),
+            vec![Tile::new(Tile::M2), Tile::new(Tile::M5)]
+        );
+    }
+
+    #[test]
+    /// 国士無双十三面待ち
+    fn waiting_tiles_of_thirteen_orphens() {
+        let test_str = "19m19p19s1234567z";
+        let test = Hand::from(test_str);
+        assert_eq!(HandAnalyzer::calc_waiting_tiles(&test).len(), 13);
+    }
+
+    #[test]
+    /// 聴牌していなければ待ち牌はない
+    fn no_waiting_tiles_without_ready_hand() {
+        let test_str = "1357m2468p1357s1z";
+        let test = Hand::from(test_str);
+        assert!(HandAnalyzer::calc_waiting_tiles(&test).is_empty());
+    }
+
+    #[test]
+    /// 一括計算の結果は1つずつ計算した結果と一致する
+    fn calc_many_matches_calc() {
+        let hands = vec![
+            Hand::from("226699m99p228s66z 1z"),
+            Hand::from("19m19p11s1234567z 5m"),
+            Hand::from("123567m234p6799s 5s"),
+            Hand::from("1357m2468p1357s1z"),
+        ];
+        let summarized: Vec<TileSummarize> = hands.iter().map(|h| h.summarize_tiles()).collect();
+        let from_hands = HandAnalyzer::calc_many(&hands);
+        let from_summarized = HandAnalyzer::calc_many(&summarized);
+        for i in 0..hands.len() {
+            let single = HandAnalyzer::calc(&hands[i]);
+            assert_eq!(from_hands[i].shanten, single.shanten);
+            assert_eq!(from_summarized[i].shanten, single.shanten);
+        }
+    }
+
+    #[test]
+    /// 一向聴の受け入れ
+    fn ukeire_of_one_shanten() {
+        let test_str = "12m456p123789s12z 5z";
+        let test = Hand::from(test_str);
+        let ukeire = HandAnalyzer::calc_ukeire(&test);
+        assert_eq!(ukeire.shanten, 1);
+        assert_eq!(
+            ukeire.tiles,
+            vec![
+                Tile::new(Tile::M3),
+                Tile::new(Tile::Z1),
+                Tile::new(Tile::Z2)
+            ]
+        );
+        assert_eq!(ukeire.count, 4 + 3 + 3);
+        assert_eq!(HandAnalyzer::calc_ukeire_many(&[test]), vec![ukeire]);
+    }
+
+    #[test]
+    /// 待ち牌の一括計算
+    fn calc_waiting_tiles_many() {
+        let hands = vec![
+            Hand::from("2345m456p789s111z"),
+            Hand::from("1357m2468p1357s1z"),
+        ];
+        let result = HandAnalyzer::calc_waiting_tiles_many(&hands);
+        assert_eq!(result[0], HandAnalyzer::calc_waiting_tiles(&hands[0]));
+        assert!(result[1].is_empty());
+    }
+
+    #[test]
+    /// 和了形が違っても向聴数が同じならば同じとみなす
+    fn compare_shanten_only() {
+        let sp = HandAnalyzer::calc_by_form(
+            &Hand::from("1122m3344p5566s1z 2z"),
+            WinningHandForm::SevenPairs,
+        );
+        let normal = HandAnalyzer::calc_by_form(
+            &Hand::from("123m456p789s1122z 3z"),
+            WinningHandForm::Normal,
+        );
+        assert!(sp.is_same_shanten(&normal));
+        assert_eq!(sp.cmp_shanten(&normal), Ordering::Equal);
+        assert_ne!(sp, normal);
+    }
+
+    #[test]
+    /// 手牌の内訳（2面子・2搭子・1対子・孤立牌2枚）
+    fn report_blocks() {
+        let test_str = "123m46p23789s1157z";
+        let report = HandAnalyzer::calc_report(&Hand::from(test_str));
+        assert_eq!(report.shanten_of(WinningHandForm::Normal), 1);
+        assert_eq!(report.min_forms, vec![WinningHandForm::Normal]);
+        assert_eq!(report.complete_blocks, 2);
+        assert_eq!(report.partial_blocks, 2);
+        assert_eq!(report.pair_candidates, 1);
+        assert_eq!(
+            report.isolated,
+            vec![Tile::new(Tile::Z5), Tile::new(Tile::Z7)]
+        );
+    }
+
+    #[test]
+    /// 4つを超える搭子は数えず、その牌は孤立牌に含める
+    fn report_too_many_partial_blocks() {
+        let test_str = "12m45m78m12p45p78p1s 9s";
+        let report = HandAnalyzer::calc_report(&Hand::from(test_str));
+        assert_eq!(report.shanten_of(WinningHandForm::Normal), 4);
+        assert_eq!(report.complete_blocks, 0);
+        assert_eq!(report.partial_blocks, 4);
+        assert_eq!(report.pair_candidates, 0);
+        assert_eq!(report.isolated.len(), 6);
+        assert_eq!(
+            report.shanten_of(WinningHandForm::Normal),
+            8 - 2 * report.complete_blocks as i32 - report.partial_blocks as i32
+        );
+    }
+
+    #[test]
+    /// 国士無双の向聴数も含める
+    fn report_thirteen_orphens() {
+        let test_str = "19m19p11s1234567z 5m";
+        let report = HandAnalyzer::calc_report(&Hand::from(test_str));
+        assert_eq!(report.shanten, 0);
+        assert_eq!(report.min_forms, vec![WinningHandForm::ThirteenOrphens]);
+        assert_eq!(report.by_form.len(), 3);
+    }
+
     #[test]
     /// 平和で和了った
     fn win_by_no_points() {
@@ -0,0 +1,614 @@
+use std::cell::RefCell;
+use std::cmp::*;
+use std::collections::HashMap;
+
+use crate::hand::Hand;
+use crate::hand_info::hand_analyzer::{HandAnalyzer, Ukeire};
+use crate::hand_info::opened::OpenType;
+use crate::tile::*;
+
+/// 色（萬子・筒子・索子・字牌）の数
+const SUIT_LEN: usize = 4;
+
+/// スレッドごとに覚えておく並びの数の上限
+const SUIT_CACHE_CAPACITY: usize = 1 << 14;
+
+thread_local! {
+    /// 1つの色の牌の並びごとに、計算済みの組み合わせを覚えておく
+    ///
+    /// 上限に達したら全て忘れて覚え直すので、スレッドが長く動いても大きくなり続けない。
+    static SUIT_CACHE: RefCell<HashMap<(u32, BlockRule), SuitPartial>> = RefCell::new(HashMap::new());
+}
+
+/// 1つの色の中で使ってよい面子・搭子の種類
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub(crate) struct BlockRule {
+    /// 順子・順子の搭子を使えるか
+    pub sequential: bool,
+    /// 一九牌を含む（または一九牌を含む順子になりうる）ものだけを使うか
+    pub terminal: bool,
+}
+
+impl BlockRule {
+    /// 数牌の通常の規則
+    pub const NUMBER: BlockRule = BlockRule {
+        sequential: true,
+        terminal: false,
+    };
+    /// 字牌の規則
+    pub const HONOR: BlockRule = BlockRule {
+        sequential: false,
+        terminal: false,
+    };
+
+    /// `i`番目の牌の刻子・対子を使えるか
+    fn allows_same(&self, i: usize) -> bool {
+        return !self.terminal || i == 0 || i == 8;
+    }
+
+    /// `i`番目の牌から始まる順子を使えるか
+    fn allows_sequential_3(&self, i: usize) -> bool {
+        return self.sequential && (!self.terminal || i == 0 || i == 6);
+    }
+
+    /// `i`番目と`i + 1`番目の牌の搭子（両面・辺張）を使えるか
+    fn allows_sequential_2(&self, i: usize) -> bool {
+        return self.sequential && (!self.terminal || i <= 1 || i == 6 || i == 7);
+    }
+
+    /// `i`番目と`i + 2`番目の牌の搭子（嵌張）を使えるか
+    fn allows_sequential_gap(&self, i: usize) -> bool {
+        return self.sequential && (!self.terminal || i == 0 || i == 6);
+    }
+}
+
+/// 1つの色について、取り得る面子・搭子の数の組み合わせ
+///
+/// どちらも他の組み合わせに劣らない（面子と搭子の両方が少ないものがない）ものだけを持つ。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SuitPartial {
+    /// 雀頭を含まない(面子, 搭子)の組み合わせ
+    without_head: Vec<(u32, u32)>,
+    /// 雀頭を1つ含む(面子, 搭子)の組み合わせ
+    with_head: Vec<(u32, u32)>,
+}
+
+impl SuitPartial {
+    /// 1つの色の牌の枚数から組み合わせを求める
+    ///
+    /// 同じ並びについては一度計算した結果を使い回す。
+    pub(crate) fn calc_cached(counts: &[u32], rule: BlockRule) -> SuitPartial {
+        let key = (counts.iter().fold(0, |acc, &c| acc * 5 + c), rule);
+        if let Some(cached) = SUIT_CACHE.with(|cache| cache.borrow().get(&key).cloned()) {
+            return cached;
+        }
+        let result = SuitPartial::calc(counts, rule);
+        SUIT_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if cache.len() >= SUIT_CACHE_CAPACITY {
+                cache.clear();
+            }
+            cache.insert(key, result.clone());
+        });
+        return result;
+    }
+
+    /// 1つの色の牌の枚数から組み合わせを求める
+    fn calc(counts: &[u32], rule: BlockRule) -> SuitPartial {
+        let mut result = SuitPartial::default();
+        let mut c = counts.to_vec();
+        SuitPartial::search(&mut c, 0, 0, 0, false, rule, &mut result);
+        result.without_head = pareto(&result.without_head);
+        result.with_head = pareto(&result.with_head);
+        return result;
+    }
+
+    /// `idx`番目以降の牌から面子・搭子・雀頭を抜き出す組み合わせを全て探す
+    fn search(
+        c: &mut Vec<u32>,
+        idx: usize,
+        same_or_sequential_3: u32,
+        same_or_sequential_2: u32,
+        head: bool,
+        rule: BlockRule,
+        result: &mut SuitPartial,
+    ) {
+        let mut i = idx;
+        while i < c.len() && c[i] == 0 {
+            i += 1;
+        }
+        if i == c.len() {
+            let blocks = (same_or_sequential_3, same_or_sequential_2);
+            if head {
+                result.with_head.push(blocks);
+            } else {
+                result.without_head.push(blocks);
+            }
+            return;
+        }
+        let (m, t) = (same_or_sequential_3, same_or_sequential_2);
+        // 刻子
+        if c[i] >= 3 && rule.allows_same(i) {
+            c[i] -= 3;
+            SuitPartial::search(c, i, m + 1, t, head, rule, result);
+            c[i] += 3;
+        }
+        // 順子
+        if rule.allows_sequential_3(i) && i + 2 < c.len() && c[i + 1] > 0 && c[i + 2] > 0 {
+            c[i] -= 1;
+            c[i + 1] -= 1;
+            c[i + 2] -= 1;
+            SuitPartial::search(c, i, m + 1, t, head, rule, result);
+            c[i] += 1;
+            c[i + 1] += 1;
+            c[i + 2] += 1;
+        }
+        // 対子（雀頭または搭子として使う）
+        if c[i] >= 2 && rule.allows_same(i) {
+            c[i] -= 2;
+            if !head {
+                SuitPartial::search(c, i, m, t, true, rule, result);
+            }
+            SuitPartial::search(c, i, m, t + 1, head, rule, result);
+            c[i] += 2;
+        }
+        // 両面・辺張
+        if rule.allows_sequential_2(i) && i + 1 < c.len() && c[i + 1] > 0 {
+            c[i] -= 1;
+            c[i + 1] -= 1;
+            SuitPartial::search(c, i, m, t + 1, head, rule, result);
+            c[i] += 1;
+            c[i + 1] += 1;
+        }
+        // 嵌張
+        if rule.allows_sequential_gap(i) && i + 2 < c.len() && c[i + 2] > 0 {
+            c[i] -= 1;
+            c[i + 2] -= 1;
+            SuitPartial::search(c, i, m, t + 1, head, rule, result);
+            c[i] += 1;
+            c[i + 2] += 1;
+        }
+        // 孤立牌として使わない
+        c[i] -= 1;
+        SuitPartial::search(c, i, m, t, head, rule, result);
+        c[i] += 1;
+    }
+}
+
+/// 他の組み合わせに劣る(面子, 搭子)を取り除く
+fn pareto(blocks: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut result: Vec<(u32, u32)> = Vec::new();
+    for &(m, t) in blocks {
+        if blocks
+            .iter()
+            .any(|&(m2, t2)| m2 >= m && t2 >= t && (m2, t2) != (m, t))
+        {
+            continue;
+        }
+        if !result.contains(&(m, t)) {
+            result.push((m, t));
+        }
+    }
+    return result;
+}
+
+/// 2つの色の組み合わせを合わせる
+pub(crate) fn merge(a: &SuitPartial, b: &SuitPartial) -> SuitPartial {
+    let mut result = SuitPartial::default();
+    for &(m1, t1) in &a.without_head {
+        for &(m2, t2) in &b.without_head {
+            result.without_head.push((m1 + m2, t1 + t2));
+        }
+        for &(m2, t2) in &b.with_head {
+            result.with_head.push((m1 + m2, t1 + t2));
+        }
+    }
+    for &(m1, t1) in &a.with_head {
+        for &(m2, t2) in &b.without_head {
+            result.with_head.push((m1 + m2, t1 + t2));
+        }
+    }
+    result.without_head = pareto(&result.without_head);
+    result.with_head = pareto(&result.with_head);
+    return result;
+}
+
+/// 差分更新で向聴数を計算する
+///
+/// 手牌を色ごとに分け、それぞれの面子・搭子の組み合わせを保持しておく。
+/// 牌を1枚加えたり除いたりしたときは、その牌の色だけを計算し直す。
+/// 向聴数は`HandAnalyzer::calc`と同じく、七対子・国士無双・通常形のうち最小のものを返す。
+/// 副露は確定した面子として数え、手牌の集計には含めない。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand::*;
+/// use mahjong_rs::hand_info::incremental_analyzer::*;
+/// use mahjong_rs::tile::*;
+///
+/// let mut analyzer = IncrementalAnalyzer::new(&Hand::from("123m456p789s1122z"));
+/// assert_eq!(analyzer.shanten(), 0);
+///
+/// // 二字をツモって和了形になる
+/// analyzer.add(Tile::new(Tile::Z2));
+/// assert_eq!(analyzer.shanten(), -1);
+///
+/// // 一萬を切ると一向聴に戻る
+/// analyzer.remove(Tile::new(Tile::M1));
+/// assert_eq!(analyzer.shanten(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalAnalyzer {
+    /// 現在の手牌（副露を除く）の集計
+    summarized: TileSummarize,
+    /// 副露している牌の集計
+    melded: TileSummarize,
+    /// 副露の数
+    fixed: u32,
+    /// 色ごとの面子・搭子の組み合わせ
+    suits: [SuitPartial; SUIT_LEN],
+}
+
+impl IncrementalAnalyzer {
+    /// 手牌（副露・ツモ牌を含む）から作る
+    ///
+    /// 副露は確定した面子として数え、手牌の集計には含めない。
+    pub fn new(hand: &Hand) -> IncrementalAnalyzer {
+        let mut concealed = hand.summarize_tiles();
+        let mut melded: TileSummarize = [0; Tile::LEN];
+        for open in hand.opened() {
+            for tile in open.tiles.iter() {
+                concealed[tile.get() as usize] -= 1;
+                melded[tile.get() as usize] += 1;
+            }
+            // 槓子は3枚で表しているので、4枚目を数える
+            if open.category == OpenType::Kan {
+                melded[open.tiles[0].get() as usize] += 1;
+            }
+        }
+        let mut result = IncrementalAnalyzer::from_summarized(&concealed);
+        result.melded = melded;
+        result.fixed = hand.opened().len() as u32;
+        return result;
+    }
+
+    /// 種類別に集計した牌（副露なし）から作る
+    pub fn from_summarized(summarized: &TileSummarize) -> IncrementalAnalyzer {
+        let mut result = IncrementalAnalyzer {
+            summarized: *summarized,
+            melded: [0; Tile::LEN],
+            fixed: 0,
+            suits: Default::default(),
+        };
+        for suit in 0..SUIT_LEN {
+            result.suits[suit] = result.calc_suit(suit, &result.summarized);
+        }
+        return result;
+    }
+
+    /// 現在の手牌（副露を除く）の集計を返す
+    pub fn summarized(&self) -> &TileSummarize {
+        return &self.summarized;
+    }
+
+    /// 牌を1枚加える
+    /// # Panics
+    ///
+    /// 手牌と副露に同じ牌が既に4枚あるとき
+    pub fn add(&mut self, tile: Tile) {
+        let i = tile.get() as usize;
+        if self.summarized[i] + self.melded[i] >= 4 {
+            panic!("Cannot add the 5th tile to `IncrementalAnalyzer`!");
+        }
+        self.summarized[i] += 1;
+        let suit = suit_of(tile.get());
+        self.suits[suit] = self.calc_suit(suit, &self.summarized);
+    }
+
+    /// 牌を1枚除く
+    /// # Panics
+    ///
+    /// 手牌（副露を除く）にその牌がないとき
+    pub fn remove(&mut self, tile: Tile) {
+        let i = tile.get() as usize;
+        if self.summarized[i] == 0 {
+            panic!("Cannot remove a tile not in `IncrementalAnalyzer`!");
+        }
+        self.summarized[i] -= 1;
+        let suit = suit_of(tile.get());
+        self.suits[suit] = self.calc_suit(suit, &self.summarized);
+    }
+
+    /// 現在の向聴数を返す
+    pub fn shanten(&self) -> i32 {
+        return min(
+            calc_normal_shanten(&self.suits, self.fixed),
+            self.calc_special_shanten(&self.summarized),
+        );
+    }
+
+    /// 手牌にある牌をそれぞれ1枚切ったときの向聴数を返す
+    ///
+    /// 牌の種類ごとに1つずつ、昇順で返す。副露している牌は含まない。
+    /// 切る牌と同じ色の組み合わせだけを計算し直すため、`remove`と`add`を繰り返すより速い。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand::*;
+    /// use mahjong_rs::hand_info::incremental_analyzer::*;
+    /// use mahjong_rs::tile::*;
+    ///
+    /// let analyzer = IncrementalAnalyzer::new(&Hand::from("123m456p789s1122z 5z"));
+    /// let discards = analyzer.shanten_by_discard();
+    /// assert_eq!(discards.last(), Some(&(Tile::new(Tile::Z5), 0)));
+    /// assert_eq!(discards[0], (Tile::new(Tile::M1), 1));
+    /// ```
+    pub fn shanten_by_discard(&self) -> Vec<(Tile, i32)> {
+        let mut result: Vec<(Tile, i32)> = Vec::new();
+        let mut t = self.summarized;
+        let mut suits = self.suits.clone();
+        for i in Tile::M1..=Tile::Z7 {
+            if t[i as usize] == 0 {
+                continue;
+            }
+            let suit = suit_of(i);
+            t[i as usize] -= 1;
+            suits[suit] = self.calc_suit(suit, &t);
+            let shanten = min(
+                calc_normal_shanten(&suits, self.fixed),
+                self.calc_special_shanten(&t),
+            );
+            result.push((Tile::new(i), shanten));
+            t[i as usize] += 1;
+            suits[suit] = self.suits[suit].clone();
+        }
+        return result;
+    }
+
+    /// 受け入れ（向聴数が進む牌）を計算する
+    ///
+    /// `HandAnalyzer::calc_ukeire`と同じく、1枚加えたときに向聴数が減る牌の種類と、その残り枚数を返す。
+    /// 加える牌と同じ色の組み合わせだけを計算し直す。残り枚数は副露している牌も除いて数える。
+    pub fn ukeire(&self) -> Ukeire {
+        let shanten = self.shanten();
+        let mut result = Ukeire {
+            shanten,
+            tiles: Vec::new(),
+            count: 0,
+        };
+        let mut t = self.summarized;
+        let mut suits = self.suits.clone();
+        for i in Tile::M1..=Tile::Z7 {
+            if t[i as usize] + self.melded[i as usize] >= 4 {
+                continue;
+            }
+            let suit = suit_of(i);
+            t[i as usize] += 1;
+            suits[suit] = self.calc_suit(suit, &t);
+            let next = min(
+                calc_normal_shanten(&suits, self.fixed),
+                self.calc_special_shanten(&t),
+            );
+            if next < shanten {
+                result.tiles.push(Tile::new(i));
+                result.count += 4 - self.summarized[i as usize] - self.melded[i as usize];
+            }
+            t[i as usize] -= 1;
+            suits[suit] = self.suits[suit].clone();
+        }
+        return result;
+    }
+
+    /// 指定した色の組み合わせを計算する
+    fn calc_suit(&self, suit: usize, t: &TileSummarize) -> SuitPartial {
+        let begin = suit * 9;
+        let end = min(begin + 9, Tile::LEN);
+        let rule = if suit < 3 {
+            BlockRule::NUMBER
+        } else {
+            BlockRule::HONOR
+        };
+        return SuitPartial::calc_cached(&t[begin..end], rule);
+    }
+
+    /// 七対子・国士無双のうち小さい方の向聴数を計算する
+    ///
+    /// 副露していればどちらにもならないので、通常形より小さくならない値を返す。
+    fn calc_special_shanten(&self, t: &TileSummarize) -> i32 {
+        if self.fixed > 0 {
+            return i32::MAX;
+        }
+        return min(
+            HandAnalyzer::calc_seven_pairs(t),
+            HandAnalyzer::calc_thirteen_orphens(t),
+        );
+    }
+}
+
+/// 牌の種類から色（萬子`0`・筒子`1`・索子`2`・字牌`3`）を返す
+fn suit_of(tile: TileType) -> usize {
+    return (tile / 9) as usize;
+}
+
+/// 色ごとの組み合わせと副露の数から通常形の向聴数を計算する
+fn calc_normal_shanten(suits: &[SuitPartial; SUIT_LEN], fixed: u32) -> i32 {
+    let mut all = suits[0].clone();
+    for suit in suits.iter().skip(1) {
+        all = merge(&all, suit);
+    }
+    return calc_normal_shanten_with_fixed(&all, fixed);
+}
+
+/// 全ての色を合わせた組み合わせと、既に確定している面子の数から通常形の向聴数を計算する
+///
+/// 確定している面子（副露など）が4つを超える場合は考えない。
+pub(crate) fn calc_normal_shanten_with_fixed(all: &SuitPartial, fixed: u32) -> i32 {
+    let sets = 4 - min(fixed, 4);
+    let mut best = 0;
+    for (blocks, head) in all
+        .without_head
+        .iter()
+        .map(|b| (b, 0))
+        .chain(all.with_head.iter().map(|b| (b, 1)))
+    {
+        let m = min(blocks.0, sets);
+        let t = min(blocks.1, sets - m);
+        best = max(best, m * 2 + t + head);
+    }
+    return (sets * 2) as i32 - best as i32;
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_HANDS: [&str; 8] = [
+        "226699m99p228s66z 1z",
+        "19m19p11s1234567z 5m",
+        "123m444p789s1112z 2z",
+        "123567m234p6799s 5s",
+        "1357m2468p1357s1z 2z",
+        "123456789m1245p 6p",
+        "1112345678999m 5m",
+        "234m8s 567m 333p 456s 8s",
+    ];
+
+    #[test]
+    /// `HandAnalyzer::calc`と同じ向聴数になる
+    fn same_as_hand_analyzer() {
+        for hand_str in TEST_HANDS.iter() {
+            let hand = Hand::from(hand_str);
+            assert_eq!(
+                IncrementalAnalyzer::new(&hand).shanten(),
+                HandAnalyzer::calc(&hand).shanten,
+                "{}",
+                hand_str
+            );
+        }
+    }
+
+    #[test]
+    /// 加えて除いた後も`HandAnalyzer::calc`と同じ向聴数になる
+    fn add_and_remove() {
+        let mut analyzer = IncrementalAnalyzer::new(&Hand::from("1357m2468p1357s1z"));
+        let draws = [Tile::M2, Tile::P3, Tile::S2, Tile::Z1, Tile::M4, Tile::S6];
+        let discards = [Tile::Z1, Tile::M7, Tile::P8, Tile::S7, Tile::Z1, Tile::P2];
+        for (&draw, &discard) in draws.iter().zip(discards.iter()) {
+            analyzer.add(Tile::new(draw));
+            assert_eq!(
+                analyzer.shanten(),
+                HandAnalyzer::calc_summarized(analyzer.summarized()).shanten
+            );
+            analyzer.remove(Tile::new(discard));
+            assert_eq!(
+                analyzer.shanten(),
+                HandAnalyzer::calc_summarized(analyzer.summarized()).shanten
+            );
+        }
+    }
+
+    #[test]
+    /// 切った後の向聴数は1枚ずつ除いて計算したものと同じになる
+    fn shanten_by_discard() {
+        for hand_str in TEST_HANDS.iter() {
+            let analyzer = IncrementalAnalyzer::new(&Hand::from(hand_str));
+            for (tile, shanten) in analyzer.shanten_by_discard() {
+                let mut t = *analyzer.summarized();
+                t[tile.get() as usize] -= 1;
+                for (count, melded) in t.iter_mut().zip(analyzer.melded.iter()) {
+                    *count += melded;
+                }
+                assert_eq!(
+                    shanten,
+                    HandAnalyzer::calc_summarized(&t).shanten,
+                    "{} {}",
+                    hand_str,
+                    tile.to_string()
+                );
+            }
+        }
+    }
+
+    #[test]
+    /// 受け入れは`HandAnalyzer::calc_ukeire`と同じになる
+    fn same_ukeire_as_hand_analyzer() {
+        for hand_str in ["12m456p123789s12z", "1357m2468p1357s1z", "3456788m123p456s"].iter() {
+            let hand = Hand::from(hand_str);
+            assert_eq!(
+                IncrementalAnalyzer::new(&hand).ukeire(),
+                HandAnalyzer::calc_ukeire(&hand)
+            );
+        }
+    }
+
+    #[test]
+    /// 副露している牌は切る候補に含めず、確定した面子として数える
+    fn opened_hand() {
+        let hand = Hand::from("113m269p37s67z 666m 3s");
+        let analyzer = IncrementalAnalyzer::new(&hand);
+        // 副露した六萬を手牌の対子や搭子として使わない
+        assert_eq!(analyzer.shanten(), 4);
+        assert_eq!(analyzer.summarized().iter().sum::<u32>(), 11);
+        assert!(analyzer
+            .shanten_by_discard()
+            .iter()
+            .all(|&(tile, _)| tile != Tile::new(Tile::M6)));
+
+        let hand = Hand::from("1m 666m 123p 456s 789s 1m");
+        let analyzer = IncrementalAnalyzer::new(&hand);
+        assert_eq!(analyzer.shanten(), -1);
+        assert_eq!(
+            analyzer.shanten_by_discard(),
+            vec![(Tile::new(Tile::M1), 0)]
+        );
+
+        let hand = Hand::from("12m456p789s 666m");
+        assert_eq!(
+            IncrementalAnalyzer::new(&hand).ukeire(),
+            HandAnalyzer::calc_ukeire(&hand)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    /// 副露と合わせて5枚目になる牌は加えられない
+    fn add_fifth_tile_with_meld() {
+        let mut analyzer = IncrementalAnalyzer::new(&Hand::from("6m456p789s 666m"));
+        analyzer.add(Tile::new(Tile::M6));
+    }
+
+    #[test]
+    #[should_panic]
+    /// 持っていない牌は除けない
+    fn remove_tile_not_in_hand() {
+        let mut analyzer = IncrementalAnalyzer::new(&Hand::from("123m456p789s1122z"));
+        analyzer.remove(Tile::new(Tile::Z7));
+    }
+
+    #[test]
+    /// 覚えておく並びの数は上限を超えない
+    fn bounded_suit_cache() {
+        let mut counts = [0; 9];
+        let mut inserted = 0;
+        for n in 0.. {
+            let mut rest = n;
+            for c in counts.iter_mut() {
+                *c = rest % 5;
+                rest /= 5;
+            }
+            // 計算の軽い、8枚以下の並びだけを使う
+            if counts.iter().sum::<u32>() > 8 {
+                continue;
+            }
+            SuitPartial::calc_cached(&counts, BlockRule::NUMBER);
+            assert!(SUIT_CACHE.with(|cache| cache.borrow().len()) <= SUIT_CACHE_CAPACITY);
+            inserted += 1;
+            if inserted > SUIT_CACHE_CAPACITY {
+                break;
+            }
+        }
+        let cached = SUIT_CACHE.with(|cache| cache.borrow().len());
+        assert!(0 < cached && cached < SUIT_CACHE_CAPACITY);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::hand::Hand;
+use crate::hand_info::block::*;
+use crate::hand_info::decomposition::*;
+use crate::hand_info::win_context::*;
+use crate::hand_info::winning_hand::WinningHandForm;
+use crate::hand_info::yaku::*;
+use crate::rule::LocalYakuRules;
+use crate::tile::*;
+
+/// 解釈と和了の状況から、ローカル役が成立しているかを判定する関数
+type CheckFn = fn(&Decomposition, &WinContext) -> bool;
+
+/// 和了形の解釈から判定するローカル役と、その役が成立しているかを判定する関数
+///
+/// 十三不塔は和了形にならないため`thirteen_unrelated_decomposition`で別に判定する。
+const CHECKS: [(Yaku, CheckFn); 6] = [
+    (Yaku::BlessingOfMan, check_blessing_of_man),
+    (Yaku::BigWheels, check_big_wheels),
+    (
+        Yaku::ThreeConsecutiveTriplets,
+        check_three_consecutive_triplets,
+    ),
+    (Yaku::PureTripleChow, check_pure_triple_chow),
+    (Yaku::OpenRiichi, check_open_riichi),
+    (Yaku::SwallowReturn, check_swallow_return),
+];
+
+/// 手牌の1つの解釈について、ルールで採用しているローカル役とその翻数を返す
+pub fn evaluate(
+    decomposition: &Decomposition,
+    context: &WinContext,
+    rules: &LocalYakuRules,
+) -> Vec<AchievedYaku> {
+    return CHECKS
+        .iter()
+        .filter(|(_, check)| check(decomposition, context))
+        .filter_map(|&(yaku, _)| {
+            rules
+                .han(yaku, decomposition.closed)
+                .map(|han| AchievedYaku { yaku, han })
+        })
+        .collect();
+}
+
+/// 通常の役とローカル役をまとめる
+///
+/// 役満（翻数が`Yaku::YAKUMAN_HAN`以上）があれば役満だけを残し、一色三順があれば一盃口を除く。
+/// 結果は`Yaku`の順に並べる。
+pub fn merge(standard: Vec<AchievedYaku>, local: Vec<AchievedYaku>) -> Vec<AchievedYaku> {
+    let mut result = standard;
+    result.extend(local);
+    if result.iter().any(|a| a.yakuman_multiplier() > 0) {
+        result.retain(|a| a.yakuman_multiplier() > 0);
+    }
+    if result.iter().any(|a| a.yaku == Yaku::PureTripleChow) {
+        result.retain(|a| a.yaku != Yaku::OneSetOfIdenticalSequences);
+    }
+    result.sort_by_key(|a| a.yaku);
+    return result;
+}
+
+/// 十三不塔なら、その手牌を解釈として返す
+///
+/// 十三不塔は鳴きが入る前の第一ツモで、面子も搭子もなく対子が1つだけの形。
+/// 和了形ではないため、雀頭だけを持つ単騎待ちの解釈として扱う。
+pub fn thirteen_unrelated_decomposition(
+    hand: &Hand,
+    context: &WinContext,
+) -> Option<Decomposition> {
+    if !context.first_turn || !context.is_tsumo() || !hand.opened().is_empty() {
+        return None;
+    }
+    let t = hand.summarize_tiles();
+    if t.iter().any(|&c| c > 2) || t.iter().filter(|&&c| c == 2).count() != 1 {
+        return None;
+    }
+    // 同じ色で2つ以内に離れた数牌があれば搭子になる
+    for i in Tile::M1..Tile::Z1 {
+        for j in (i + 1)..(i + 3) {
+            if t[i as usize] > 0 && j < Tile::Z1 && j / 9 == i / 9 && t[j as usize] > 0 {
+                return None;
+            }
+        }
+    }
+    let head = (Tile::M1..=Tile::Z7).find(|&i| t[i as usize] == 2)?;
+    return Some(Decomposition {
+        form: WinningHandForm::Normal,
+        pairs: vec![Same2::new(Tile::new(head), Tile::new(head))],
+        same3: Vec::new(),
+        sequential3: Vec::new(),
+        wait: Wait::Tanki,
+        closed: true,
+        tiles: t,
+    });
+}
+
+/// 人和
+///
+/// 子が、鳴きが入る前の第一ツモより前にロンで和了したもの。
+fn check_blessing_of_man(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.first_turn && !context.is_tsumo() && !context.is_dealer();
+}
+/// 大車輪
+///
+/// 筒子の二から八の七対子。
+fn check_big_wheels(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.form == WinningHandForm::SevenPairs
+        && d.kinds() == (Tile::P2..=Tile::P8).collect::<Vec<TileType>>();
+}
+/// 三連刻
+///
+/// 同じ色で数字が1つずつ連続した3つの刻子。
+fn check_three_consecutive_triplets(d: &Decomposition, _context: &WinContext) -> bool {
+    let firsts: Vec<TileType> = d.same3.iter().map(|b| b.get()).collect();
+    return firsts.iter().any(|&i| {
+        i < Tile::Z1 && i % 9 <= 6 && firsts.contains(&(i + 1)) && firsts.contains(&(i + 2))
+    });
+}
+/// 一色三順
+///
+/// 同じ順子が3つ。
+fn check_pure_triple_chow(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.sequential3.iter().any(|block| {
+        d.sequential3
+            .iter()
+            .filter(|other| other.get() == block.get())
+            .count()
+            >= 3
+    });
+}
+/// オープン立直
+fn check_open_riichi(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.open_riichi && context.is_riichi();
+}
+/// 燕返し
+///
+/// 他家の立直宣言牌でロンしたもの。
+fn check_swallow_return(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.on_riichi_declaration && !context.is_tsumo();
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 全てのローカル役を採用して、いずれかの解釈で成立するローカル役を返す
+    fn local_yaku_of(hand_str: &str, context: &WinContext) -> Vec<Yaku> {
+        let hand = Hand::from(hand_str);
+        let mut result: Vec<Yaku> =
+            Decomposition::enumerate(&hand, context.winning_tile, context.method)
+                .iter()
+                .flat_map(|d| evaluate(d, context, &LocalYakuRules::all()))
+                .map(|a| a.yaku)
+                .collect();
+        result.sort();
+        result.dedup();
+        return result;
+    }
+
+    /// 南家・東場の状況を作る
+    fn context_of(tile: TileType, method: WinMethod) -> WinContext {
+        return WinContext::new(Tile::new(tile), method, Wind::South, Wind::East);
+    }
+
+    #[test]
+    /// 採用していないローカル役は成立しない
+    fn disabled_by_default() {
+        let hand = Hand::from("2233445566778p 8p");
+        let context = context_of(Tile::P8, WinMethod::Ron);
+        let d = &Decomposition::enumerate(&hand, context.winning_tile, context.method)[0];
+        assert!(evaluate(d, &context, &LocalYakuRules::default()).is_empty());
+    }
+
+    #[test]
+    /// 大車輪
+    fn big_wheels() {
+        let context = context_of(Tile::P8, WinMethod::Ron);
+        assert_eq!(
+            local_yaku_of("2233445566778p 8p", &context),
+            vec![Yaku::BigWheels]
+        );
+    }
+
+    #[test]
+    /// 三連刻と一色三順（刻子とも順子とも読める）
+    fn sets() {
+        let context = context_of(Tile::M9, WinMethod::Tsumo);
+        assert_eq!(
+            local_yaku_of("444555666s123p9m 9m", &context),
+            vec![Yaku::ThreeConsecutiveTriplets, Yaku::PureTripleChow]
+        );
+    }
+
+    #[test]
+    /// 人和・燕返し・オープン立直
+    fn situational() {
+        let mut context = context_of(Tile::M4, WinMethod::Ron);
+        context.first_turn = true;
+        context.on_riichi_declaration = true;
+        context.riichi = RiichiState::Riichi;
+        context.open_riichi = true;
+        assert_eq!(
+            local_yaku_of("123m456p789s55p23m 4m", &context),
+            vec![Yaku::BlessingOfMan, Yaku::OpenRiichi, Yaku::SwallowReturn]
+        );
+    }
+
+    #[test]
+    /// 十三不塔
+    fn thirteen_unrelated() {
+        let hand = Hand::from("147m258p369s1234z 1z");
+        let mut context = context_of(Tile::Z1, WinMethod::Tsumo);
+        assert!(thirteen_unrelated_decomposition(&hand, &context).is_none());
+        context.first_turn = true;
+        assert!(thirteen_unrelated_decomposition(&hand, &context).is_some());
+        // 搭子があれば成立しない
+        let hand = Hand::from("146m258p369s1234z 1z");
+        assert!(thirteen_unrelated_decomposition(&hand, &context).is_none());
+    }
+
+    #[test]
+    /// 役満があれば役満だけを残す
+    fn merge_yakuman() {
+        let standard = vec![AchievedYaku {
+            yaku: Yaku::SevenPairs,
+            han: 2,
+        }];
+        let local = vec![AchievedYaku {
+            yaku: Yaku::BigWheels,
+            han: 13,
+        }];
+        assert_eq!(
+            merge(standard, local),
+            vec![AchievedYaku {
+                yaku: Yaku::BigWheels,
+                han: 13
+            }]
+        );
+    }
+}
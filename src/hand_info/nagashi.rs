@@ -0,0 +1,149 @@
+use crate::hand_info::river::River;
+use crate::hand_info::score::*;
+use crate::hand_info::win_context::WinMethod;
+use crate::rule::*;
+
+/// 流し満貫の支払い
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct NagashiPayment {
+    /// 流し満貫が成立したプレイヤーの席（`0`〜`3`）
+    pub player: usize,
+    /// 他家からの支払い（満貫のツモ和了と同じ）
+    pub payment: Payment,
+}
+
+impl NagashiPayment {
+    /// 各席の点数の増減を返す
+    ///
+    /// `dealer`は親の席。
+    pub fn deltas(&self, dealer: usize) -> [i32; 4] {
+        return self.payment.deltas(self.player, dealer, None);
+    }
+}
+
+/// 流し満貫が成立しているか否かを返す
+///
+/// 荒牌流局（牌山が尽きた流局）で、捨て牌が全て么九牌であり、どの捨て牌も鳴かれていなければ成立する。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand_info::nagashi::*;
+/// use mahjong_rs::hand_info::river::*;
+/// use mahjong_rs::tile::*;
+///
+/// let mut river = River::new();
+/// for &i in [Tile::M1, Tile::Z1, Tile::P9, Tile::Z7].iter() {
+///     river.discard(Tile::new(i));
+/// }
+/// assert!(check_nagashi_mangan(&river, true));
+/// assert!(!check_nagashi_mangan(&river, false));
+/// river.mark_last_called();
+/// assert!(!check_nagashi_mangan(&river, true));
+/// ```
+pub fn check_nagashi_mangan(river: &River, exhaustive_draw: bool) -> bool {
+    return exhaustive_draw
+        && !river.is_empty()
+        && river
+            .discards()
+            .iter()
+            .all(|d| d.tile.is_1_9_honor() && !d.called);
+}
+
+/// 4人の河から流し満貫の支払いを求める
+///
+/// 席は手番の順に`0`〜`3`で、`dealer`は親の席。
+/// 流し満貫を認めないルールや荒牌流局でなければ空を返す。
+/// 複数人で成立したときは`Ruleset::multiple_nagashi`に従う。
+pub fn settle_nagashi_mangan(
+    rivers: &[River; 4],
+    dealer: usize,
+    exhaustive_draw: bool,
+    ruleset: &Ruleset,
+) -> Vec<NagashiPayment> {
+    if !ruleset.nagashi_mangan {
+        return Vec::new();
+    }
+    let mangan = Score::new(5, 0, 0, &ruleset.limit_rule());
+    let mut result: Vec<NagashiPayment> = (0..4)
+        .map(|i| (dealer + i) % 4)
+        .filter(|&seat| check_nagashi_mangan(&rivers[seat], exhaustive_draw))
+        .map(|seat| NagashiPayment {
+            player: seat,
+            payment: mangan
+                .settle(seat == dealer, WinMethod::Tsumo, 0, 0)
+                .payment,
+        })
+        .collect();
+    if ruleset.multiple_nagashi == MultipleNagashi::FirstInTurnOrder {
+        result.truncate(1);
+    }
+    return result;
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::*;
+
+    /// 牌の種類から河を作る
+    fn river_of(tiles: &[TileType]) -> River {
+        let mut river = River::new();
+        for &i in tiles {
+            river.discard(Tile::new(i));
+        }
+        return river;
+    }
+
+    #[test]
+    /// 中張牌を捨てていれば成立しない
+    fn simple_tile_discarded() {
+        let river = river_of(&[Tile::M1, Tile::M2]);
+        assert!(!check_nagashi_mangan(&river, true));
+    }
+
+    #[test]
+    /// 子の流し満貫は満貫のツモ和了と同じ支払い
+    fn non_dealer_payment() {
+        let rivers = [
+            river_of(&[Tile::M5]),
+            river_of(&[Tile::Z1, Tile::S9]),
+            river_of(&[Tile::P5]),
+            river_of(&[Tile::S5]),
+        ];
+        let result = settle_nagashi_mangan(&rivers, 0, true, &Ruleset::tenhou());
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].player, 1);
+        assert_eq!(
+            result[0].payment,
+            Payment::NonDealerTsumo {
+                dealer: 4000,
+                non_dealer: 2000
+            }
+        );
+        assert_eq!(result[0].deltas(0), [-4000, 8000, -2000, -2000]);
+        assert!(settle_nagashi_mangan(&rivers, 0, false, &Ruleset::tenhou()).is_empty());
+        assert!(settle_nagashi_mangan(&rivers, 0, true, &Ruleset::ema()).is_empty());
+    }
+
+    #[test]
+    /// 複数人で成立したときの扱い
+    fn multiple() {
+        let rivers = [
+            river_of(&[Tile::M5]),
+            river_of(&[Tile::Z1]),
+            river_of(&[Tile::P5]),
+            river_of(&[Tile::Z2]),
+        ];
+        let mut ruleset = Ruleset::tenhou();
+        let result = settle_nagashi_mangan(&rivers, 2, true, &ruleset);
+        assert_eq!(
+            result.iter().map(|r| r.player).collect::<Vec<usize>>(),
+            vec![3, 1]
+        );
+        ruleset.multiple_nagashi = MultipleNagashi::FirstInTurnOrder;
+        let result = settle_nagashi_mangan(&rivers, 2, true, &ruleset);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].player, 3);
+    }
+}
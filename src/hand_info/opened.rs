@@ -26,8 +26,19 @@ pub enum OpenFrom {
     Unknown,
 }
 
+/// 槓の種類
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum KanType {
+    /// 暗槓
+    Closed,
+    /// 大明槓（他家の捨て牌で槓した）
+    Open,
+    /// 加槓（ポンに自分の牌を加えた）。`melds`は加槓した時点の副露の数
+    Added { melds: usize },
+}
+
 /// 副露状態を表す構造体
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct OpenTiles {
     /// 3枚の牌が入る。カンした時も3枚（4枚目は自明）
     pub tiles: [Tile; 3],
@@ -35,4 +46,6 @@ pub struct OpenTiles {
     pub category: OpenType,
     /// 誰から副露したか
     pub from: OpenFrom,
+    /// 槓の種類（槓でなければ`None`）
+    pub kan: Option<KanType>,
 }
@@ -0,0 +1,391 @@
+use crate::hand::Hand;
+use crate::hand_info::opened::*;
+use crate::hand_info::score::Score;
+use crate::hand_info::yaku::*;
+
+/// 責任払い（包）
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Liability {
+    /// 責任払いの対象になる役満
+    pub yaku: Yaku,
+    /// 責任払いをするプレイヤーの席（`0`〜`3`）
+    pub liable: usize,
+}
+
+/// 副露した相手の席を返す
+///
+/// 席は手番の順に`0`〜`3`で、上家は1つ前、下家は1つ後の席。相手が分からなければ`None`を返す。
+pub fn seat_of(from: OpenFrom, seat: usize) -> Option<usize> {
+    return match from {
+        OpenFrom::Previous => Some((seat + 3) % 4),
+        OpenFrom::Following => Some((seat + 1) % 4),
+        OpenFrom::Opposite => Some((seat + 2) % 4),
+        OpenFrom::Myself | OpenFrom::Unknown => None,
+    };
+}
+
+/// 手牌の副露から責任払いを求める
+///
+/// 大三元の3つ目の三元牌、大四喜の4つ目の風牌、四槓子の4つ目の槓子（大明槓のみ）を他家から鳴かせた
+/// プレイヤーが責任払いをする。副露は鳴いた順に並んでいるものとし、加槓の順は`KanType::Added`に従う。
+/// `seat`は手牌の持ち主の席。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand::*;
+/// use mahjong_rs::hand_info::opened::*;
+/// use mahjong_rs::hand_info::pao::*;
+/// use mahjong_rs::hand_info::yaku::*;
+/// use mahjong_rs::tile::*;
+///
+/// let pon = |i: TileType, from: OpenFrom| OpenTiles {
+///     tiles: [Tile::new(i); 3],
+///     category: OpenType::Pon,
+///     from,
+///     kan: None,
+/// };
+/// let hand = Hand::new_with_opened(
+///     [Tile::M1, Tile::M2, Tile::M3, Tile::P4].iter().map(|&i| Tile::new(i)).collect(),
+///     vec![
+///         pon(Tile::Z5, OpenFrom::Previous),
+///         pon(Tile::Z6, OpenFrom::Opposite),
+///         pon(Tile::Z7, OpenFrom::Following),
+///     ],
+///     Some(Tile::new(Tile::P4)),
+/// );
+/// // 席2の手牌で、下家（席3）が3つ目の三元牌を鳴かせた
+/// assert_eq!(
+///     find_liabilities(&hand, 2),
+///     vec![Liability { yaku: Yaku::BigThreeDragons, liable: 3 }]
+/// );
+/// ```
+pub fn find_liabilities(hand: &Hand, seat: usize) -> Vec<Liability> {
+    let mut result = Vec::new();
+    let triplets = |open: &&OpenTiles| open.category != OpenType::Chi;
+    let dragons: Vec<&OpenTiles> = hand
+        .opened()
+        .iter()
+        .filter(triplets)
+        .filter(|open| open.tiles[0].is_dragon())
+        .collect();
+    let winds: Vec<&OpenTiles> = hand
+        .opened()
+        .iter()
+        .filter(triplets)
+        .filter(|open| open.tiles[0].is_wind())
+        .collect();
+    // 暗槓・加槓は自分の牌で槓するので、4つ目が大明槓のときだけ責任払いになる
+    let kans = hand
+        .opened()
+        .iter()
+        .enumerate()
+        .filter(|(_, open)| open.category == OpenType::Kan);
+    let fourth_kan: Vec<&OpenTiles> = match kans.clone().count() {
+        4 => kans
+            .max_by_key(|&(i, open)| kan_order(i, open))
+            .map(|(_, open)| open)
+            .filter(|open| open.kan == Some(KanType::Open))
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    };
+    for (yaku, melds, count) in [
+        (Yaku::BigThreeDragons, dragons, 3),
+        (Yaku::BigFourWinds, winds, 4),
+        (Yaku::FourKans, fourth_kan, 1),
+    ] {
+        if melds.len() != count {
+            continue;
+        }
+        if let Some(liable) = seat_of(melds[count - 1].from, seat) {
+            result.push(Liability { yaku, liable });
+        }
+    }
+    return result;
+}
+
+/// 槓した順を比べるための値を返す
+///
+/// `i`番目の副露を鳴いた（暗槓した）時点を`2 * i + 1`、副露が`n`個の時点の加槓を`2 * n`とする。
+fn kan_order(i: usize, open: &OpenTiles) -> usize {
+    return match open.kan {
+        Some(KanType::Added { melds }) => 2 * melds,
+        _ => 2 * i + 1,
+    };
+}
+
+/// 責任払いを含めて、和了による各席の点数の増減を返す
+///
+/// `yaku`は成立した役（`Ruleset`による調整後）、`winner`は和了者の席、`dealer`は親の席、
+/// `discarder`は放銃者の席（ツモなら`None`）。
+/// 責任払いの対象の役満の分は、ツモなら責任者が全額を、ロンなら責任者と放銃者が半分ずつ支払う。
+/// それ以外の役満の分は通常通りに支払い、本場はツモなら責任者が、ロンなら放銃者が支払う。
+/// 責任払いの対象の役満がなければ、本場も通常通りに支払う。
+/// 供託された立直棒は和了者が受け取る。
+pub fn settle_with_liabilities(
+    yaku: &[AchievedYaku],
+    liabilities: &[Liability],
+    winner: usize,
+    dealer: usize,
+    discarder: Option<usize>,
+    honba: u32,
+    riichi_sticks: u32,
+) -> [i32; 4] {
+    let is_dealer = winner == dealer;
+    let points_of = |yakuman: u32| (8000 * yakuman * if is_dealer { 6 } else { 4 }) as i32;
+    let honba_points = (Score::HONBA_POINTS * honba) as i32;
+    let mut deltas = [0; 4];
+    let mut pay = |seat: usize, points: i32| {
+        deltas[seat] -= points;
+        deltas[winner] += points;
+    };
+
+    // 責任払いの対象の役満と、それ以外の役満
+    let mut rest = 0;
+    let mut liable_parts: Vec<(usize, i32)> = Vec::new();
+    for achieved in yaku {
+        match liabilities.iter().find(|l| l.yaku == achieved.yaku) {
+            Some(l) => liable_parts.push((l.liable, points_of(achieved.yakuman_multiplier()))),
+            None => rest += achieved.yakuman_multiplier(),
+        }
+    }
+
+    match discarder {
+        Some(discarder) => {
+            for &(liable, points) in liable_parts.iter() {
+                if liable == discarder {
+                    pay(discarder, points);
+                } else {
+                    pay(liable, points / 2);
+                    pay(discarder, points - points / 2);
+                }
+            }
+            pay(discarder, points_of(rest) + honba_points);
+        }
+        None => {
+            for &(liable, points) in liable_parts.iter() {
+                pay(liable, points);
+            }
+            // 残りは通常のツモ和了と同じく分担する
+            let base = (8000 * rest) as i32;
+            for seat in (0..4).filter(|&s| s != winner) {
+                let share = if is_dealer || seat == dealer {
+                    base * 2
+                } else {
+                    base
+                };
+                pay(seat, share);
+            }
+            match liable_parts.first() {
+                Some(&(liable, _)) => pay(liable, honba_points),
+                None => {
+                    for seat in (0..4).filter(|&s| s != winner) {
+                        pay(seat, honba_points / 3);
+                    }
+                }
+            }
+        }
+    }
+    deltas[winner] += (Score::RIICHI_STICK_POINTS * riichi_sticks) as i32;
+    return deltas;
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::*;
+
+    /// ポンを作る
+    fn pon(i: TileType, from: OpenFrom) -> OpenTiles {
+        return OpenTiles {
+            tiles: [Tile::new(i); 3],
+            category: OpenType::Pon,
+            from,
+            kan: None,
+        };
+    }
+
+    /// 槓子を作る
+    fn kan(i: TileType, kan: KanType, from: OpenFrom) -> OpenTiles {
+        return OpenTiles {
+            tiles: [Tile::new(i); 3],
+            category: OpenType::Kan,
+            from,
+            kan: Some(kan),
+        };
+    }
+
+    /// 役満を作る
+    fn yakuman(yaku: Yaku, multiplier: u32) -> AchievedYaku {
+        return AchievedYaku {
+            yaku,
+            han: Yaku::YAKUMAN_HAN * multiplier,
+        };
+    }
+
+    #[test]
+    /// 最後の副露が他家からでなければ責任払いにならない
+    fn no_liability() {
+        let hand = Hand::new_with_opened(
+            Vec::new(),
+            vec![
+                pon(Tile::Z5, OpenFrom::Previous),
+                kan(Tile::Z6, KanType::Closed, OpenFrom::Myself),
+            ],
+            None,
+        );
+        assert!(find_liabilities(&hand, 0).is_empty());
+    }
+
+    #[test]
+    /// 四槓子の4つ目の大明槓
+    fn four_kans() {
+        let hand = Hand::new_with_opened(
+            Vec::new(),
+            vec![
+                kan(Tile::M1, KanType::Closed, OpenFrom::Myself),
+                kan(Tile::M2, KanType::Closed, OpenFrom::Myself),
+                kan(Tile::M3, KanType::Open, OpenFrom::Previous),
+                kan(Tile::M4, KanType::Open, OpenFrom::Opposite),
+            ],
+            None,
+        );
+        assert_eq!(
+            find_liabilities(&hand, 1),
+            vec![Liability {
+                yaku: Yaku::FourKans,
+                liable: 3
+            }]
+        );
+    }
+
+    #[test]
+    /// 4つ目が加槓なら責任払いにならない
+    fn four_kans_with_added_kan() {
+        // 暗槓を3回した後に、対面からのポンを加槓した
+        let hand = Hand::new_with_opened(
+            Vec::new(),
+            vec![
+                kan(Tile::M5, KanType::Added { melds: 4 }, OpenFrom::Opposite),
+                kan(Tile::M1, KanType::Closed, OpenFrom::Myself),
+                kan(Tile::M2, KanType::Closed, OpenFrom::Myself),
+                kan(Tile::M3, KanType::Closed, OpenFrom::Myself),
+            ],
+            None,
+        );
+        assert!(find_liabilities(&hand, 0).is_empty());
+
+        // 大明槓の後に加槓した
+        let hand = Hand::new_with_opened(
+            Vec::new(),
+            vec![
+                kan(Tile::M5, KanType::Added { melds: 4 }, OpenFrom::Opposite),
+                kan(Tile::M1, KanType::Closed, OpenFrom::Myself),
+                kan(Tile::M3, KanType::Open, OpenFrom::Previous),
+                kan(Tile::M2, KanType::Closed, OpenFrom::Myself),
+            ],
+            None,
+        );
+        assert!(find_liabilities(&hand, 0).is_empty());
+
+        // 加槓の後に大明槓した
+        let hand = Hand::new_with_opened(
+            Vec::new(),
+            vec![
+                kan(Tile::M5, KanType::Added { melds: 3 }, OpenFrom::Opposite),
+                kan(Tile::M1, KanType::Closed, OpenFrom::Myself),
+                kan(Tile::M2, KanType::Closed, OpenFrom::Myself),
+                kan(Tile::M3, KanType::Open, OpenFrom::Previous),
+            ],
+            None,
+        );
+        assert_eq!(
+            find_liabilities(&hand, 0),
+            vec![Liability {
+                yaku: Yaku::FourKans,
+                liable: 3
+            }]
+        );
+    }
+
+    #[test]
+    /// 責任払いの対象の役満がなければ、ツモの本場は3人で支払う
+    fn tsumo_without_liable_yaku() {
+        let liabilities = [Liability {
+            yaku: Yaku::BigFourWinds,
+            liable: 1,
+        }];
+        let deltas = settle_with_liabilities(
+            &[yakuman(Yaku::BigThreeDragons, 1)],
+            &liabilities,
+            2,
+            0,
+            None,
+            1,
+            0,
+        );
+        assert_eq!(deltas, [-16100, -8100, 32300, -8100]);
+    }
+
+    #[test]
+    /// ツモなら責任者が全額を支払う
+    fn tsumo() {
+        let liabilities = [Liability {
+            yaku: Yaku::BigThreeDragons,
+            liable: 1,
+        }];
+        let deltas = settle_with_liabilities(
+            &[yakuman(Yaku::BigThreeDragons, 1)],
+            &liabilities,
+            2,
+            0,
+            None,
+            1,
+            1,
+        );
+        assert_eq!(deltas, [0, -32300, 33300, 0]);
+    }
+
+    #[test]
+    /// 他家からのロンなら責任者と放銃者が半分ずつ支払う
+    fn ron_by_other() {
+        let liabilities = [Liability {
+            yaku: Yaku::BigThreeDragons,
+            liable: 1,
+        }];
+        let deltas = settle_with_liabilities(
+            &[yakuman(Yaku::BigThreeDragons, 1)],
+            &liabilities,
+            2,
+            0,
+            Some(3),
+            0,
+            0,
+        );
+        assert_eq!(deltas, [0, -16000, 32000, -16000]);
+    }
+
+    #[test]
+    /// 他の役満と複合したツモでは、責任払いの対象の分だけを責任者が支払う
+    fn combined_tsumo() {
+        let liabilities = [Liability {
+            yaku: Yaku::BigThreeDragons,
+            liable: 1,
+        }];
+        let deltas = settle_with_liabilities(
+            &[
+                yakuman(Yaku::BigThreeDragons, 1),
+                yakuman(Yaku::AllHonors, 1),
+            ],
+            &liabilities,
+            0,
+            0,
+            None,
+            0,
+            0,
+        );
+        // 親の役満は48000点。大三元の分は責任者が全額、字一色の分は16000点ずつ
+        assert_eq!(deltas, [96000, -64000, -16000, -16000]);
+    }
+}
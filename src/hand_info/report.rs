@@ -0,0 +1,435 @@
+use crate::hand::Hand;
+use crate::hand_info::decomposition::*;
+use crate::hand_info::fu::FuItem;
+use crate::hand_info::pao::*;
+use crate::hand_info::score::Limit;
+use crate::hand_info::scorer::ScoreResult;
+use crate::hand_info::win_context::*;
+use crate::hand_info::winning_hand::WinningHandForm;
+use crate::hand_info::yaku::Yaku;
+use crate::locale::*;
+use crate::tile::*;
+use serde::Serialize;
+
+const HAND: Term = Term::new("手牌", "Hand", "Tehai");
+const WINNING_TILE: Term = Term::new("和了牌", "Winning tile", "Agarihai");
+const TSUMO: Term = Term::new("ツモ", "Tsumo", "Tsumo");
+const RON: Term = Term::new("ロン", "Ron", "Ron");
+const BLOCKS: Term = Term::new("面子", "Blocks", "Mentsu");
+const WAIT: Term = Term::new("待ち", "Wait", "Machi");
+const YAKU: Term = Term::new("役", "Yaku", "Yaku");
+const DORA: Term = Term::new("ドラ", "Dora", "Dora");
+const URA_DORA: Term = Term::new("裏ドラ", "Ura dora", "Uradora");
+const RED_DORA: Term = Term::new("赤ドラ", "Red dora", "Akadora");
+const FU: Term = Term::new("符", "Fu", "Fu");
+const TOTAL: Term = Term::new("合計", "Total", "Goukei");
+const PAYMENTS: Term = Term::new("支払い", "Payments", "Shiharai");
+const SEAT: Term = Term::new("席", "Seat ", "Seki ");
+
+/// 和了に関わる席
+///
+/// 席は手番の順に`0`〜`3`。
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct WinSeats {
+    /// 和了者の席
+    pub winner: usize,
+    /// 親の席
+    pub dealer: usize,
+    /// 放銃者の席（ツモなら`None`）
+    pub discarder: Option<usize>,
+}
+
+/// 成立した役の1行
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct YakuLine {
+    /// 役の名前
+    pub name: String,
+    /// 翻数（役満は`Yaku::YAKUMAN_HAN`の倍数）
+    pub han: u32,
+}
+
+/// 符の内訳の1行
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct FuLine {
+    /// 項目の名前
+    pub name: String,
+    /// 符
+    pub fu: u32,
+}
+
+/// 和了の点数の内訳
+///
+/// 名前は作るときに指定した言語で持ち、`to_text`で同じ言語の文章にする。
+/// 構造化したデータとして`serde`で書き出すこともできる。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand::*;
+/// use mahjong_rs::hand_info::report::*;
+/// use mahjong_rs::hand_info::scorer::*;
+/// use mahjong_rs::hand_info::win_context::*;
+/// use mahjong_rs::locale::*;
+/// use mahjong_rs::rule::*;
+/// use mahjong_rs::tile::*;
+///
+/// let hand = Hand::from("123m456p789s11s12p 3p");
+/// let context = WinContext::new(Tile::new(Tile::P3), WinMethod::Tsumo, Wind::South, Wind::East);
+/// let result = score(&hand, &context, &Ruleset::default()).unwrap();
+/// let seats = WinSeats { winner: 1, dealer: 0, discarder: None };
+/// let report = ScoreReport::new(&hand, &context, &result, &seats, 0, Locale::English);
+/// // 30符1翻ツモは300点・500点
+/// assert_eq!(report.deltas, [-500, 1100, -300, -300]);
+/// assert!(report.to_text().contains("Self pick: 1 han"));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct ScoreReport {
+    /// 表示に使う言語
+    #[serde(skip)]
+    pub locale: Locale,
+    /// 副露を含む手牌
+    pub hand: String,
+    /// 和了牌
+    pub winning_tile: String,
+    /// ツモ和了か
+    pub tsumo: bool,
+    /// 採用した解釈の面子・雀頭
+    pub blocks: Vec<String>,
+    /// 待ちの形
+    pub wait: String,
+    /// 成立した役
+    pub yaku: Vec<YakuLine>,
+    /// ドラの数
+    pub dora: u32,
+    /// 裏ドラの数
+    pub ura_dora: u32,
+    /// 赤ドラの数
+    pub red_dora: u32,
+    /// 符の内訳
+    pub fu: Vec<FuLine>,
+    /// 切り上げた符
+    pub total_fu: u32,
+    /// ドラを含む翻数（役満では`0`）
+    pub total_han: u32,
+    /// 満貫以上の点数の区分の名前
+    pub limit: Option<String>,
+    /// 各席の点数の増減（本場・供託・責任払いを含む）
+    pub deltas: [i32; 4],
+}
+
+impl ScoreReport {
+    /// 点数計算の結果から内訳を作る
+    ///
+    /// 本場は`context.honba`を使い、`riichi_sticks`は供託された立直棒の本数。
+    /// 大三元・大四喜・四槓子の責任払いがあれば、支払いに反映する。
+    pub fn new(
+        hand: &Hand,
+        context: &WinContext,
+        result: &ScoreResult,
+        seats: &WinSeats,
+        riichi_sticks: u32,
+        locale: Locale,
+    ) -> ScoreReport {
+        let chosen = &result.chosen;
+        let d = &chosen.decomposition;
+        // 役満ではドラを数えない
+        let (dora, ura_dora, red_dora) = if chosen.dora == 0 {
+            (0, 0, 0)
+        } else {
+            (
+                context.count_dora(hand),
+                context.count_ura_dora(hand),
+                hand.count_red_dora(),
+            )
+        };
+        return ScoreReport {
+            locale,
+            hand: hand.to_short_string(),
+            winning_tile: context.winning_tile.to_string(),
+            tsumo: context.is_tsumo(),
+            blocks: blocks_of(d),
+            wait: FuItem::Wait(d.wait).name(locale).to_string(),
+            yaku: chosen
+                .yaku
+                .iter()
+                .map(|a| YakuLine {
+                    name: a.yaku.localized_name(locale).to_string(),
+                    han: a.han,
+                })
+                .collect(),
+            dora,
+            ura_dora,
+            red_dora,
+            fu: chosen
+                .fu
+                .items
+                .iter()
+                .map(|&(item, fu)| FuLine {
+                    name: fu_line_name(item, locale),
+                    fu,
+                })
+                .collect(),
+            total_fu: chosen.fu.value,
+            total_han: chosen.score.han,
+            limit: chosen.score.limit.name(locale),
+            deltas: win_deltas(hand, context, result, seats, riichi_sticks),
+        };
+    }
+
+    /// 内訳を文章にする
+    pub fn to_text(&self) -> String {
+        let locale = self.locale;
+        let label = |term: Term| term.get(locale);
+        let method = if self.tsumo { TSUMO } else { RON };
+        let mut lines = vec![
+            format!("{}: {}", label(HAND), self.hand),
+            format!(
+                "{}: {} ({})",
+                label(WINNING_TILE),
+                self.winning_tile,
+                label(method)
+            ),
+            format!(
+                "{}: {} ({}: {})",
+                label(BLOCKS),
+                self.blocks.join(" "),
+                label(WAIT),
+                self.wait
+            ),
+            format!("{}:", label(YAKU)),
+        ];
+        for line in self.yaku.iter() {
+            let han = match line.han / Yaku::YAKUMAN_HAN {
+                0 => han_text(line.han, locale),
+                n => Limit::Yakuman(n).name(locale).unwrap_or_default(),
+            };
+            lines.push(format!("  {}: {}", line.name, han));
+        }
+        lines.push(format!(
+            "{}: {} / {}: {} / {}: {}",
+            label(DORA),
+            self.dora,
+            label(URA_DORA),
+            self.ura_dora,
+            label(RED_DORA),
+            self.red_dora
+        ));
+        lines.push(format!("{}:", label(FU)));
+        for line in self.fu.iter() {
+            lines.push(format!("  {}: {}", line.name, fu_text(line.fu, locale)));
+        }
+        let mut total = Vec::new();
+        if self.total_han > 0 {
+            total.push(fu_text(self.total_fu, locale));
+            total.push(han_text(self.total_han, locale));
+        }
+        total.extend(self.limit.clone());
+        lines.push(format!("{}: {}", label(TOTAL), total.join(" ")));
+        lines.push(format!("{}:", label(PAYMENTS)));
+        for (seat, delta) in self.deltas.iter().enumerate() {
+            lines.push(format!("  {}{}: {:+}", label(SEAT), seat, delta));
+        }
+        return lines.join("\n");
+    }
+}
+
+/// 翻数を文字列にする
+fn han_text(han: u32, locale: Locale) -> String {
+    return match locale {
+        Locale::Japanese => format!("{}翻", han),
+        Locale::English | Locale::Romaji => format!("{} han", han),
+    };
+}
+
+/// 符を文字列にする
+fn fu_text(fu: u32, locale: Locale) -> String {
+    return match locale {
+        Locale::Japanese => format!("{}符", fu),
+        Locale::English | Locale::Romaji => format!("{} fu", fu),
+    };
+}
+
+/// 符の内訳の項目の名前に、対象の牌を添える
+fn fu_line_name(item: FuItem, locale: Locale) -> String {
+    return match item {
+        FuItem::Triplet { tile, .. } | FuItem::HonorPair(tile) => {
+            format!("{} ({})", item.name(locale), Tile::new(tile).to_string())
+        }
+        _ => item.name(locale).to_string(),
+    };
+}
+
+/// 同じ色の牌の並びを`123m`のような文字列にする
+fn render(tiles: &[TileType]) -> String {
+    let suit = Tile::new(tiles[0]).to_string().pop().unwrap_or_default();
+    let mut result: String = tiles
+        .iter()
+        .map(|&i| Tile::new(i).to_string().remove(0))
+        .collect();
+    result.push(suit);
+    return result;
+}
+
+/// 解釈の面子・雀頭を文字列にする
+///
+/// 面子を牌の順に並べ、雀頭（七対子では対子）を最後に置く。
+/// 国士無双は色ごとに牌を並べる。
+fn blocks_of(d: &Decomposition) -> Vec<String> {
+    if d.form == WinningHandForm::ThirteenOrphens {
+        return (0..4)
+            .filter_map(|suit| {
+                let tiles: Vec<TileType> = (suit * 9..(suit * 9 + 9).min(Tile::LEN as TileType))
+                    .flat_map(|i| std::iter::repeat_n(i, d.tiles[i as usize] as usize))
+                    .collect();
+                if tiles.is_empty() {
+                    None
+                } else {
+                    Some(render(&tiles))
+                }
+            })
+            .collect();
+    }
+    let mut sets: Vec<(TileType, String)> = d
+        .sequential3
+        .iter()
+        .map(|b| (b.get(), render(&[b.get(), b.get() + 1, b.get() + 2])))
+        .chain(d.same3.iter().map(|b| {
+            let count = if b.kan { 4 } else { 3 };
+            (b.get(), render(&vec![b.get(); count]))
+        }))
+        .collect();
+    sets.sort();
+    let mut result: Vec<String> = sets.into_iter().map(|(_, s)| s).collect();
+    result.extend(d.pairs.iter().map(|b| render(&[b.get(), b.get()])));
+    return result;
+}
+
+/// 和了による各席の点数の増減を求める
+///
+/// 包の責任がある役満を和了していれば責任払いにする。`riichi_sticks`の供託は和了者が受け取る。
+pub fn win_deltas(
+    hand: &Hand,
+    context: &WinContext,
+    result: &ScoreResult,
+    seats: &WinSeats,
+    riichi_sticks: u32,
+) -> [i32; 4] {
+    let chosen = &result.chosen;
+    let liabilities: Vec<Liability> = find_liabilities(hand, seats.winner)
+        .into_iter()
+        .filter(|l| chosen.yaku.iter().any(|a| a.yaku == l.yaku))
+        .collect();
+    if !liabilities.is_empty() {
+        return settle_with_liabilities(
+            &chosen.yaku,
+            &liabilities,
+            seats.winner,
+            seats.dealer,
+            seats.discarder,
+            context.honba,
+            riichi_sticks,
+        );
+    }
+    let settlement = chosen.score.settle(
+        seats.winner == seats.dealer,
+        context.method,
+        context.honba,
+        riichi_sticks,
+    );
+    let mut deltas = settlement
+        .payment
+        .deltas(seats.winner, seats.dealer, seats.discarder);
+    deltas[seats.winner] += settlement.deposit as i32;
+    return deltas;
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_info::opened::*;
+    use crate::hand_info::scorer::score;
+    use crate::rule::Ruleset;
+
+    #[test]
+    /// 日本語の内訳
+    fn japanese_text() {
+        let hand = Hand::from("789m456p789s55p13m 2m");
+        let mut context =
+            WinContext::new(Tile::new(Tile::M2), WinMethod::Ron, Wind::South, Wind::East);
+        context.riichi = RiichiState::Riichi;
+        context.dora_indicators = vec![Tile::new(Tile::S8)];
+        context.honba = 1;
+        let result = score(&hand, &context, &Ruleset::default()).unwrap();
+        let seats = WinSeats {
+            winner: 1,
+            dealer: 0,
+            discarder: Some(3),
+        };
+        let report = ScoreReport::new(&hand, &context, &result, &seats, 1, Locale::Japanese);
+        assert_eq!(report.blocks, vec!["123m", "789m", "456p", "789s", "55p"]);
+        assert_eq!(report.dora, 1);
+        // 40符2翻は2600点。本場300点と供託1000点を加える
+        assert_eq!(report.deltas, [0, 3900, 0, -2900]);
+        assert_eq!(
+            report.to_text(),
+            [
+                "手牌: 13789m45556p789s 2m",
+                "和了牌: 2m (ロン)",
+                "面子: 123m 789m 456p 789s 55p (待ち: 嵌張待ち)",
+                "役:",
+                "  立直: 1翻",
+                "ドラ: 1 / 裏ドラ: 0 / 赤ドラ: 0",
+                "符:",
+                "  副底: 20符",
+                "  嵌張待ち: 2符",
+                "  門前加符: 10符",
+                "合計: 40符 2翻",
+                "支払い:",
+                "  席0: +0",
+                "  席1: +3900",
+                "  席2: +0",
+                "  席3: -2900",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    /// 責任払いのある役満
+    fn yakuman_with_liability() {
+        let pon = |i: TileType, from: OpenFrom| OpenTiles {
+            tiles: [Tile::new(i); 3],
+            category: OpenType::Pon,
+            from,
+            kan: None,
+        };
+        let hand = Hand::new_with_opened(
+            [Tile::M1, Tile::M2, Tile::M3, Tile::P4]
+                .iter()
+                .map(|&i| Tile::new(i))
+                .collect(),
+            vec![
+                pon(Tile::Z5, OpenFrom::Previous),
+                pon(Tile::Z6, OpenFrom::Opposite),
+                pon(Tile::Z7, OpenFrom::Following),
+            ],
+            Some(Tile::new(Tile::P4)),
+        );
+        let context = WinContext::new(
+            Tile::new(Tile::P4),
+            WinMethod::Tsumo,
+            Wind::West,
+            Wind::East,
+        );
+        let result = score(&hand, &context, &Ruleset::default()).unwrap();
+        let seats = WinSeats {
+            winner: 2,
+            dealer: 0,
+            discarder: None,
+        };
+        let report = ScoreReport::new(&hand, &context, &result, &seats, 0, Locale::Romaji);
+        assert_eq!(report.limit, Some("Yakuman".to_string()));
+        assert_eq!(report.deltas, [0, 0, 32000, -32000]);
+        assert!(report.to_text().contains("Daisangen: Yakuman"));
+    }
+}
@@ -0,0 +1,87 @@
+use crate::tile::*;
+
+/// 捨て牌1枚
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Discard {
+    /// 捨てた牌
+    pub tile: Tile,
+    /// 他家に鳴かれた（チー・ポン・明カン）か
+    pub called: bool,
+    /// 立直宣言牌か
+    pub riichi: bool,
+}
+
+/// 1人のプレイヤーの捨て牌（河）
+///
+/// 鳴かれた牌も河に含めたまま、鳴かれたことを記録する。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand_info::river::*;
+/// use mahjong_rs::tile::*;
+///
+/// let mut river = River::new();
+/// river.discard(Tile::new(Tile::M1));
+/// river.discard(Tile::new(Tile::Z5));
+/// river.mark_last_called();
+/// assert_eq!(river.len(), 2);
+/// assert!(river.discards()[1].called);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct River {
+    discards: Vec<Discard>,
+}
+
+impl River {
+    /// 空の河を作る
+    pub fn new() -> River {
+        return River::default();
+    }
+
+    /// 牌を捨てる
+    pub fn discard(&mut self, tile: Tile) {
+        self.discards.push(Discard {
+            tile,
+            called: false,
+            riichi: false,
+        });
+    }
+
+    /// 立直を宣言して牌を捨てる
+    pub fn discard_with_riichi(&mut self, tile: Tile) {
+        self.discards.push(Discard {
+            tile,
+            called: false,
+            riichi: true,
+        });
+    }
+
+    /// 最後に捨てた牌が鳴かれたことを記録する
+    ///
+    /// 河が空なら何もしない。
+    pub fn mark_last_called(&mut self) {
+        if let Some(last) = self.discards.last_mut() {
+            last.called = true;
+        }
+    }
+
+    /// 捨て牌を捨てた順に返す
+    pub fn discards(&self) -> &[Discard] {
+        return &self.discards;
+    }
+
+    /// 捨てた牌を捨てた順に返す（振聴の判定などに使う）
+    pub fn tiles(&self) -> Vec<Tile> {
+        return self.discards.iter().map(|d| d.tile).collect();
+    }
+
+    /// 捨て牌の枚数を返す
+    pub fn len(&self) -> usize {
+        return self.discards.len();
+    }
+
+    /// 1枚も捨てていないか否かを返す
+    pub fn is_empty(&self) -> bool {
+        return self.discards.is_empty();
+    }
+}
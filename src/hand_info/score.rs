@@ -0,0 +1,304 @@
+use crate::hand_info::win_context::WinMethod;
+use crate::locale::*;
+
+/// 満貫以上の点数の区分
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Limit {
+    /// 満貫未満
+    None,
+    /// 満貫
+    Mangan,
+    /// 跳満
+    Haneman,
+    /// 倍満
+    Baiman,
+    /// 三倍満
+    Sanbaiman,
+    /// 役満（数え役満を含む）。値は何倍役満か
+    Yakuman(u32),
+}
+
+impl Limit {
+    /// 指定した言語での名前を返す
+    ///
+    /// 満貫未満なら`None`を返す。
+    pub fn name(&self, locale: Locale) -> Option<String> {
+        return limit_name(*self, locale);
+    }
+}
+
+/// 点数の上限に関するルール
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct LimitRule {
+    /// 切り上げ満貫（4翻30符・3翻60符を満貫とする）
+    pub kiriage_mangan: bool,
+    /// 数え役満（役満以外で13翻以上を役満とする。なければ三倍満）
+    pub kazoe_yakuman: bool,
+}
+
+impl Default for LimitRule {
+    fn default() -> LimitRule {
+        return LimitRule {
+            kiriage_mangan: false,
+            kazoe_yakuman: true,
+        };
+    }
+}
+
+/// 和了者への支払い（本場を含む）
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Payment {
+    /// ロン和了。放銃者が支払う点数
+    Ron { discarder: u32 },
+    /// 親のツモ和了。子がそれぞれ支払う点数
+    DealerTsumo { each: u32 },
+    /// 子のツモ和了。親と子がそれぞれ支払う点数
+    NonDealerTsumo { dealer: u32, non_dealer: u32 },
+}
+
+impl Payment {
+    /// 支払いの合計を返す
+    pub fn total(&self) -> u32 {
+        return match *self {
+            Payment::Ron { discarder } => discarder,
+            Payment::DealerTsumo { each } => each * 3,
+            Payment::NonDealerTsumo { dealer, non_dealer } => dealer + non_dealer * 2,
+        };
+    }
+
+    /// 各席の点数の増減を返す
+    ///
+    /// 席は手番の順に`0`〜`3`で、`winner`は和了者、`dealer`は親、`discarder`は放銃者の席。
+    /// ロンで`discarder`が`None`なら、放銃者の支払いは数えない。
+    pub fn deltas(&self, winner: usize, dealer: usize, discarder: Option<usize>) -> [i32; 4] {
+        let mut result = [0; 4];
+        for (seat, delta) in result.iter_mut().enumerate() {
+            if seat == winner {
+                continue;
+            }
+            *delta = -(match *self {
+                Payment::Ron { discarder: points } if Some(seat) == discarder => points,
+                Payment::Ron { .. } => 0,
+                Payment::DealerTsumo { each } => each,
+                Payment::NonDealerTsumo { dealer: d, .. } if seat == dealer => d,
+                Payment::NonDealerTsumo { non_dealer, .. } => non_dealer,
+            } as i32);
+        }
+        result[winner] = -result.iter().sum::<i32>();
+        return result;
+    }
+}
+
+/// 和了者が受け取る点数
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Settlement {
+    /// 他家からの支払い（本場を含む）
+    pub payment: Payment,
+    /// 供託された立直棒の点数
+    pub deposit: u32,
+    /// 受け取る点数の合計
+    pub total: u32,
+}
+
+/// 翻数と符から求めた点数
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Score {
+    /// 翻数（役満では`0`）
+    pub han: u32,
+    /// 符
+    pub fu: u32,
+    /// 基本点
+    pub base: u32,
+    /// 満貫以上の区分
+    pub limit: Limit,
+}
+
+impl Score {
+    /// 1本場あたりの加算点
+    pub const HONBA_POINTS: u32 = 300;
+    /// 立直棒1本の点数
+    pub const RIICHI_STICK_POINTS: u32 = 1000;
+
+    /// 翻数と符から点数を求める
+    ///
+    /// `yakuman`は成立した役満の倍数で、`0`でなければ翻数と符によらず役満として扱う。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand_info::score::*;
+    ///
+    /// let score = Score::new(3, 30, 0, &LimitRule::default());
+    /// assert_eq!(score.base, 960);
+    /// assert_eq!(Score::new(5, 30, 0, &LimitRule::default()).limit, Limit::Mangan);
+    /// ```
+    pub fn new(han: u32, fu: u32, yakuman: u32, rule: &LimitRule) -> Score {
+        if yakuman > 0 {
+            return Score {
+                han: 0,
+                fu,
+                base: 8000 * yakuman,
+                limit: Limit::Yakuman(yakuman),
+            };
+        }
+        let limit = match han {
+            0..=4 => {
+                let base = fu << (han + 2);
+                if base >= 2000 || (rule.kiriage_mangan && base == 1920) {
+                    Limit::Mangan
+                } else {
+                    Limit::None
+                }
+            }
+            5 => Limit::Mangan,
+            6 | 7 => Limit::Haneman,
+            8..=10 => Limit::Baiman,
+            11 | 12 => Limit::Sanbaiman,
+            _ if rule.kazoe_yakuman => Limit::Yakuman(1),
+            _ => Limit::Sanbaiman,
+        };
+        let base = match limit {
+            Limit::None => fu << (han + 2),
+            Limit::Mangan => 2000,
+            Limit::Haneman => 3000,
+            Limit::Baiman => 4000,
+            Limit::Sanbaiman => 6000,
+            Limit::Yakuman(n) => 8000 * n,
+        };
+        return Score {
+            han,
+            fu,
+            base,
+            limit,
+        };
+    }
+
+    /// 支払いを求める
+    ///
+    /// 本場はロンなら放銃者が`300`点、ツモなら各自が`100`点ずつ加算して支払い、
+    /// 供託された立直棒は和了者が受け取る。
+    /// # Examples
+    ///
+    /// ```
+    /// use mahjong_rs::hand_info::score::*;
+    /// use mahjong_rs::hand_info::win_context::WinMethod;
+    ///
+    /// // 子の30符4翻ツモ・1本場・供託1本
+    /// let score = Score::new(4, 30, 0, &LimitRule::default());
+    /// let settlement = score.settle(false, WinMethod::Tsumo, 1, 1);
+    /// assert_eq!(
+    ///     settlement.payment,
+    ///     Payment::NonDealerTsumo { dealer: 4000, non_dealer: 2100 }
+    /// );
+    /// assert_eq!(settlement.total, 9200);
+    /// ```
+    pub fn settle(
+        &self,
+        dealer: bool,
+        method: WinMethod,
+        honba: u32,
+        riichi_sticks: u32,
+    ) -> Settlement {
+        let honba_each = Score::HONBA_POINTS / 3 * honba;
+        let payment = match (method, dealer) {
+            (WinMethod::Ron, true) => Payment::Ron {
+                discarder: round_up(self.base * 6) + honba_each * 3,
+            },
+            (WinMethod::Ron, false) => Payment::Ron {
+                discarder: round_up(self.base * 4) + honba_each * 3,
+            },
+            (WinMethod::Tsumo, true) => Payment::DealerTsumo {
+                each: round_up(self.base * 2) + honba_each,
+            },
+            (WinMethod::Tsumo, false) => Payment::NonDealerTsumo {
+                dealer: round_up(self.base * 2) + honba_each,
+                non_dealer: round_up(self.base) + honba_each,
+            },
+        };
+        let deposit = Score::RIICHI_STICK_POINTS * riichi_sticks;
+        return Settlement {
+            payment,
+            deposit,
+            total: payment.total() + deposit,
+        };
+    }
+}
+
+/// 100点単位に切り上げる
+fn round_up(points: u32) -> u32 {
+    return points.div_ceil(100) * 100;
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 満貫未満の点数
+    fn below_mangan() {
+        let score = Score::new(1, 30, 0, &LimitRule::default());
+        assert_eq!(score.base, 240);
+        assert_eq!(
+            score.settle(false, WinMethod::Ron, 0, 0).payment,
+            Payment::Ron { discarder: 1000 }
+        );
+        assert_eq!(
+            score.settle(true, WinMethod::Ron, 0, 0).payment,
+            Payment::Ron { discarder: 1500 }
+        );
+        // 20符2翻ツモ（平和ツモ）
+        let score = Score::new(2, 20, 0, &LimitRule::default());
+        assert_eq!(
+            score.settle(false, WinMethod::Tsumo, 0, 0).payment,
+            Payment::NonDealerTsumo {
+                dealer: 700,
+                non_dealer: 400
+            }
+        );
+        assert_eq!(
+            score.settle(true, WinMethod::Tsumo, 0, 0).payment,
+            Payment::DealerTsumo { each: 700 }
+        );
+    }
+
+    #[test]
+    /// 満貫以上の区分
+    fn limits() {
+        let rule = LimitRule::default();
+        assert_eq!(Score::new(4, 40, 0, &rule).limit, Limit::Mangan);
+        assert_eq!(Score::new(4, 30, 0, &rule).limit, Limit::None);
+        assert_eq!(Score::new(7, 30, 0, &rule).limit, Limit::Haneman);
+        assert_eq!(Score::new(10, 30, 0, &rule).limit, Limit::Baiman);
+        assert_eq!(Score::new(12, 30, 0, &rule).limit, Limit::Sanbaiman);
+        assert_eq!(Score::new(13, 30, 0, &rule).limit, Limit::Yakuman(1));
+        assert_eq!(Score::new(0, 30, 2, &rule).base, 16000);
+        assert_eq!(
+            Score::new(0, 30, 1, &rule)
+                .settle(true, WinMethod::Ron, 0, 0)
+                .total,
+            48000
+        );
+    }
+
+    #[test]
+    /// 切り上げ満貫と数え役満のルール
+    fn limit_rules() {
+        let rule = LimitRule {
+            kiriage_mangan: true,
+            kazoe_yakuman: false,
+        };
+        assert_eq!(Score::new(4, 30, 0, &rule).limit, Limit::Mangan);
+        assert_eq!(Score::new(3, 60, 0, &rule).limit, Limit::Mangan);
+        assert_eq!(Score::new(13, 30, 0, &rule).limit, Limit::Sanbaiman);
+    }
+
+    #[test]
+    /// 本場と供託
+    fn honba_and_deposit() {
+        let score = Score::new(3, 40, 0, &LimitRule::default());
+        let settlement = score.settle(false, WinMethod::Ron, 2, 3);
+        assert_eq!(settlement.payment, Payment::Ron { discarder: 5800 });
+        assert_eq!(settlement.deposit, 3000);
+        assert_eq!(settlement.total, 8800);
+    }
+}
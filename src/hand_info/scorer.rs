@@ -0,0 +1,355 @@
+use crate::hand::Hand;
+use crate::hand_info::decomposition::*;
+use crate::hand_info::fu::*;
+use crate::hand_info::hand_analyzer::HandAnalyzer;
+use crate::hand_info::local_yaku;
+use crate::hand_info::score::*;
+use crate::hand_info::win_context::*;
+use crate::hand_info::winning_hand::evaluate_decomposition;
+use crate::hand_info::yaku::*;
+use crate::rule::Ruleset;
+
+/// 和了形の1つの解釈と、その点数
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Interpretation {
+    /// 手牌の解釈
+    pub decomposition: Decomposition,
+    /// 成立した役
+    pub yaku: Vec<AchievedYaku>,
+    /// ドラ（裏ドラ・赤ドラを含む）の数。役満では数えない
+    pub dora: u32,
+    /// 符
+    pub fu: Fu,
+    /// 点数
+    pub score: Score,
+    /// 和了者が受け取る点数（本場・供託を除く）
+    pub points: u32,
+}
+
+impl Interpretation {
+    /// 役があるか否かを返す
+    pub fn has_yaku(&self) -> bool {
+        return !self.yaku.is_empty();
+    }
+
+    /// 高い方を選ぶための比較
+    ///
+    /// 点数が高い方、同じなら翻数が多い方、それも同じなら符が高い方を優先する。
+    fn rank(&self) -> (bool, u32, u32, u32) {
+        return (self.has_yaku(), self.points, self.score.han, self.fu.value);
+    }
+}
+
+/// 点数計算の結果
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ScoreResult {
+    /// 最も点数が高い解釈
+    pub chosen: Interpretation,
+    /// 採用しなかった解釈（デバッグ用）
+    pub rejected: Vec<Interpretation>,
+}
+
+/// 和了した手牌の点数を計算する
+///
+/// 全ての解釈と和了牌の読み方（待ちの形）について役・符・点数を求め、最も点数が高いものを選ぶ。
+/// 和了形でないか、どの解釈でも役がなければ`None`を返す。
+/// 喰い断・ダブル役満・数え役満・切り上げ満貫・連風牌の符・2翻縛り・後付けはルールに従う。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand::*;
+/// use mahjong_rs::hand_info::scorer::*;
+/// use mahjong_rs::hand_info::win_context::*;
+/// use mahjong_rs::hand_info::yaku::*;
+/// use mahjong_rs::rule::*;
+/// use mahjong_rs::tile::*;
+///
+/// // 三暗刻の読み（40符3翻）が一盃口の読み（30符2翻）より高い
+/// let hand = Hand::from("111222333m456p9s 9s");
+/// let context = WinContext::new(Tile::new(Tile::S9), WinMethod::Tsumo, Wind::South, Wind::East);
+/// let result = score(&hand, &context, &Ruleset::default()).unwrap();
+/// assert!(result
+///     .chosen
+///     .yaku
+///     .iter()
+///     .any(|a| a.yaku == Yaku::ThreeClosedTriplets));
+/// assert!(!result.rejected.is_empty());
+/// ```
+pub fn score(hand: &Hand, context: &WinContext, ruleset: &Ruleset) -> Option<ScoreResult> {
+    let result = score_without_atozuke_check(hand, context, ruleset)?;
+    if !ruleset.atozuke && !has_yaku_on_every_wait(hand, context, ruleset) {
+        return None;
+    }
+    return Some(result);
+}
+
+/// 後付けの判定をせずに点数を計算する
+fn score_without_atozuke_check(
+    hand: &Hand,
+    context: &WinContext,
+    ruleset: &Ruleset,
+) -> Option<ScoreResult> {
+    let dora = count_all_dora(hand, context);
+    let mut interpretations: Vec<Interpretation> =
+        Decomposition::enumerate_by_rule(hand, context.winning_tile, context.method, ruleset)
+            .into_iter()
+            .map(|d| interpret(d, context, dora, ruleset))
+            .collect();
+    if interpretations.is_empty() {
+        interpretations.extend(interpret_thirteen_unrelated(hand, context, ruleset));
+    }
+    let best = (0..interpretations.len()).max_by_key(|&i| interpretations[i].rank())?;
+    let chosen = interpretations.remove(best);
+    if !chosen.has_yaku() {
+        return None;
+    }
+    return Some(ScoreResult {
+        chosen,
+        rejected: interpretations,
+    });
+}
+
+/// ドラ・裏ドラ・赤ドラの合計を返す
+fn count_all_dora(hand: &Hand, context: &WinContext) -> u32 {
+    return context.count_dora(hand) + context.count_ura_dora(hand) + hand.count_red_dora();
+}
+
+/// 和了前の全ての待ち牌で役があるか否かを返す（後付けなしのルール）
+fn has_yaku_on_every_wait(hand: &Hand, context: &WinContext, ruleset: &Ruleset) -> bool {
+    return HandAnalyzer::calc_waiting_tiles(hand).iter().all(|&tile| {
+        let mut other = context.clone();
+        other.winning_tile = tile;
+        return score_without_atozuke_check(&hand.with_drawn(tile), &other, ruleset).is_some();
+    });
+}
+
+/// ルールに従って役の翻数を調整する
+///
+/// 喰い断を認めなければ副露した断么九を除き、ダブル役満を認めなければ役満として数える。
+/// 2翻縛りで役の翻数が足りなければ全て除く。
+fn apply_ruleset(
+    mut yaku: Vec<AchievedYaku>,
+    closed: bool,
+    context: &WinContext,
+    ruleset: &Ruleset,
+) -> Vec<AchievedYaku> {
+    if !closed && !ruleset.open_tanyao {
+        yaku.retain(|a| a.yaku != Yaku::AllSimples);
+    }
+    if !ruleset.double_yakuman {
+        for a in yaku.iter_mut() {
+            a.han = a.han.min(Yaku::YAKUMAN_HAN);
+        }
+    }
+    if ruleset.requires_two_han(context.honba) && yaku.iter().map(|a| a.han).sum::<u32>() < 2 {
+        yaku.clear();
+    }
+    return yaku;
+}
+
+/// 1つの解釈について役・符・点数を求める
+///
+/// ルールで採用しているローカル役も通常の役と同じように数える。
+fn interpret(
+    decomposition: Decomposition,
+    context: &WinContext,
+    dora: u32,
+    ruleset: &Ruleset,
+) -> Interpretation {
+    let yaku = local_yaku::merge(
+        evaluate_decomposition(&decomposition, context),
+        local_yaku::evaluate(&decomposition, context, &ruleset.local_yaku),
+    );
+    return interpret_with_yaku(decomposition, yaku, context, dora, ruleset);
+}
+
+/// 十三不塔を採用していて成立していれば、その解釈と点数を返す
+fn interpret_thirteen_unrelated(
+    hand: &Hand,
+    context: &WinContext,
+    ruleset: &Ruleset,
+) -> Option<Interpretation> {
+    let han = ruleset.local_yaku.han(Yaku::ThirteenUnrelatedTiles, true)?;
+    let decomposition = local_yaku::thirteen_unrelated_decomposition(hand, context)?;
+    let yaku = vec![AchievedYaku {
+        yaku: Yaku::ThirteenUnrelatedTiles,
+        han,
+    }];
+    let dora = count_all_dora(hand, context);
+    return Some(interpret_with_yaku(
+        decomposition,
+        yaku,
+        context,
+        dora,
+        ruleset,
+    ));
+}
+
+/// 成立した役から、ルールに従って符・点数を求める
+fn interpret_with_yaku(
+    decomposition: Decomposition,
+    yaku: Vec<AchievedYaku>,
+    context: &WinContext,
+    dora: u32,
+    ruleset: &Ruleset,
+) -> Interpretation {
+    let yaku = apply_ruleset(yaku, decomposition.closed, context, ruleset);
+    let fu = Fu::calc(&decomposition, context, ruleset.double_wind_pair_fu);
+    let yakuman: u32 = yaku.iter().map(|a| a.yakuman_multiplier()).sum();
+    let (dora, han) = if yakuman > 0 || yaku.is_empty() {
+        (0, 0)
+    } else {
+        (dora, yaku.iter().map(|a| a.han).sum::<u32>() + dora)
+    };
+    let score = Score::new(han, fu.value, yakuman, &ruleset.limit_rule());
+    let points = score
+        .settle(context.is_dealer(), context.method, 0, 0)
+        .total;
+    return Interpretation {
+        decomposition,
+        yaku,
+        dora,
+        fu,
+        score,
+        points,
+    };
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_info::winning_hand::WinningHandForm;
+    use crate::tile::*;
+
+    /// 南家・東場で点数を計算する
+    fn score_of(hand_str: &str, tile: TileType, method: WinMethod) -> Option<ScoreResult> {
+        return score_by_rule(hand_str, tile, method, &Ruleset::default());
+    }
+
+    /// 南家・東場で、ルールを指定して点数を計算する
+    fn score_by_rule(
+        hand_str: &str,
+        tile: TileType,
+        method: WinMethod,
+        ruleset: &Ruleset,
+    ) -> Option<ScoreResult> {
+        let hand = Hand::from(hand_str);
+        let context = WinContext::new(Tile::new(tile), method, Wind::South, Wind::East);
+        return score(&hand, &context, ruleset);
+    }
+
+    #[test]
+    /// 七対子より二盃口の読みを選ぶ
+    fn two_sets_of_identical_sequences_over_seven_pairs() {
+        let result = score_of("112233m445566p7z 7z", Tile::Z7, WinMethod::Ron).unwrap();
+        assert_eq!(result.chosen.decomposition.form, WinningHandForm::Normal);
+        assert_eq!(result.chosen.score.han, 3);
+        assert!(result
+            .rejected
+            .iter()
+            .any(|i| i.decomposition.form == WinningHandForm::SevenPairs));
+    }
+
+    #[test]
+    /// 両面と単騎に読めるときは点数が高い方を選ぶ
+    fn higher_points_wait() {
+        // 45m＋66m（両面）なら平和・断么九の30符2翻（2000点）、
+        // 456m＋6m（単騎）なら断么九の40符1翻（1300点）
+        let result = score_of("4566m234p345678s 6m", Tile::M6, WinMethod::Ron).unwrap();
+        assert_eq!(result.chosen.decomposition.wait, Wait::Ryanmen);
+        assert_eq!(result.chosen.points, 2000);
+        assert_eq!(result.rejected.len(), 1);
+        assert_eq!(result.rejected[0].decomposition.wait, Wait::Tanki);
+        assert_eq!(result.rejected[0].points, 1300);
+    }
+
+    #[test]
+    /// 役がなければ和了できない
+    fn no_yaku() {
+        assert!(score_of("123m456p789s11s12p 3p", Tile::P3, WinMethod::Ron).is_none());
+    }
+
+    #[test]
+    /// 喰い断を認めないルール
+    fn open_tanyao() {
+        let hand_str = "23m567p55s 678s 345p 4m";
+        assert!(score_of(hand_str, Tile::M4, WinMethod::Ron).is_some());
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.open_tanyao = false;
+        assert!(score_by_rule(hand_str, Tile::M4, WinMethod::Ron, &ruleset).is_none());
+    }
+
+    #[test]
+    /// ダブル役満を認めるか否か
+    fn double_yakuman() {
+        let hand_str = "19m19p19s1234567z 1m";
+        let result = score_of(hand_str, Tile::M1, WinMethod::Ron).unwrap();
+        assert_eq!(result.chosen.points, 32000);
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.double_yakuman = true;
+        let result = score_by_rule(hand_str, Tile::M1, WinMethod::Ron, &ruleset).unwrap();
+        assert_eq!(result.chosen.points, 64000);
+    }
+
+    #[test]
+    /// 5本場以上の2翻縛り
+    fn two_han_minimum() {
+        let hand = Hand::from("123m456p789s55s23p 1p");
+        let mut context =
+            WinContext::new(Tile::new(Tile::P1), WinMethod::Ron, Wind::South, Wind::East);
+        context.honba = 5;
+        let mut ruleset = Ruleset::tenhou();
+        assert!(score(&hand, &context, &ruleset).is_some());
+        ruleset.two_han_minimum_after_five_honba = true;
+        assert!(score(&hand, &context, &ruleset).is_none());
+    }
+
+    #[test]
+    /// 後付けを認めないルールでは、役のない待ちがあれば和了できない
+    fn atozuke() {
+        // 四萬なら断么九だが、一萬では役がない
+        let hand_str = "23m234p55s 678s 567p 4m";
+        assert!(score_of(hand_str, Tile::M4, WinMethod::Ron).is_some());
+        let mut ruleset = Ruleset::tenhou();
+        ruleset.atozuke = false;
+        assert!(score_by_rule(hand_str, Tile::M4, WinMethod::Ron, &ruleset).is_none());
+    }
+
+    #[test]
+    /// ローカル役も通常の役と同じ結果に含める
+    fn local_yaku() {
+        let mut ruleset = Ruleset::tenhou();
+        let hand_str = "2233445566778p 8p";
+        let result = score_by_rule(hand_str, Tile::P8, WinMethod::Ron, &ruleset).unwrap();
+        assert!(result.chosen.score.han < Yaku::YAKUMAN_HAN);
+        ruleset.local_yaku.daisharin = Some(13);
+        let result = score_by_rule(hand_str, Tile::P8, WinMethod::Ron, &ruleset).unwrap();
+        assert_eq!(
+            result.chosen.yaku,
+            vec![AchievedYaku {
+                yaku: Yaku::BigWheels,
+                han: 13
+            }]
+        );
+        assert_eq!(result.chosen.points, 32000);
+    }
+
+    #[test]
+    /// 十三不塔は和了形でなくても和了できる
+    fn thirteen_unrelated() {
+        let hand = Hand::from("147m258p369s1234z 1z");
+        let mut context = WinContext::new(
+            Tile::new(Tile::Z1),
+            WinMethod::Tsumo,
+            Wind::South,
+            Wind::East,
+        );
+        context.first_turn = true;
+        let mut ruleset = Ruleset::tenhou();
+        assert!(score(&hand, &context, &ruleset).is_none());
+        ruleset.local_yaku.shiisanbuta = Some(5);
+        let result = score(&hand, &context, &ruleset).unwrap();
+        assert_eq!(result.chosen.score.limit, Limit::Mangan);
+    }
+}
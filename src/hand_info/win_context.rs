@@ -0,0 +1,208 @@
+use crate::hand::Hand;
+use crate::hand_info::opened::*;
+use crate::tile::*;
+
+/// 和了の方法
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WinMethod {
+    /// ツモ和了
+    Tsumo,
+    /// ロン和了
+    Ron,
+}
+
+/// 立直の状態
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RiichiState {
+    /// 立直していない
+    None,
+    /// 立直
+    Riichi,
+    /// ダブル立直
+    DoubleRiichi,
+}
+
+/// 和了したときの状況
+///
+/// 手牌の形だけでは決まらない役（立直・門前清自摸和・一発・海底撈月・河底撈魚・嶺上開花・搶槓・
+/// ダブル立直・天和・地和）の判定や、ドラの計算に使う。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand_info::win_context::*;
+/// use mahjong_rs::tile::*;
+///
+/// let mut context = WinContext::new(Tile::new(Tile::M1), WinMethod::Tsumo, Wind::East, Wind::East);
+/// context.riichi = RiichiState::Riichi;
+/// context.ippatsu = true;
+/// assert!(context.is_dealer());
+/// ```
+#[derive(Debug, Clone)]
+pub struct WinContext {
+    /// 和了牌
+    pub winning_tile: Tile,
+    /// ツモかロンか
+    pub method: WinMethod,
+    /// 立直の状態
+    pub riichi: RiichiState,
+    /// 一発（立直後、鳴きが入らずに1巡以内に和了った）
+    pub ippatsu: bool,
+    /// 最後の牌（ツモなら海底牌、ロンなら河底牌）で和了った
+    pub last_tile: bool,
+    /// カンした後の嶺上牌で和了った
+    pub after_kan: bool,
+    /// 他家の加槓した牌で和了った
+    pub robbing_kan: bool,
+    /// 鳴きが入る前の第一ツモで和了った（天和・地和・人和）
+    pub first_turn: bool,
+    /// オープン立直している
+    pub open_riichi: bool,
+    /// 他家の立直宣言牌でロンした（燕返し）
+    pub on_riichi_declaration: bool,
+    /// 自風
+    pub seat_wind: Wind,
+    /// 場風
+    pub round_wind: Wind,
+    /// ドラ表示牌
+    pub dora_indicators: Vec<Tile>,
+    /// 裏ドラ表示牌（立直していなければ数えない）
+    pub ura_dora_indicators: Vec<Tile>,
+    /// 本場
+    pub honba: u32,
+}
+
+impl WinContext {
+    /// 和了牌・和了の方法・自風・場風から作る
+    ///
+    /// 他の状況は全て成立していないものとし、ドラ表示牌も空に、本場も`0`にする。
+    pub fn new(
+        winning_tile: Tile,
+        method: WinMethod,
+        seat_wind: Wind,
+        round_wind: Wind,
+    ) -> WinContext {
+        return WinContext {
+            winning_tile,
+            method,
+            riichi: RiichiState::None,
+            ippatsu: false,
+            last_tile: false,
+            after_kan: false,
+            robbing_kan: false,
+            first_turn: false,
+            open_riichi: false,
+            on_riichi_declaration: false,
+            seat_wind,
+            round_wind,
+            dora_indicators: Vec::new(),
+            ura_dora_indicators: Vec::new(),
+            honba: 0,
+        };
+    }
+
+    /// 親か否かを返す
+    pub fn is_dealer(&self) -> bool {
+        return self.seat_wind == Wind::East;
+    }
+
+    /// ツモ和了か否かを返す
+    pub fn is_tsumo(&self) -> bool {
+        return self.method == WinMethod::Tsumo;
+    }
+
+    /// 立直（ダブル立直を含む）しているか否かを返す
+    pub fn is_riichi(&self) -> bool {
+        return self.riichi != RiichiState::None;
+    }
+
+    /// 手牌に含まれるドラの数を返す
+    pub fn count_dora(&self, hand: &Hand) -> u32 {
+        return count_by_indicators(hand, &self.dora_indicators);
+    }
+
+    /// 手牌に含まれる裏ドラの数を返す
+    ///
+    /// 立直していなければ`0`を返す。
+    pub fn count_ura_dora(&self, hand: &Hand) -> u32 {
+        if !self.is_riichi() {
+            return 0;
+        }
+        return count_by_indicators(hand, &self.ura_dora_indicators);
+    }
+}
+
+/// ドラ表示牌からドラを返す
+///
+/// 数牌は次の数字（九の次は一）、風牌は東南西北、三元牌は白發中の順に巡る。
+pub fn dora_from_indicator(indicator: Tile) -> Tile {
+    let i = indicator.get();
+    let next = match i {
+        Tile::M1..=Tile::S9 => i - i % 9 + (i % 9 + 1) % 9,
+        Tile::Z1..=Tile::Z4 => Tile::Z1 + (i - Tile::Z1 + 1) % 4,
+        _ => Tile::Z5 + (i - Tile::Z5 + 1) % 3,
+    };
+    return Tile::new(next);
+}
+
+/// 手牌（副露・ツモ牌を含む）に含まれる、表示牌が示すドラの数を返す
+fn count_by_indicators(hand: &Hand, indicators: &[Tile]) -> u32 {
+    let summarized = hand.summarize_tiles();
+    let mut result = 0;
+    for indicator in indicators {
+        let dora = dora_from_indicator(*indicator);
+        result += summarized[dora.get() as usize];
+        // 槓子は4枚目も数える
+        for open in hand.opened() {
+            if open.category == OpenType::Kan && open.tiles[0].get() == dora.get() {
+                result += 1;
+            }
+        }
+    }
+    return result;
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// ドラ表示牌からドラを求める
+    fn dora_from_indicators() {
+        assert_eq!(
+            dora_from_indicator(Tile::new(Tile::M1)),
+            Tile::new(Tile::M2)
+        );
+        assert_eq!(
+            dora_from_indicator(Tile::new(Tile::P9)),
+            Tile::new(Tile::P1)
+        );
+        assert_eq!(
+            dora_from_indicator(Tile::new(Tile::Z4)),
+            Tile::new(Tile::Z1)
+        );
+        assert_eq!(
+            dora_from_indicator(Tile::new(Tile::Z7)),
+            Tile::new(Tile::Z5)
+        );
+    }
+
+    #[test]
+    /// 手牌のドラを数える
+    fn count_dora() {
+        let hand = Hand::from("123m456p789s2z 1111z 2z");
+        let mut context = WinContext::new(
+            Tile::new(Tile::Z2),
+            WinMethod::Tsumo,
+            Wind::South,
+            Wind::East,
+        );
+        context.dora_indicators = vec![Tile::new(Tile::Z4), Tile::new(Tile::S8)];
+        context.ura_dora_indicators = vec![Tile::new(Tile::Z1)];
+        assert_eq!(context.count_dora(&hand), 5);
+        // 立直していなければ裏ドラは数えない
+        assert_eq!(context.count_ura_dora(&hand), 0);
+        context.riichi = RiichiState::Riichi;
+        assert_eq!(context.count_ura_dora(&hand), 2);
+    }
+}
@@ -0,0 +1,301 @@
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::hand::Hand;
+use crate::hand_info::incremental_analyzer::IncrementalAnalyzer;
+use crate::hand_info::opened::OpenType;
+use crate::tile::*;
+
+/// 信頼区間に使う標準正規分布の値（95%）
+const Z_95: f64 = 1.96;
+
+/// 打牌を選ぶ方針
+pub trait DiscardPolicy {
+    /// ツモった後（14枚）の手牌から切る牌を選ぶ
+    fn choose(&self, analyzer: &IncrementalAnalyzer) -> Tile;
+}
+
+/// 向聴数が最小になる打牌のうち、受け入れ枚数が最大のものを選ぶ
+///
+/// 受け入れ枚数が同じならば、牌の種類の順で後ろ（字牌側）のものを切る。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GreedyUkeire;
+
+impl DiscardPolicy for GreedyUkeire {
+    fn choose(&self, analyzer: &IncrementalAnalyzer) -> Tile {
+        let discards = analyzer.shanten_by_discard();
+        let min_shanten = discards.iter().map(|&(_, s)| s).min().unwrap();
+        let mut best: Option<(Tile, u32)> = None;
+        for &(tile, shanten) in discards.iter().rev() {
+            if shanten != min_shanten {
+                continue;
+            }
+            let mut after = analyzer.clone();
+            after.remove(tile);
+            let count = after.ukeire().count;
+            if best.is_none_or(|(_, c)| count > c) {
+                best = Some((tile, count));
+            }
+        }
+        return best.unwrap().0;
+    }
+}
+
+/// 確率の推定値と95%信頼区間
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    /// 推定された確率
+    pub probability: f64,
+    /// 信頼区間の下限
+    pub lower: f64,
+    /// 信頼区間の上限
+    pub upper: f64,
+}
+
+impl Estimate {
+    /// 試行回数と成功回数から推定値を求める（Wilsonスコア区間）
+    fn from_count(success: u32, trials: u32) -> Estimate {
+        if trials == 0 {
+            return Estimate {
+                probability: 0.0,
+                lower: 0.0,
+                upper: 1.0,
+            };
+        }
+        let n = trials as f64;
+        let p = success as f64 / n;
+        let z2 = Z_95 * Z_95;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let half = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+        return Estimate {
+            probability: p,
+            lower: (center - half).max(0.0),
+            upper: (center + half).min(1.0),
+        };
+    }
+}
+
+/// 推定結果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EstimateResult {
+    /// 試行回数
+    pub trials: u32,
+    /// 残りのツモまでに聴牌する確率
+    pub tenpai: Estimate,
+    /// 残りのツモまでに自摸和了する確率
+    pub win: Estimate,
+}
+
+/// モンテカルロ法で聴牌率・和了率を推定する
+///
+/// 見えていない牌からランダムにツモを繰り返し、`DiscardPolicy`に従って打牌する。
+/// 他家の和了や鳴き、役の有無は考慮しない。副露は確定した面子として扱い、切る候補には含めない。
+/// 乱数の種を指定し、生成器もChaCha8に固定しているため、同じ入力からは常に同じ結果が得られる。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand::*;
+/// use mahjong_rs::hand_info::win_estimator::*;
+/// use mahjong_rs::tile::*;
+///
+/// let hand = Hand::from("23m456p789s11122z");
+/// // 一萬・四萬が残り8枚、それ以外の10種類の牌が4枚ずつ見えていない
+/// let mut unseen = Vec::new();
+/// for &i in [Tile::M1, Tile::M4, Tile::M5, Tile::M6, Tile::M7, Tile::M8].iter() {
+///     unseen.extend(vec![Tile::new(i); 4]);
+/// }
+/// for &i in [Tile::M9, Tile::P1, Tile::P2, Tile::P3, Tile::S1, Tile::S2].iter() {
+///     unseen.extend(vec![Tile::new(i); 4]);
+/// }
+///
+/// let estimator = WinEstimator::new(GreedyUkeire, 1000, 42);
+/// let result = estimator.estimate(&hand, &unseen, 1);
+/// assert_eq!(result.tenpai.probability, 1.0);
+/// assert!(result.win.lower < 8.0 / 48.0 && 8.0 / 48.0 < result.win.upper);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WinEstimator<P: DiscardPolicy> {
+    /// 打牌の方針
+    policy: P,
+    /// 試行回数
+    trials: u32,
+    /// 乱数の種
+    seed: u64,
+}
+
+impl<P: DiscardPolicy> WinEstimator<P> {
+    pub fn new(policy: P, trials: u32, seed: u64) -> WinEstimator<P> {
+        return WinEstimator {
+            policy,
+            trials,
+            seed,
+        };
+    }
+
+    /// 手牌・見えていない牌・残りツモ回数から聴牌率と和了率を推定する
+    ///
+    /// 手牌にツモ牌がある（14枚の）場合は、最初に`DiscardPolicy`で1枚切ってから始める。
+    /// 残りツモ回数が見えていない牌の数より多い場合は、見えていない牌の数までしかツモらない。
+    /// # Panics
+    ///
+    /// 手牌（副露を含む）と見えていない牌を合わせて、同じ牌が5枚以上あるとき
+    pub fn estimate(&self, hand: &Hand, unseen: &[Tile], draws: u32) -> EstimateResult {
+        let mut t = hand.summarize_tiles();
+        for open in hand.opened() {
+            // 槓子は3枚で表しているので、4枚目を数える
+            if open.category == OpenType::Kan {
+                t[open.tiles[0].get() as usize] += 1;
+            }
+        }
+        for tile in unseen {
+            t[tile.get() as usize] += 1;
+            if t[tile.get() as usize] > 4 {
+                panic!(
+                    "The hand and unseen tiles have the 5th {}!",
+                    tile.to_string()
+                );
+            }
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut wall = unseen.to_vec();
+        let draws = std::cmp::min(draws as usize, wall.len());
+        let start = IncrementalAnalyzer::new(hand);
+
+        let mut tenpai: u32 = 0;
+        let mut win: u32 = 0;
+        for _ in 0..self.trials {
+            wall.shuffle(&mut rng);
+            let (reached_tenpai, won) = self.rollout(&start, &wall[..draws]);
+            if reached_tenpai {
+                tenpai += 1;
+            }
+            if won {
+                win += 1;
+            }
+        }
+        return EstimateResult {
+            trials: self.trials,
+            tenpai: Estimate::from_count(tenpai, self.trials),
+            win: Estimate::from_count(win, self.trials),
+        };
+    }
+
+    /// 1回分の試行を行う
+    /// # returns
+    /// (聴牌したか, 和了したか)
+    fn rollout(&self, start: &IncrementalAnalyzer, draws: &[Tile]) -> (bool, bool) {
+        let mut analyzer = start.clone();
+        let tile_count: u32 = analyzer.summarized().iter().sum();
+        if tile_count % 3 == 2 {
+            if analyzer.shanten() == -1 {
+                return (true, true);
+            }
+            let discard = self.policy.choose(&analyzer);
+            analyzer.remove(discard);
+        }
+        let mut reached_tenpai = analyzer.shanten() == 0;
+        for &tile in draws {
+            analyzer.add(tile);
+            if analyzer.shanten() == -1 {
+                return (true, true);
+            }
+            let discard = self.policy.choose(&analyzer);
+            analyzer.remove(discard);
+            if analyzer.shanten() == 0 {
+                reached_tenpai = true;
+            }
+        }
+        return (reached_tenpai, false);
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 手牌で使っている牌を除いた残りの牌
+    fn unseen_tiles(hand: &Hand) -> Vec<Tile> {
+        let t = hand.summarize_tiles();
+        let mut result: Vec<Tile> = Vec::new();
+        for i in Tile::M1..=Tile::Z7 {
+            for _ in t[i as usize]..4 {
+                result.push(Tile::new(i));
+            }
+        }
+        return result;
+    }
+
+    #[test]
+    /// 同じ乱数の種からは同じ結果になる
+    fn same_seed_same_result() {
+        let hand = Hand::from("1357m2468p1357s1z");
+        let unseen = unseen_tiles(&hand);
+        let a = WinEstimator::new(GreedyUkeire, 50, 1).estimate(&hand, &unseen, 12);
+        let b = WinEstimator::new(GreedyUkeire, 50, 1).estimate(&hand, &unseen, 12);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    /// 和了形ならば確率は1
+    fn already_won() {
+        let hand = Hand::from("123m444p789s1112z 2z");
+        let unseen = unseen_tiles(&hand);
+        let result = WinEstimator::new(GreedyUkeire, 10, 1).estimate(&hand, &unseen, 0);
+        assert_eq!(result.tenpai.probability, 1.0);
+        assert_eq!(result.win.probability, 1.0);
+    }
+
+    #[test]
+    /// 待ち牌が残っていなければ和了できない
+    fn no_waiting_tiles_left() {
+        let hand = Hand::from("23m456p789s11122z");
+        let mut unseen = Vec::new();
+        for &i in [Tile::M9, Tile::P1, Tile::P2, Tile::P3, Tile::S1, Tile::S2].iter() {
+            unseen.extend(vec![Tile::new(i); 4]);
+        }
+        let result = WinEstimator::new(GreedyUkeire, 20, 1).estimate(&hand, &unseen, 10);
+        assert_eq!(result.tenpai.probability, 1.0);
+        assert_eq!(result.win.probability, 0.0);
+        assert!(result.win.upper > 0.0);
+    }
+
+    #[test]
+    /// 副露した牌は切らずに確定した面子として扱う
+    fn opened_hand() {
+        let hand = Hand::from("113m269p37s67z 666m 3s");
+        let discard = GreedyUkeire.choose(&IncrementalAnalyzer::new(&hand));
+        assert_ne!(discard, Tile::new(Tile::M6));
+
+        // 一萬・四萬待ちの聴牌
+        let hand = Hand::from("23m456p11z 789s 2222z");
+        let unseen = vec![Tile::new(Tile::M1); 4];
+        let result = WinEstimator::new(GreedyUkeire, 10, 1).estimate(&hand, &unseen, 1);
+        assert_eq!(result.tenpai.probability, 1.0);
+        assert_eq!(result.win.probability, 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    /// 見えていない牌に、副露と合わせて5枚目になる牌があれば受け付けない
+    fn fifth_tile_in_unseen() {
+        let hand = Hand::from("23m456p11z 789s 2222z");
+        let unseen = vec![Tile::new(Tile::Z2)];
+        WinEstimator::new(GreedyUkeire, 10, 1).estimate(&hand, &unseen, 1);
+    }
+
+    #[test]
+    /// ツモ回数が多いほど和了率は上がる
+    fn more_draws_more_wins() {
+        let hand = Hand::from("12m456p123789s12z 5z");
+        let unseen = unseen_tiles(&hand);
+        let estimator = WinEstimator::new(GreedyUkeire, 200, 7);
+        let short = estimator.estimate(&hand, &unseen, 3);
+        let long = estimator.estimate(&hand, &unseen, 15);
+        assert!(short.win.probability < long.win.probability);
+        assert!(short.tenpai.probability <= long.tenpai.probability);
+        assert!(long.win.lower <= long.win.probability && long.win.probability <= long.win.upper);
+    }
+}
@@ -1,9 +1,15 @@
-use crate::hand_info::hand_analyzer::HandAnalyzer;
+use crate::hand::Hand;
+use crate::hand_info::block::*;
+use crate::hand_info::decomposition::*;
+use crate::hand_info::win_context::*;
+use crate::hand_info::yaku::*;
+use crate::locale::Locale;
+use crate::tile::*;
 /// 役を判定する
 use std::collections::HashMap;
 
 /// 和了時の手牌の形態
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum WinningHandForm {
     /// 七対子
     SevenPairs,
@@ -15,7 +21,7 @@ pub enum WinningHandForm {
 
 /// 役の名前
 /// https://en.wikipedia.org/wiki/Japanese_Mahjong_yaku による英語名
-pub const HAND_NAME: [&'static str; 40] = [
+pub const HAND_NAME: [&str; 51] = [
     // 立直
     "ready_hand",
     // 七対子
@@ -52,6 +58,8 @@ pub const HAND_NAME: [&'static str; 40] = [
     "three_closed_triplets",
     // 三色同刻
     "three_colour_triplets",
+    // 三槓子
+    "three_kans",
     // 断么九
     "all_simples",
     // 役牌（自風牌）
@@ -74,8 +82,12 @@ pub const HAND_NAME: [&'static str; 40] = [
     "flush",
     // 国士無双
     "thirteen_orphans",
+    // 国士無双十三面待ち
+    "thirteen_wait_thirteen_orphans",
     // 四暗刻
     "four_concealed_triplets",
+    // 四暗刻単騎
+    "single_wait_four_concealed_triplets",
     // 大三元
     "big_three_dragons",
     // 小四喜
@@ -90,417 +102,507 @@ pub const HAND_NAME: [&'static str; 40] = [
     "all_green",
     // 九蓮宝燈
     "nine_gates",
+    // 純正九蓮宝燈
+    "true_nine_gates",
     // 四槓子
     "four_kans",
     // 天和
     "heavenly_hand",
     // 地和
     "hand_of_earth",
+    // 人和（ローカル役）
+    "blessing_of_man",
+    // 大車輪（ローカル役）
+    "big_wheels",
+    // 三連刻（ローカル役）
+    "three_consecutive_triplets",
+    // 一色三順（ローカル役）
+    "pure_triple_chow",
+    // オープン立直（ローカル役）
+    "open_riichi",
+    // 十三不塔（ローカル役）
+    "thirteen_unrelated_tiles",
+    // 燕返し（ローカル役）
+    "swallow_return",
 ];
 
-pub fn check(hand: &HandAnalyzer) -> HashMap<&str, (&str, bool, u32)> {
+/// 解釈と和了の状況から、役が成立しているかを判定する関数
+type CheckFn = fn(&Decomposition, &WinContext) -> bool;
+
+/// 役と、その役が成立しているかを判定する関数
+///
+/// 役牌（三元牌）は複数成立しうるため`check_honor_tiles_dragons`で別に数える。
+/// 流し満貫は捨て牌で判定するため`nagashi::check_nagashi_mangan`で判定する。
+const CHECKS: [(Yaku, CheckFn); 42] = [
+    (Yaku::ReadyHand, check_ready_hand),
+    (Yaku::SevenPairs, check_seven_pairs),
+    (Yaku::SelfPick, check_self_pick),
+    (Yaku::OneShot, check_one_shot),
+    (Yaku::LastTileFromTheWall, check_last_tile_from_the_wall),
+    (Yaku::LastDiscard, check_last_discard),
+    (Yaku::DeadWallDraw, check_dead_wall_draw),
+    (Yaku::RobbingAQuad, check_robbing_a_quad),
+    (Yaku::DoubleReady, check_double_ready),
+    (Yaku::NoPointsHand, check_no_points_hand),
+    (
+        Yaku::OneSetOfIdenticalSequences,
+        check_one_set_of_identical_sequences,
+    ),
+    (Yaku::ThreeColourStraight, check_three_colour_straight),
+    (Yaku::Straight, check_straight),
+    (
+        Yaku::TwoSetsOfIdenticalSequences,
+        check_two_sets_of_identical_sequences,
+    ),
+    (Yaku::AllTripletHand, check_all_triplet_hand),
+    (Yaku::ThreeClosedTriplets, check_three_closed_triplets),
+    (Yaku::ThreeColourTriplets, check_three_colour_triplets),
+    (Yaku::ThreeKans, check_three_kans),
+    (Yaku::AllSimples, check_all_simples),
+    (Yaku::HonorTilesPlayersWind, check_honor_tiles_players_wind),
+    (
+        Yaku::HonorTilesPrevailingWind,
+        check_honor_tiles_prevailing_wind,
+    ),
+    (
+        Yaku::TerminalOrHonorInEachSet,
+        check_terminal_or_honor_in_each_set,
+    ),
+    (Yaku::TerminalInEachSet, check_terminal_in_each_set),
+    (Yaku::AllTerminalsAndHonors, check_all_terminals_and_honors),
+    (Yaku::LittleThreeDragons, check_little_three_dragons),
+    (Yaku::HalfFlush, check_half_flush),
+    (Yaku::Flush, check_flush),
+    (Yaku::ThirteenOrphans, check_thirteen_orphans),
+    (
+        Yaku::ThirteenWaitThirteenOrphans,
+        check_thirteen_wait_thirteen_orphans,
+    ),
+    (Yaku::FourConcealedTriplets, check_four_concealed_triplets),
+    (
+        Yaku::SingleWaitFourConcealedTriplets,
+        check_single_wait_four_concealed_triplets,
+    ),
+    (Yaku::BigThreeDragons, check_big_three_dragons),
+    (Yaku::LittleFourWinds, check_little_four_winds),
+    (Yaku::BigFourWinds, check_big_four_winds),
+    (Yaku::AllHonors, check_all_honors),
+    (Yaku::AllTerminals, check_all_terminals),
+    (Yaku::AllGreen, check_all_green),
+    (Yaku::NineGates, check_nine_gates),
+    (Yaku::TrueNineGates, check_true_nine_gates),
+    (Yaku::FourKans, check_four_kans),
+    (Yaku::HeavenlyHand, check_heavenly_hand),
+    (Yaku::HandOfEarth, check_hand_of_earth),
+];
+
+/// 和了した手牌と和了の状況から、成立した役とその翻数を返す
+///
+/// 手牌の解釈（`Decomposition::enumerate`）ごとに役を判定し、合計の翻数が最も大きいものを返す。
+/// 副露している場合は食い下がり後の翻数になり、門前でなければ成立しない役は含めない。
+/// 役満が成立している場合は役満だけを返す（複数の役満は複合する）。和了していなければ空を返す。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand::*;
+/// use mahjong_rs::hand_info::win_context::*;
+/// use mahjong_rs::hand_info::winning_hand::*;
+/// use mahjong_rs::hand_info::yaku::*;
+/// use mahjong_rs::tile::*;
+///
+/// // 七対子より二盃口の方が高い
+/// let hand = Hand::from("112233m445566p7z 7z");
+/// let context = WinContext::new(Tile::new(Tile::Z7), WinMethod::Ron, Wind::South, Wind::East);
+/// assert_eq!(
+///     evaluate(&hand, &context),
+///     vec![AchievedYaku { yaku: Yaku::TwoSetsOfIdenticalSequences, han: 3 }]
+/// );
+/// ```
+pub fn evaluate(hand: &Hand, context: &WinContext) -> Vec<AchievedYaku> {
+    let mut best: Vec<AchievedYaku> = Vec::new();
+    for decomposition in Decomposition::enumerate(hand, context.winning_tile, context.method) {
+        let result = evaluate_decomposition(&decomposition, context);
+        if total_han(&result) > total_han(&best) {
+            best = result;
+        }
+    }
+    return best;
+}
+
+/// 手牌の1つの解釈について、成立した役とその翻数を返す
+pub fn evaluate_decomposition(
+    decomposition: &Decomposition,
+    context: &WinContext,
+) -> Vec<AchievedYaku> {
+    // 成立した役と、その役が成立した数
+    let mut found: Vec<(Yaku, u32)> = Vec::new();
+    for &(yaku, check) in CHECKS.iter() {
+        if check(decomposition, context) {
+            found.push((yaku, 1));
+        }
+    }
+    let dragons = check_honor_tiles_dragons(decomposition);
+    if dragons > 0 {
+        found.push((Yaku::HonorTilesDragons, dragons));
+    }
+    return to_achieved(&found, decomposition.closed);
+}
+
+/// 成立した役の翻数の合計を返す
+fn total_han(achieved: &[AchievedYaku]) -> u32 {
+    return achieved.iter().map(|a| a.han).sum();
+}
+
+/// 成立した役と数から、翻数をつけた結果を作る
+///
+/// 役満があれば役満だけを残し、門前でなければ成立しない役を除く。結果は`Yaku`の順に並べる。
+fn to_achieved(found: &[(Yaku, u32)], closed: bool) -> Vec<AchievedYaku> {
+    let has_yakuman = found.iter().any(|(yaku, _)| yaku.is_yakuman());
+    let mut result: Vec<AchievedYaku> = found
+        .iter()
+        .filter(|(yaku, _)| yaku.is_yakuman() == has_yakuman)
+        .filter_map(|&(yaku, count)| {
+            yaku.han(closed).map(|han| AchievedYaku {
+                yaku,
+                han: han * count,
+            })
+        })
+        .collect();
+    result.sort_by_key(|achieved| achieved.yaku);
+    return result;
+}
+
+/// 和了した手牌と和了の状況から役を判定する
+///
+/// `HAND_NAME`の各キーに対して、(日本語の名前, 成立したか, 翻数)を返す。
+/// 互換性のために残しているもので、新しいコードでは`evaluate`を使う。
+pub fn check(
+    hand: &Hand,
+    context: &WinContext,
+) -> HashMap<&'static str, (&'static str, bool, u32)> {
+    return check_localized(hand, context, Locale::Japanese);
+}
+
+/// 和了した手牌と和了の状況から役を判定する
+///
+/// `check`と同じだが、名前を`locale`の言語で返す。
+pub fn check_localized(
+    hand: &Hand,
+    context: &WinContext,
+    locale: Locale,
+) -> HashMap<&'static str, (&'static str, bool, u32)> {
+    let achieved = evaluate(hand, context);
     let mut result = HashMap::new();
-    for i in 0..HAND_NAME.len() {
-        result.insert(HAND_NAME[i], ("unknown", false, 0));
+    for yaku in Yaku::ALL.iter() {
+        let name = yaku.localized_name(locale);
+        let value = match achieved.iter().find(|a| a.yaku == *yaku) {
+            Some(a) => (name, true, a.han),
+            None => (name, false, 0),
+        };
+        result.insert(yaku.key(), value);
     }
+    return result;
+}
 
-    // 立直
-    result.insert("ready_hand", check_ready_hand(hand));
-    // 七対子
-    result.insert("seven_pairs", check_seven_pairs(hand));
-    // 流し満貫
-    result.insert("nagashi_mangan", check_nagashi_mangan(hand));
-    // 門前清自摸和
-    result.insert("self_pick", check_self_pick(hand));
-    // 一発
-    result.insert("one_shot", check_one_shot(hand));
-    // 海底撈月
-    result.insert(
-        "last_tile_from_the_wall",
-        check_last_tile_from_the_wall(hand),
-    );
-    // 河底撈魚
-    result.insert("last_discard", check_last_discard(hand));
-    // 嶺上開花
-    result.insert("dead_wall_draw", check_dead_wall_draw(hand));
-    // 搶槓
-    result.insert("robbing_a_quad", check_robbing_a_quad(hand));
-    // ダブル立直
-    result.insert("double_ready", check_double_ready(hand));
-    // 平和
-    result.insert("no_points_hand", check_no_points_hand(hand));
-    // 一盃口
-    result.insert(
-        "one_set_of_identical_sequences",
-        check_one_set_of_identical_sequences(hand),
-    );
-    // 三色同順
-    result.insert("three_colour_straight", check_three_colour_straight(hand));
-    // 一気通貫
-    result.insert("straight", check_straight(hand));
-    // 二盃口
-    result.insert(
-        "two_sets_of_identical_sequences",
-        check_two_sets_of_identical_sequences(hand),
-    );
-    // 対々和
-    result.insert("all_triplet_hand", check_all_triplet_hand(hand));
-    // 三暗刻
-    result.insert("three_closed_triplets", check_three_closed_triplets(hand));
-    // 三色同刻
-    result.insert("three_colour_triplets", check_three_colour_triplets(hand));
-    // 断么九
-    result.insert("all_simples", check_all_simples(hand));
-    // 役牌（自風牌）
-    result.insert(
-        "honor_tiles_players_wind",
-        check_honor_tiles_players_wind(hand),
-    );
-    // 役牌（場風牌）
-    result.insert(
-        "honor_tiles_prevailing_wind",
-        check_honor_tiles_prevailing_wind(hand),
-    );
-    // 役牌（三元牌）
-    result.insert("honor_tiles_dragons", check_honor_tiles_dragons(hand));
-    // 混全帯么九
-    result.insert(
-        "terminal_or_honor_in_each_set",
-        check_terminal_or_honor_in_each_set(hand),
-    );
-    // 純全帯么九
-    result.insert("terminal_in_each_set", check_terminal_in_each_set(hand));
-    // 混老頭
-    result.insert(
-        "all_terminals_and_honors",
-        check_all_terminals_and_honors(hand),
-    );
-    // 小三元
-    result.insert("little_three_dragons", check_little_three_dragons(hand));
-    // 混一色
-    result.insert("half_flush", check_half_flush(hand));
-    // 清一色
-    result.insert("flush", check_flush(hand));
-    // 国士無双
-    result.insert("thirteen_orphans", check_thirteen_orphans(hand));
-    // 四暗刻
-    result.insert(
-        "four_concealed_triplets",
-        check_four_concealed_triplets(hand),
-    );
-    // 大三元
-    result.insert("big_three_dragons", check_big_three_dragons(hand));
-    // 小四喜
-    result.insert("little_four_winds", check_little_four_winds(hand));
-    // 大四喜
-    result.insert("big_four_winds", check_big_four_winds(hand));
-    // 字一色
-    result.insert("all_honors", check_all_honors(hand));
-    // 清老頭
-    result.insert("all_terminals", check_all_terminals(hand));
-    // 緑一色
-    result.insert("all_green", check_all_green(hand));
-    // 九蓮宝燈
-    result.insert("nine_gates", check_nine_gates(hand));
-    // 四槓子
-    result.insert("four_kans", check_four_kans(hand));
-    // 天和
-    result.insert("heavenly_hand", check_heavenly_hand(hand));
-    // 地和
-    result.insert("hand_of_earth", check_hand_of_earth(hand));
+/// 役牌（三元牌・自風牌・場風牌）か否かを返す
+fn is_honor_tile(tile: TileType, context: &WinContext) -> bool {
+    return matches!(tile, Tile::Z5..=Tile::Z7)
+        || tile == context.seat_wind as TileType
+        || tile == context.round_wind as TileType;
+}
 
+/// 同じ順子の組の数を返す（一盃口なら`1`、二盃口なら`2`）
+fn count_identical_sequences(d: &Decomposition) -> usize {
+    let mut firsts: Vec<TileType> = d
+        .sequential3
+        .iter()
+        .filter(|block| block.concealed)
+        .map(|block| block.get())
+        .collect();
+    firsts.sort_unstable();
+    let mut result = 0;
+    let mut i = 0;
+    while i + 1 < firsts.len() {
+        if firsts[i] == firsts[i + 1] {
+            result += 1;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
     return result;
 }
 
+/// 萬子・筒子・索子で同じ数字のブロックが揃っているか否かを返す
+fn has_three_colours(firsts: &[TileType]) -> bool {
+    return (0..9).any(|n| (0..3).all(|suit| firsts.contains(&(suit * 9 + n))));
+}
+
+/// 手牌に含まれる数牌の色（萬子`0`・筒子`1`・索子`2`）と、字牌を含むか否かを返す
+fn suits(d: &Decomposition) -> (Vec<TileType>, bool) {
+    let kinds = d.kinds();
+    let mut result: Vec<TileType> = kinds
+        .iter()
+        .filter(|&&i| i < Tile::Z1)
+        .map(|&i| i / 9)
+        .collect();
+    result.dedup();
+    return (result, kinds.iter().any(|&i| i >= Tile::Z1));
+}
+
+/// 全ての面子と雀頭に么九牌が含まれ、順子が1つ以上あるか否かを返す
+fn is_outside_hand(d: &Decomposition) -> bool {
+    return d.form == WinningHandForm::Normal
+        && !d.sequential3.is_empty()
+        && d.pairs.iter().all(|b| b.has_1_9_honor())
+        && d.same3.iter().all(|b| b.has_1_9_honor())
+        && d.sequential3.iter().all(|b| b.has_1_9_honor());
+}
+
 /// 立直
-fn check_ready_hand(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("立直", false, 0);
-    }
-    unimplemented!();
+fn check_ready_hand(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.riichi == RiichiState::Riichi;
 }
 /// 七対子
-fn check_seven_pairs(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    let name = "七対子";
-    if hand.shanten > -1 {
-        return (name, false, 0);
-    }
-    return if hand.form == WinningHandForm::SevenPairs {
-        (name, true, 2)
-    } else {
-        (name, false, 0)
-    };
-}
-/// 流し満貫
-fn check_nagashi_mangan(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("流し満貫", false, 0);
-    }
-    unimplemented!();
+fn check_seven_pairs(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.form == WinningHandForm::SevenPairs;
 }
 /// 門前清自摸和
-fn check_self_pick(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("門前清自摸和", false, 0);
-    }
-    unimplemented!();
+fn check_self_pick(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.is_tsumo();
 }
 /// 一発
-fn check_one_shot(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("一発", false, 0);
-    }
-    unimplemented!();
+fn check_one_shot(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.is_riichi() && context.ippatsu;
 }
 /// 海底撈月
-fn check_last_tile_from_the_wall(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("海底撈月", false, 0);
-    }
-    unimplemented!();
+fn check_last_tile_from_the_wall(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.last_tile && context.is_tsumo() && !context.after_kan;
 }
 /// 河底撈魚
-fn check_last_discard(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("河底撈魚", false, 0);
-    }
-    unimplemented!();
+fn check_last_discard(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.last_tile && !context.is_tsumo();
 }
 /// 嶺上開花
-fn check_dead_wall_draw(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("嶺上開花", false, 0);
-    }
-    unimplemented!();
+fn check_dead_wall_draw(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.after_kan && context.is_tsumo();
 }
 /// 搶槓
-fn check_robbing_a_quad(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("搶槓", false, 0);
-    }
-    unimplemented!();
+fn check_robbing_a_quad(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.robbing_kan && !context.is_tsumo();
 }
 /// ダブル立直
-fn check_double_ready(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("ダブル立直", false, 0);
-    }
-    unimplemented!();
+fn check_double_ready(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.riichi == RiichiState::DoubleRiichi;
 }
 /// 平和
-fn check_no_points_hand(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("平和", false, 0);
-    }
-    unimplemented!();
+fn check_no_points_hand(d: &Decomposition, context: &WinContext) -> bool {
+    return d.form == WinningHandForm::Normal
+        && d.sequential3.len() == 4
+        && d.wait == Wait::Ryanmen
+        && !is_honor_tile(d.head().get(), context);
 }
 /// 一盃口
-fn check_one_set_of_identical_sequences(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("一盃口", false, 0);
-    }
-    unimplemented!();
+fn check_one_set_of_identical_sequences(d: &Decomposition, _context: &WinContext) -> bool {
+    return count_identical_sequences(d) == 1;
 }
 /// 三色同順
-fn check_three_colour_straight(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("三色同順", false, 0);
-    }
-    unimplemented!();
+fn check_three_colour_straight(d: &Decomposition, _context: &WinContext) -> bool {
+    let firsts: Vec<TileType> = d.sequential3.iter().map(|b| b.get()).collect();
+    return has_three_colours(&firsts);
 }
 /// 一気通貫
-fn check_straight(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("一気通貫", false, 0);
-    }
-    unimplemented!();
+fn check_straight(d: &Decomposition, _context: &WinContext) -> bool {
+    let firsts: Vec<TileType> = d.sequential3.iter().map(|b| b.get()).collect();
+    return (0..3).any(|suit| (0..3).all(|n| firsts.contains(&(suit * 9 + n * 3))));
 }
 /// 二盃口
-fn check_two_sets_of_identical_sequences(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("二盃口", false, 0);
-    }
-    unimplemented!();
+fn check_two_sets_of_identical_sequences(d: &Decomposition, _context: &WinContext) -> bool {
+    return count_identical_sequences(d) == 2;
 }
 /// 対々和
-fn check_all_triplet_hand(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("対々和", false, 0);
-    }
-    unimplemented!();
+fn check_all_triplet_hand(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.form == WinningHandForm::Normal && d.same3.len() == 4;
 }
 /// 三暗刻
-fn check_three_closed_triplets(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("三暗刻", false, 0);
-    }
-    unimplemented!();
+fn check_three_closed_triplets(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.same3.iter().filter(|b| b.concealed).count() == 3;
 }
 /// 三色同刻
-fn check_three_colour_triplets(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("三色同刻", false, 0);
-    }
-    unimplemented!();
+fn check_three_colour_triplets(d: &Decomposition, _context: &WinContext) -> bool {
+    let firsts: Vec<TileType> = d.same3.iter().map(|b| b.get()).collect();
+    return has_three_colours(&firsts);
+}
+/// 三槓子
+fn check_three_kans(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.same3.iter().filter(|b| b.kan).count() == 3;
 }
 /// 断么九
-fn check_all_simples(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("断么九", false, 0);
-    }
-    unimplemented!();
+fn check_all_simples(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.kinds().iter().all(|&i| !Tile::new(i).is_1_9_honor());
 }
 /// 役牌（自風牌）
-fn check_honor_tiles_players_wind(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("役牌（自風牌）", false, 0);
-    }
-    unimplemented!();
+fn check_honor_tiles_players_wind(d: &Decomposition, context: &WinContext) -> bool {
+    return d
+        .same3
+        .iter()
+        .any(|b| b.get() == context.seat_wind as TileType);
 }
 /// 役牌（場風牌）
-fn check_honor_tiles_prevailing_wind(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("役牌（場風牌）", false, 0);
-    }
-    unimplemented!();
+fn check_honor_tiles_prevailing_wind(d: &Decomposition, context: &WinContext) -> bool {
+    return d
+        .same3
+        .iter()
+        .any(|b| b.get() == context.round_wind as TileType);
 }
 /// 役牌（三元牌）
-fn check_honor_tiles_dragons(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("役牌（三元牌）", false, 0);
-    }
-    unimplemented!();
+///
+/// 三元牌の刻子・槓子の数を返す。
+fn check_honor_tiles_dragons(d: &Decomposition) -> u32 {
+    return d
+        .same3
+        .iter()
+        .filter(|b| matches!(b.get(), Tile::Z5..=Tile::Z7))
+        .count() as u32;
 }
 /// 混全帯么九
-fn check_terminal_or_honor_in_each_set(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("混全帯么九", false, 0);
-    }
-    unimplemented!();
+fn check_terminal_or_honor_in_each_set(d: &Decomposition, _context: &WinContext) -> bool {
+    return is_outside_hand(d) && suits(d).1;
 }
 /// 純全帯么九
-fn check_terminal_in_each_set(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("純全帯么九", false, 0);
-    }
-    unimplemented!();
+fn check_terminal_in_each_set(d: &Decomposition, _context: &WinContext) -> bool {
+    return is_outside_hand(d) && !suits(d).1;
 }
 /// 混老頭
-fn check_all_terminals_and_honors(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("混老頭", false, 0);
-    }
-    unimplemented!();
+fn check_all_terminals_and_honors(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.form != WinningHandForm::ThirteenOrphens
+        && d.kinds().iter().all(|&i| Tile::new(i).is_1_9_honor());
 }
 /// 小三元
-fn check_little_three_dragons(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("小三元", false, 0);
-    }
-    unimplemented!();
+fn check_little_three_dragons(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.form == WinningHandForm::Normal
+        && check_honor_tiles_dragons(d) == 2
+        && matches!(d.head().get(), Tile::Z5..=Tile::Z7);
 }
 /// 混一色
-fn check_half_flush(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("混一色", false, 0);
-    }
-    unimplemented!();
+fn check_half_flush(d: &Decomposition, _context: &WinContext) -> bool {
+    let (suits, honor) = suits(d);
+    return suits.len() == 1 && honor;
 }
 /// 清一色
-fn check_flush(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("清一色", false, 0);
-    }
-    unimplemented!();
+fn check_flush(d: &Decomposition, _context: &WinContext) -> bool {
+    let (suits, honor) = suits(d);
+    return suits.len() == 1 && !honor;
 }
 /// 国士無双
-fn check_thirteen_orphans(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    let name = "国士無双";
-    if hand.shanten > -1 {
-        return (name, false, 0);
-    }
-    return if hand.form == WinningHandForm::ThirteenOrphens {
-        (name, true, 13)
-    } else {
-        (name, false, 0)
-    };
+fn check_thirteen_orphans(d: &Decomposition, context: &WinContext) -> bool {
+    return d.form == WinningHandForm::ThirteenOrphens
+        && !check_thirteen_wait_thirteen_orphans(d, context);
+}
+/// 国士無双十三面待ち
+///
+/// 和了牌が雀頭になっていれば、和了前は13種の么九牌が1枚ずつ揃っていた。
+fn check_thirteen_wait_thirteen_orphans(d: &Decomposition, context: &WinContext) -> bool {
+    return d.form == WinningHandForm::ThirteenOrphens
+        && d.pairs[0].get() == context.winning_tile.get();
+}
+/// 暗刻（暗槓を含む）が4つあるか否かを返す
+fn has_four_concealed_triplets(d: &Decomposition) -> bool {
+    return d.closed && d.same3.iter().filter(|b| b.concealed).count() == 4;
 }
 /// 四暗刻
-fn check_four_concealed_triplets(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("四暗刻", false, 0);
-    }
-    unimplemented!();
+fn check_four_concealed_triplets(d: &Decomposition, _context: &WinContext) -> bool {
+    return has_four_concealed_triplets(d) && d.wait != Wait::Tanki;
+}
+/// 四暗刻単騎
+fn check_single_wait_four_concealed_triplets(d: &Decomposition, _context: &WinContext) -> bool {
+    return has_four_concealed_triplets(d) && d.wait == Wait::Tanki;
 }
 /// 大三元
-fn check_big_three_dragons(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("大三元", false, 0);
-    }
-    unimplemented!();
+fn check_big_three_dragons(d: &Decomposition, _context: &WinContext) -> bool {
+    return check_honor_tiles_dragons(d) == 3;
+}
+/// 風牌の刻子・槓子の数を返す
+fn count_wind_triplets(d: &Decomposition) -> usize {
+    return d
+        .same3
+        .iter()
+        .filter(|b| b.get() >= Tile::Z1 && b.get() <= Tile::Z4)
+        .count();
 }
 /// 小四喜
-fn check_little_four_winds(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("小四喜", false, 0);
-    }
-    unimplemented!();
+fn check_little_four_winds(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.form == WinningHandForm::Normal
+        && count_wind_triplets(d) == 3
+        && (Tile::Z1..=Tile::Z4).contains(&d.head().get());
 }
 /// 大四喜
-fn check_big_four_winds(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("大四喜", false, 0);
-    }
-    unimplemented!();
+fn check_big_four_winds(d: &Decomposition, _context: &WinContext) -> bool {
+    return count_wind_triplets(d) == 4;
 }
 /// 字一色
-fn check_all_honors(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("字一色", false, 0);
-    }
-    unimplemented!();
+fn check_all_honors(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.kinds().iter().all(|&i| i >= Tile::Z1);
 }
 /// 清老頭
-fn check_all_terminals(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("清老頭", false, 0);
-    }
-    unimplemented!();
+fn check_all_terminals(d: &Decomposition, _context: &WinContext) -> bool {
+    return d
+        .kinds()
+        .iter()
+        .all(|&i| i < Tile::Z1 && Tile::new(i).is_1_or_9());
 }
 /// 緑一色
-fn check_all_green(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("緑一色", false, 0);
-    }
-    unimplemented!();
+///
+/// 發がなくても成立する。
+fn check_all_green(d: &Decomposition, _context: &WinContext) -> bool {
+    const GREEN: [TileType; 6] = [Tile::S2, Tile::S3, Tile::S4, Tile::S6, Tile::S8, Tile::Z6];
+    return d.kinds().iter().all(|i| GREEN.contains(i));
+}
+/// 九蓮宝燈の形（`1112345678999`＋同じ色の1枚）の色の最初の牌を返す
+fn nine_gates_suit(d: &Decomposition) -> Option<TileType> {
+    if !d.closed || d.form != WinningHandForm::Normal || !d.same3.iter().all(|b| !b.kan) {
+        return None;
+    }
+    const BASE: [u32; 9] = [3, 1, 1, 1, 1, 1, 1, 1, 3];
+    return [Tile::M1, Tile::P1, Tile::S1]
+        .iter()
+        .copied()
+        .find(|&first| {
+            (0..9).all(|n| d.tiles[(first + n) as usize] >= BASE[n as usize])
+                && d.tiles[first as usize..(first + 9) as usize]
+                    .iter()
+                    .sum::<u32>()
+                    == 14
+        });
 }
 /// 九蓮宝燈
-fn check_nine_gates(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("九蓮宝燈", false, 0);
-    }
-    unimplemented!();
+fn check_nine_gates(d: &Decomposition, context: &WinContext) -> bool {
+    return nine_gates_suit(d).is_some() && !check_true_nine_gates(d, context);
+}
+/// 純正九蓮宝燈
+///
+/// 和了前が`1112345678999`の九面待ちだったもの。和了牌が2枚目・4枚目になっている。
+fn check_true_nine_gates(d: &Decomposition, context: &WinContext) -> bool {
+    return match nine_gates_suit(d) {
+        Some(first) => {
+            let w = context.winning_tile.get();
+            let expected = if w == first || w == first + 8 { 4 } else { 2 };
+            d.tiles[w as usize] == expected
+        }
+        None => false,
+    };
 }
 /// 四槓子
-fn check_four_kans(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("四槓子", false, 0);
-    }
-    unimplemented!();
+fn check_four_kans(d: &Decomposition, _context: &WinContext) -> bool {
+    return d.same3.iter().filter(|b| b.kan).count() == 4;
 }
 /// 天和
-fn check_heavenly_hand(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("天和", false, 0);
-    }
-    unimplemented!();
+fn check_heavenly_hand(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.first_turn && context.is_tsumo() && context.is_dealer();
 }
 /// 地和
-fn check_hand_of_earth(hand: &HandAnalyzer) -> (&str, bool, u32) {
-    if hand.shanten > -1 {
-        return ("地和", false, 0);
-    }
-    unimplemented!();
+fn check_hand_of_earth(_d: &Decomposition, context: &WinContext) -> bool {
+    return context.first_turn && context.is_tsumo() && !context.is_dealer();
 }
 
 /// ユニットテスト
@@ -509,24 +611,369 @@ mod tests {
     use super::*;
     use crate::hand::*;
 
+    /// 手牌を和了牌でロン（`tsumo`なら自摸）したときの役を返す
+    fn yaku_of(hand_str: &str, tile: TileType, tsumo: bool) -> Vec<(Yaku, u32)> {
+        let hand = Hand::from(hand_str);
+        let method = if tsumo {
+            WinMethod::Tsumo
+        } else {
+            WinMethod::Ron
+        };
+        let context = WinContext::new(Tile::new(tile), method, Wind::South, Wind::East);
+        return evaluate(&hand, &context)
+            .iter()
+            .map(|a| (a.yaku, a.han))
+            .collect();
+    }
+
     #[test]
     /// 七対子で和了った
     fn win_by_seven_pairs() {
-        let test_str = "1122m3344p5566s1z 1z";
-        let test = Hand::from(test_str);
-        let test_analyzer = HandAnalyzer::calc(&test);
-        assert_eq!(check_seven_pairs(&test_analyzer), ("七対子", true, 2));
+        assert_eq!(
+            yaku_of("1122m3344p5566s1z 1z", Tile::Z1, false),
+            vec![(Yaku::SevenPairs, 2)]
+        );
     }
 
     #[test]
     /// 国士無双で和了った
     fn win_by_thirteen_orphens() {
-        let test_str = "19m19p19s1234567z 1m";
-        let test = Hand::from(test_str);
-        let test_analyzer = HandAnalyzer::calc(&test);
         assert_eq!(
-            check_thirteen_orphans(&test_analyzer),
-            ("国士無双", true, 13)
+            yaku_of("119m19p19s123456z 7z", Tile::Z7, false),
+            vec![(Yaku::ThirteenOrphans, 13)]
+        );
+        // 13種の么九牌が揃った十三面待ち
+        assert_eq!(
+            yaku_of("19m19p19s1234567z 1m", Tile::M1, false),
+            vec![(Yaku::ThirteenWaitThirteenOrphans, 26)]
+        );
+    }
+
+    #[test]
+    /// 四暗刻と四暗刻単騎
+    fn win_by_four_concealed_triplets() {
+        assert_eq!(
+            yaku_of("111m333p555s77z99p 9p", Tile::P9, true),
+            vec![(Yaku::FourConcealedTriplets, 13)]
+        );
+        // ロンで完成した刻子は明刻なので三暗刻・対々和
+        assert_eq!(
+            yaku_of("111m333p555s77z99p 9p", Tile::P9, false),
+            vec![(Yaku::AllTripletHand, 2), (Yaku::ThreeClosedTriplets, 2),]
+        );
+        assert_eq!(
+            yaku_of("111m333p555s777z9p 9p", Tile::P9, false),
+            vec![(Yaku::SingleWaitFourConcealedTriplets, 26)]
+        );
+    }
+
+    #[test]
+    /// 九蓮宝燈と純正九蓮宝燈
+    fn win_by_nine_gates() {
+        assert_eq!(
+            yaku_of("1112345678999m 5m", Tile::M5, false),
+            vec![(Yaku::TrueNineGates, 26)]
+        );
+        assert_eq!(
+            yaku_of("1112345678999m 1m", Tile::M1, true),
+            vec![(Yaku::TrueNineGates, 26)]
+        );
+        assert_eq!(
+            yaku_of("1112245678999p 3p", Tile::P3, false),
+            vec![(Yaku::NineGates, 13)]
+        );
+    }
+
+    #[test]
+    /// 字牌・老頭牌・緑の役満
+    fn win_by_tile_yakuman() {
+        assert_eq!(
+            yaku_of("11223344556677z", Tile::Z7, false),
+            vec![(Yaku::AllHonors, 13)]
+        );
+        assert_eq!(
+            yaku_of("1119m 999p 111s 999s 9m", Tile::M9, false),
+            vec![(Yaku::AllTerminals, 13)]
+        );
+        assert_eq!(
+            yaku_of("223344s666s888s6z 6z", Tile::Z6, false),
+            vec![(Yaku::AllGreen, 13)]
+        );
+    }
+
+    #[test]
+    /// 三元牌・風牌の役満
+    fn win_by_honor_yakuman() {
+        assert_eq!(
+            yaku_of("555z666z777z12m99p 3m", Tile::M3, false),
+            vec![(Yaku::BigThreeDragons, 13)]
+        );
+        assert_eq!(
+            yaku_of("111z222z333z5m 444z 5m", Tile::M5, false),
+            vec![(Yaku::BigFourWinds, 26)]
+        );
+        assert_eq!(
+            yaku_of("111z222z333z4z567m 4z", Tile::Z4, false),
+            vec![(Yaku::LittleFourWinds, 13)]
+        );
+    }
+
+    #[test]
+    /// 役満は複合する
+    fn yakuman_stack() {
+        // 字一色・大四喜・四暗刻単騎
+        assert_eq!(
+            yaku_of("111z222z333z444z5z 5z", Tile::Z5, true),
+            vec![
+                (Yaku::SingleWaitFourConcealedTriplets, 26),
+                (Yaku::BigFourWinds, 26),
+                (Yaku::AllHonors, 13),
+            ]
+        );
+    }
+
+    #[test]
+    /// 四槓子
+    fn win_by_four_kans() {
+        assert_eq!(
+            yaku_of("5m 1111p 2222p 3333s 4444s 5m", Tile::M5, false),
+            vec![(Yaku::FourKans, 13)]
+        );
+    }
+
+    #[test]
+    /// 平和・断么九・一盃口
+    fn no_points_hand() {
+        assert_eq!(
+            yaku_of("22334m567p55p678s 4m", Tile::M4, false),
+            vec![
+                (Yaku::NoPointsHand, 1),
+                (Yaku::OneSetOfIdenticalSequences, 1),
+                (Yaku::AllSimples, 1)
+            ]
+        );
+    }
+
+    #[test]
+    /// 二盃口は一盃口と複合しない
+    fn two_sets_of_identical_sequences() {
+        assert_eq!(
+            yaku_of("112233m445566p7z 7z", Tile::Z7, false),
+            vec![(Yaku::TwoSetsOfIdenticalSequences, 3)]
+        );
+    }
+
+    #[test]
+    /// 門前清自摸和・三色同順・純全帯么九
+    fn terminal_in_each_set() {
+        assert_eq!(
+            yaku_of("123m99m123p89p123s 7p", Tile::P7, true),
+            vec![
+                (Yaku::SelfPick, 1),
+                (Yaku::ThreeColourStraight, 2),
+                (Yaku::TerminalInEachSet, 3)
+            ]
+        );
+    }
+
+    #[test]
+    /// 副露した混一色・混全帯么九・役牌は食い下がる
+    fn open_hand() {
+        assert_eq!(
+            yaku_of("123m999m1z 777z 789m 1z", Tile::Z1, false),
+            vec![
+                (Yaku::HonorTilesDragons, 1),
+                (Yaku::TerminalOrHonorInEachSet, 1),
+                (Yaku::HalfFlush, 2)
+            ]
+        );
+    }
+
+    #[test]
+    /// 対々和・三暗刻
+    fn all_triplet_hand() {
+        assert_eq!(
+            yaku_of("111m222p333s5z 777z 5z", Tile::Z5, true),
+            vec![
+                (Yaku::AllTripletHand, 2),
+                (Yaku::ThreeClosedTriplets, 2),
+                (Yaku::HonorTilesDragons, 1)
+            ]
+        );
+        // ロンで完成した刻子は暗刻に数えない
+        assert_eq!(
+            yaku_of("111m222p33s55z 777z 3s", Tile::S3, false),
+            vec![(Yaku::AllTripletHand, 2), (Yaku::HonorTilesDragons, 1)]
+        );
+    }
+
+    #[test]
+    /// 小三元
+    fn little_three_dragons() {
+        assert_eq!(
+            yaku_of("123m555z666z7z 456m 7z", Tile::Z7, false),
+            vec![
+                (Yaku::HonorTilesDragons, 2),
+                (Yaku::LittleThreeDragons, 2),
+                (Yaku::HalfFlush, 2)
+            ]
+        );
+    }
+
+    #[test]
+    /// 一気通貫・自風牌・混一色
+    fn straight() {
+        assert_eq!(
+            yaku_of("123456789p22z33z 2z", Tile::Z2, false),
+            vec![
+                (Yaku::Straight, 2),
+                (Yaku::HonorTilesPlayersWind, 1),
+                (Yaku::HalfFlush, 3)
+            ]
+        );
+    }
+
+    #[test]
+    /// 清一色
+    fn flush() {
+        assert_eq!(
+            yaku_of("2233445566778s 8s", Tile::S8, false),
+            vec![
+                (Yaku::NoPointsHand, 1),
+                (Yaku::TwoSetsOfIdenticalSequences, 3),
+                (Yaku::AllSimples, 1),
+                (Yaku::Flush, 6)
+            ]
+        );
+    }
+
+    #[test]
+    /// 和了の状況で決まる役
+    fn situational_yaku() {
+        let hand = Hand::from("1122m3344p5566s1z 1z");
+        let mut context = WinContext::new(
+            Tile::new(Tile::Z1),
+            WinMethod::Tsumo,
+            Wind::South,
+            Wind::East,
+        );
+        context.riichi = RiichiState::Riichi;
+        context.ippatsu = true;
+        context.last_tile = true;
+        let yaku: Vec<Yaku> = evaluate(&hand, &context).iter().map(|a| a.yaku).collect();
+        assert_eq!(
+            yaku,
+            vec![
+                Yaku::ReadyHand,
+                Yaku::SevenPairs,
+                Yaku::SelfPick,
+                Yaku::OneShot,
+                Yaku::LastTileFromTheWall
+            ]
+        );
+
+        // ロン和了
+        context.method = WinMethod::Ron;
+        context.robbing_kan = true;
+        context.ippatsu = false;
+        let yaku: Vec<Yaku> = evaluate(&hand, &context).iter().map(|a| a.yaku).collect();
+        assert_eq!(
+            yaku,
+            vec![
+                Yaku::ReadyHand,
+                Yaku::SevenPairs,
+                Yaku::LastDiscard,
+                Yaku::RobbingAQuad
+            ]
+        );
+    }
+
+    #[test]
+    /// 天和・地和
+    fn first_turn_win() {
+        let hand = Hand::from("1122m3344p5566s1z 1z");
+        let mut context = WinContext::new(
+            Tile::new(Tile::Z1),
+            WinMethod::Tsumo,
+            Wind::East,
+            Wind::East,
+        );
+        context.first_turn = true;
+        let yaku: Vec<Yaku> = evaluate(&hand, &context).iter().map(|a| a.yaku).collect();
+        assert_eq!(yaku, vec![Yaku::HeavenlyHand]);
+        context.seat_wind = Wind::West;
+        let yaku: Vec<Yaku> = evaluate(&hand, &context).iter().map(|a| a.yaku).collect();
+        assert_eq!(yaku, vec![Yaku::HandOfEarth]);
+    }
+
+    #[test]
+    /// 和了していなければ状況に関わらず役はつかない
+    fn not_winning() {
+        let test = Hand::from("1122m3344p5566s1z 2z");
+        let mut context = WinContext::new(
+            Tile::new(Tile::Z2),
+            WinMethod::Tsumo,
+            Wind::East,
+            Wind::East,
+        );
+        context.riichi = RiichiState::DoubleRiichi;
+        assert!(evaluate(&test, &context).is_empty());
+    }
+
+    #[test]
+    /// 文字列をキーにした結果
+    fn check_by_name() {
+        let hand = Hand::from("22334m567p55p678s 4m");
+        let context = WinContext::new(Tile::new(Tile::M4), WinMethod::Ron, Wind::South, Wind::East);
+        let result = check(&hand, &context);
+        assert_eq!(result["all_simples"], ("断么九", true, 1));
+        assert_eq!(result["flush"], ("清一色", false, 0));
+        assert_eq!(result.len(), HAND_NAME.len());
+    }
+
+    #[test]
+    /// 役満があれば役満だけを残す
+    fn yakuman_only() {
+        let found = [
+            (Yaku::ReadyHand, 1),
+            (Yaku::ThirteenOrphans, 1),
+            (Yaku::HeavenlyHand, 1),
+        ];
+        assert_eq!(
+            to_achieved(&found, true),
+            vec![
+                AchievedYaku {
+                    yaku: Yaku::ThirteenOrphans,
+                    han: 13
+                },
+                AchievedYaku {
+                    yaku: Yaku::HeavenlyHand,
+                    han: 13
+                },
+            ]
+        );
+    }
+
+    #[test]
+    /// 副露していれば食い下がり、門前でなければ成立しない役を除く
+    fn open_han() {
+        let found = [
+            (Yaku::SelfPick, 1),
+            (Yaku::HonorTilesDragons, 2),
+            (Yaku::HalfFlush, 1),
+        ];
+        assert_eq!(
+            to_achieved(&found, false),
+            vec![
+                AchievedYaku {
+                    yaku: Yaku::HonorTilesDragons,
+                    han: 2
+                },
+                AchievedYaku {
+                    yaku: Yaku::HalfFlush,
+                    han: 2
+                },
+            ]
         );
     }
 }
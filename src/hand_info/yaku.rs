@@ -0,0 +1,404 @@
+use crate::locale::*;
+
+/// 役
+///
+/// 並び順は`winning_hand::HAND_NAME`と同じ。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Yaku {
+    /// 立直
+    ReadyHand,
+    /// 七対子
+    SevenPairs,
+    /// 流し満貫
+    NagashiMangan,
+    /// 門前清自摸和
+    SelfPick,
+    /// 一発
+    OneShot,
+    /// 海底撈月
+    LastTileFromTheWall,
+    /// 河底撈魚
+    LastDiscard,
+    /// 嶺上開花
+    DeadWallDraw,
+    /// 搶槓
+    RobbingAQuad,
+    /// ダブル立直
+    DoubleReady,
+    /// 平和
+    NoPointsHand,
+    /// 一盃口
+    OneSetOfIdenticalSequences,
+    /// 三色同順
+    ThreeColourStraight,
+    /// 一気通貫
+    Straight,
+    /// 二盃口
+    TwoSetsOfIdenticalSequences,
+    /// 対々和
+    AllTripletHand,
+    /// 三暗刻
+    ThreeClosedTriplets,
+    /// 三色同刻
+    ThreeColourTriplets,
+    /// 三槓子
+    ThreeKans,
+    /// 断么九
+    AllSimples,
+    /// 役牌（自風牌）
+    HonorTilesPlayersWind,
+    /// 役牌（場風牌）
+    HonorTilesPrevailingWind,
+    /// 役牌（三元牌）
+    HonorTilesDragons,
+    /// 混全帯么九
+    TerminalOrHonorInEachSet,
+    /// 純全帯么九
+    TerminalInEachSet,
+    /// 混老頭
+    AllTerminalsAndHonors,
+    /// 小三元
+    LittleThreeDragons,
+    /// 混一色
+    HalfFlush,
+    /// 清一色
+    Flush,
+    /// 国士無双
+    ThirteenOrphans,
+    /// 国士無双十三面待ち
+    ThirteenWaitThirteenOrphans,
+    /// 四暗刻
+    FourConcealedTriplets,
+    /// 四暗刻単騎
+    SingleWaitFourConcealedTriplets,
+    /// 大三元
+    BigThreeDragons,
+    /// 小四喜
+    LittleFourWinds,
+    /// 大四喜
+    BigFourWinds,
+    /// 字一色
+    AllHonors,
+    /// 清老頭
+    AllTerminals,
+    /// 緑一色
+    AllGreen,
+    /// 九蓮宝燈
+    NineGates,
+    /// 純正九蓮宝燈
+    TrueNineGates,
+    /// 四槓子
+    FourKans,
+    /// 天和
+    HeavenlyHand,
+    /// 地和
+    HandOfEarth,
+    /// 人和（ローカル役）
+    BlessingOfMan,
+    /// 大車輪（ローカル役）
+    BigWheels,
+    /// 三連刻（ローカル役）
+    ThreeConsecutiveTriplets,
+    /// 一色三順（ローカル役）
+    PureTripleChow,
+    /// オープン立直（ローカル役）
+    OpenRiichi,
+    /// 十三不塔（ローカル役）
+    ThirteenUnrelatedTiles,
+    /// 燕返し（ローカル役）
+    SwallowReturn,
+}
+
+impl Yaku {
+    /// 全ての役
+    pub const ALL: [Yaku; 51] = [
+        Yaku::ReadyHand,
+        Yaku::SevenPairs,
+        Yaku::NagashiMangan,
+        Yaku::SelfPick,
+        Yaku::OneShot,
+        Yaku::LastTileFromTheWall,
+        Yaku::LastDiscard,
+        Yaku::DeadWallDraw,
+        Yaku::RobbingAQuad,
+        Yaku::DoubleReady,
+        Yaku::NoPointsHand,
+        Yaku::OneSetOfIdenticalSequences,
+        Yaku::ThreeColourStraight,
+        Yaku::Straight,
+        Yaku::TwoSetsOfIdenticalSequences,
+        Yaku::AllTripletHand,
+        Yaku::ThreeClosedTriplets,
+        Yaku::ThreeColourTriplets,
+        Yaku::ThreeKans,
+        Yaku::AllSimples,
+        Yaku::HonorTilesPlayersWind,
+        Yaku::HonorTilesPrevailingWind,
+        Yaku::HonorTilesDragons,
+        Yaku::TerminalOrHonorInEachSet,
+        Yaku::TerminalInEachSet,
+        Yaku::AllTerminalsAndHonors,
+        Yaku::LittleThreeDragons,
+        Yaku::HalfFlush,
+        Yaku::Flush,
+        Yaku::ThirteenOrphans,
+        Yaku::ThirteenWaitThirteenOrphans,
+        Yaku::FourConcealedTriplets,
+        Yaku::SingleWaitFourConcealedTriplets,
+        Yaku::BigThreeDragons,
+        Yaku::LittleFourWinds,
+        Yaku::BigFourWinds,
+        Yaku::AllHonors,
+        Yaku::AllTerminals,
+        Yaku::AllGreen,
+        Yaku::NineGates,
+        Yaku::TrueNineGates,
+        Yaku::FourKans,
+        Yaku::HeavenlyHand,
+        Yaku::HandOfEarth,
+        Yaku::BlessingOfMan,
+        Yaku::BigWheels,
+        Yaku::ThreeConsecutiveTriplets,
+        Yaku::PureTripleChow,
+        Yaku::OpenRiichi,
+        Yaku::ThirteenUnrelatedTiles,
+        Yaku::SwallowReturn,
+    ];
+
+    /// 役満の翻数
+    pub const YAKUMAN_HAN: u32 = 13;
+
+    /// `winning_hand::check`で使う英語名のキーを返す
+    pub fn key(&self) -> &'static str {
+        return match self {
+            Yaku::ReadyHand => "ready_hand",
+            Yaku::SevenPairs => "seven_pairs",
+            Yaku::NagashiMangan => "nagashi_mangan",
+            Yaku::SelfPick => "self_pick",
+            Yaku::OneShot => "one_shot",
+            Yaku::LastTileFromTheWall => "last_tile_from_the_wall",
+            Yaku::LastDiscard => "last_discard",
+            Yaku::DeadWallDraw => "dead_wall_draw",
+            Yaku::RobbingAQuad => "robbing_a_quad",
+            Yaku::DoubleReady => "double_ready",
+            Yaku::NoPointsHand => "no_points_hand",
+            Yaku::OneSetOfIdenticalSequences => "one_set_of_identical_sequences",
+            Yaku::ThreeColourStraight => "three_colour_straight",
+            Yaku::Straight => "straight",
+            Yaku::TwoSetsOfIdenticalSequences => "two_sets_of_identical_sequences",
+            Yaku::AllTripletHand => "all_triplet_hand",
+            Yaku::ThreeClosedTriplets => "three_closed_triplets",
+            Yaku::ThreeColourTriplets => "three_colour_triplets",
+            Yaku::ThreeKans => "three_kans",
+            Yaku::AllSimples => "all_simples",
+            Yaku::HonorTilesPlayersWind => "honor_tiles_players_wind",
+            Yaku::HonorTilesPrevailingWind => "honor_tiles_prevailing_wind",
+            Yaku::HonorTilesDragons => "honor_tiles_dragons",
+            Yaku::TerminalOrHonorInEachSet => "terminal_or_honor_in_each_set",
+            Yaku::TerminalInEachSet => "terminal_in_each_set",
+            Yaku::AllTerminalsAndHonors => "all_terminals_and_honors",
+            Yaku::LittleThreeDragons => "little_three_dragons",
+            Yaku::HalfFlush => "half_flush",
+            Yaku::Flush => "flush",
+            Yaku::ThirteenOrphans => "thirteen_orphans",
+            Yaku::ThirteenWaitThirteenOrphans => "thirteen_wait_thirteen_orphans",
+            Yaku::FourConcealedTriplets => "four_concealed_triplets",
+            Yaku::SingleWaitFourConcealedTriplets => "single_wait_four_concealed_triplets",
+            Yaku::BigThreeDragons => "big_three_dragons",
+            Yaku::LittleFourWinds => "little_four_winds",
+            Yaku::BigFourWinds => "big_four_winds",
+            Yaku::AllHonors => "all_honors",
+            Yaku::AllTerminals => "all_terminals",
+            Yaku::AllGreen => "all_green",
+            Yaku::NineGates => "nine_gates",
+            Yaku::TrueNineGates => "true_nine_gates",
+            Yaku::FourKans => "four_kans",
+            Yaku::HeavenlyHand => "heavenly_hand",
+            Yaku::HandOfEarth => "hand_of_earth",
+            Yaku::BlessingOfMan => "blessing_of_man",
+            Yaku::BigWheels => "big_wheels",
+            Yaku::ThreeConsecutiveTriplets => "three_consecutive_triplets",
+            Yaku::PureTripleChow => "pure_triple_chow",
+            Yaku::OpenRiichi => "open_riichi",
+            Yaku::ThirteenUnrelatedTiles => "thirteen_unrelated_tiles",
+            Yaku::SwallowReturn => "swallow_return",
+        };
+    }
+
+    /// 日本語の名前を返す
+    pub fn name(&self) -> &'static str {
+        return self.localized_name(Locale::Japanese);
+    }
+
+    /// 指定した言語での名前を返す
+    pub fn localized_name(&self, locale: Locale) -> &'static str {
+        return yaku_name(*self, locale);
+    }
+
+    /// 門前で和了したときの翻数を返す
+    ///
+    /// 役満は`Yaku::YAKUMAN_HAN`を、ダブル役満は`Yaku::YAKUMAN_HAN`の2倍を返す。
+    /// ローカル役は既定の翻数を返す（実際の翻数は`Ruleset::local_yaku`で決まる）。
+    pub fn closed_han(&self) -> u32 {
+        return match self {
+            Yaku::ReadyHand
+            | Yaku::SelfPick
+            | Yaku::OneShot
+            | Yaku::LastTileFromTheWall
+            | Yaku::LastDiscard
+            | Yaku::DeadWallDraw
+            | Yaku::RobbingAQuad
+            | Yaku::NoPointsHand
+            | Yaku::OneSetOfIdenticalSequences
+            | Yaku::AllSimples
+            | Yaku::HonorTilesPlayersWind
+            | Yaku::HonorTilesPrevailingWind
+            | Yaku::HonorTilesDragons => 1,
+            Yaku::SevenPairs
+            | Yaku::DoubleReady
+            | Yaku::ThreeColourStraight
+            | Yaku::Straight
+            | Yaku::AllTripletHand
+            | Yaku::ThreeClosedTriplets
+            | Yaku::ThreeColourTriplets
+            | Yaku::ThreeKans
+            | Yaku::TerminalOrHonorInEachSet
+            | Yaku::AllTerminalsAndHonors
+            | Yaku::LittleThreeDragons => 2,
+            Yaku::TwoSetsOfIdenticalSequences | Yaku::TerminalInEachSet | Yaku::HalfFlush => 3,
+            Yaku::SwallowReturn | Yaku::OpenRiichi => 1,
+            Yaku::ThreeConsecutiveTriplets => 2,
+            Yaku::PureTripleChow => 3,
+            Yaku::NagashiMangan => 5,
+            Yaku::Flush => 6,
+            Yaku::ThirteenWaitThirteenOrphans
+            | Yaku::SingleWaitFourConcealedTriplets
+            | Yaku::TrueNineGates
+            | Yaku::BigFourWinds => Yaku::YAKUMAN_HAN * 2,
+            _ => Yaku::YAKUMAN_HAN,
+        };
+    }
+
+    /// 副露して和了したときの翻数を返す
+    ///
+    /// 食い下がり（副露すると1翻下がる）を反映する。門前でなければ成立しない役は`None`を返す。
+    pub fn open_han(&self) -> Option<u32> {
+        return match self {
+            Yaku::ReadyHand
+            | Yaku::SevenPairs
+            | Yaku::SelfPick
+            | Yaku::OneShot
+            | Yaku::DoubleReady
+            | Yaku::NoPointsHand
+            | Yaku::OneSetOfIdenticalSequences
+            | Yaku::TwoSetsOfIdenticalSequences
+            | Yaku::ThirteenOrphans
+            | Yaku::ThirteenWaitThirteenOrphans
+            | Yaku::FourConcealedTriplets
+            | Yaku::SingleWaitFourConcealedTriplets
+            | Yaku::NineGates
+            | Yaku::TrueNineGates
+            | Yaku::HeavenlyHand
+            | Yaku::HandOfEarth
+            | Yaku::BlessingOfMan
+            | Yaku::BigWheels
+            | Yaku::OpenRiichi
+            | Yaku::ThirteenUnrelatedTiles => None,
+            Yaku::ThreeColourStraight
+            | Yaku::Straight
+            | Yaku::TerminalOrHonorInEachSet
+            | Yaku::TerminalInEachSet
+            | Yaku::HalfFlush
+            | Yaku::Flush
+            | Yaku::PureTripleChow => Some(self.closed_han() - 1),
+            _ => Some(self.closed_han()),
+        };
+    }
+
+    /// 門前か否かに応じた翻数を返す
+    ///
+    /// 副露していて成立しない役は`None`を返す。
+    pub fn han(&self, closed: bool) -> Option<u32> {
+        return if closed {
+            Some(self.closed_han())
+        } else {
+            self.open_han()
+        };
+    }
+
+    /// 役満か否かを返す
+    pub fn is_yakuman(&self) -> bool {
+        return self.closed_han() >= Yaku::YAKUMAN_HAN;
+    }
+
+    /// ダブル役満として数えうる役か否かを返す
+    ///
+    /// 既定ではダブル役満として数えるが、`Ruleset::double_yakuman`が`false`なら役満として数える。
+    pub fn is_double_yakuman(&self) -> bool {
+        return matches!(
+            self,
+            Yaku::ThirteenWaitThirteenOrphans
+                | Yaku::SingleWaitFourConcealedTriplets
+                | Yaku::TrueNineGates
+                | Yaku::BigFourWinds
+        );
+    }
+}
+
+/// 成立した役とその翻数
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AchievedYaku {
+    /// 役
+    pub yaku: Yaku,
+    /// 翻数（役満は`Yaku::YAKUMAN_HAN`の倍数）
+    pub han: u32,
+}
+
+impl AchievedYaku {
+    /// 役満なら何倍役満かを返す（役満でなければ`0`）
+    ///
+    /// ローカル役の翻数はルールで変えられるため、翻数が`Yaku::YAKUMAN_HAN`以上のものを役満とする。
+    pub fn yakuman_multiplier(&self) -> u32 {
+        return self.han / Yaku::YAKUMAN_HAN;
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_info::winning_hand::HAND_NAME;
+
+    #[test]
+    /// 全ての役のキーが`HAND_NAME`と一致する
+    fn keys_match_hand_name() {
+        for (yaku, name) in Yaku::ALL.iter().zip(HAND_NAME.iter()) {
+            assert_eq!(yaku.key(), *name);
+        }
+    }
+
+    #[test]
+    /// 食い下がりと門前限定の役
+    fn open_han() {
+        assert_eq!(Yaku::Flush.han(true), Some(6));
+        assert_eq!(Yaku::Flush.han(false), Some(5));
+        assert_eq!(Yaku::AllTripletHand.han(false), Some(2));
+        assert_eq!(Yaku::NoPointsHand.han(false), None);
+        assert_eq!(Yaku::BigThreeDragons.han(false), Some(13));
+    }
+
+    #[test]
+    /// 役満の判定
+    fn yakuman() {
+        assert!(Yaku::ThirteenOrphans.is_yakuman());
+        assert!(!Yaku::Flush.is_yakuman());
+        assert!(Yaku::BigFourWinds.is_double_yakuman());
+        assert!(!Yaku::NineGates.is_double_yakuman());
+        assert_eq!(Yaku::TrueNineGates.closed_han(), 26);
+        let achieved = AchievedYaku {
+            yaku: Yaku::BigFourWinds,
+            han: 26,
+        };
+        assert_eq!(achieved.yakuman_multiplier(), 2);
+    }
+}
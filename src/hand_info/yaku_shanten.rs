@@ -0,0 +1,394 @@
+use std::cmp::*;
+
+use crate::hand::Hand;
+use crate::hand_info::hand_analyzer::HandAnalyzer;
+use crate::hand_info::incremental_analyzer::{
+    calc_normal_shanten_with_fixed, merge, BlockRule, SuitPartial,
+};
+use crate::hand_info::opened::*;
+use crate::tile::*;
+
+/// 向聴数を求める対象の役
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum YakuTarget {
+    /// 断么九
+    AllSimples,
+    /// 混一色
+    HalfFlush,
+    /// 清一色
+    Flush,
+    /// 対々和
+    AllTripletHand,
+    /// 混全帯么九
+    TerminalOrHonorInEachSet,
+    /// 役牌（三元牌・自風牌・場風牌のいずれかの刻子）
+    HonorTiles { seat_wind: Wind, round_wind: Wind },
+    /// 三色同順
+    ThreeColourStraight,
+    /// 一気通貫
+    Straight,
+}
+
+impl YakuTarget {
+    /// 自風と場風から、全ての対象の役を返す
+    pub fn all(seat_wind: Wind, round_wind: Wind) -> [YakuTarget; 8] {
+        return [
+            YakuTarget::AllSimples,
+            YakuTarget::HalfFlush,
+            YakuTarget::Flush,
+            YakuTarget::AllTripletHand,
+            YakuTarget::TerminalOrHonorInEachSet,
+            YakuTarget::HonorTiles {
+                seat_wind,
+                round_wind,
+            },
+            YakuTarget::ThreeColourStraight,
+            YakuTarget::Straight,
+        ];
+    }
+}
+
+/// 役を満たす和了形を目指すときの条件
+struct Condition {
+    /// 使ってよい牌
+    allowed: [bool; Tile::LEN],
+    /// 色ごとに使ってよい面子・搭子の種類
+    rules: [BlockRule; 4],
+    /// 必ず作る面子（副露で既にできているものを除く）
+    forced: Vec<[TileType; 3]>,
+    /// 七対子でもよいか
+    seven_pairs: bool,
+}
+
+impl Condition {
+    /// 全ての牌・面子を使ってよい条件
+    fn new() -> Condition {
+        return Condition {
+            allowed: [true; Tile::LEN],
+            rules: [
+                BlockRule::NUMBER,
+                BlockRule::NUMBER,
+                BlockRule::NUMBER,
+                BlockRule::HONOR,
+            ],
+            forced: Vec::new(),
+            seven_pairs: false,
+        };
+    }
+
+    /// 条件に合う牌だけを使う
+    fn allow_only<F: Fn(TileType) -> bool>(mut self, f: F) -> Condition {
+        for i in Tile::M1..=Tile::Z7 {
+            self.allowed[i as usize] = f(i);
+        }
+        return self;
+    }
+}
+
+/// 指定した役を満たす和了形への向聴数を計算する
+///
+/// 副露が役の条件を満たしていないなど、その役では和了できない場合は`None`を返す。
+/// 役の条件は手牌の形だけで判断し、他家の捨て牌や見えている牌は考えない。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::hand::*;
+/// use mahjong_rs::hand_info::hand_analyzer::*;
+/// use mahjong_rs::hand_info::yaku_shanten::{self, YakuTarget};
+/// use mahjong_rs::tile::*;
+///
+/// // 一向聴だが、断么九を目指すと三向聴
+/// let hand = Hand::from("2399m178p 345s 567p");
+/// assert_eq!(HandAnalyzer::calc(&hand).shanten, 1);
+/// assert_eq!(yaku_shanten::calc(&hand, YakuTarget::AllSimples), Some(3));
+///
+/// // 一索を鳴いているので断么九にはならない
+/// let hand = Hand::from("2399m178p 111s 567p");
+/// assert_eq!(yaku_shanten::calc(&hand, YakuTarget::AllSimples), None);
+/// ```
+pub fn calc(hand: &Hand, target: YakuTarget) -> Option<i32> {
+    let mut concealed = hand.summarize_tiles();
+    for open in hand.opened() {
+        for tile in open.tiles.iter() {
+            concealed[tile.get() as usize] -= 1;
+        }
+    }
+    return conditions(hand.opened(), target)
+        .iter()
+        .filter_map(|condition| calc_with(&concealed, hand.opened().len() as u32, condition))
+        .min();
+}
+
+/// 対象の役のうち、最も向聴数が小さいものを返す
+///
+/// 同じ向聴数の役が複数あれば`YakuTarget::all`の順で先のものを返す。
+/// どの役でも和了できない場合は`None`を返す。
+pub fn calc_min(hand: &Hand, seat_wind: Wind, round_wind: Wind) -> Option<(YakuTarget, i32)> {
+    let mut result: Option<(YakuTarget, i32)> = None;
+    for &target in YakuTarget::all(seat_wind, round_wind).iter() {
+        if let Some(shanten) = calc(hand, target) {
+            if result.is_none_or(|(_, best)| shanten < best) {
+                result = Some((target, shanten));
+            }
+        }
+    }
+    return result;
+}
+
+/// 役ごとに、目指す和了形の条件を列挙する
+///
+/// 副露が条件を満たさないものは含めない。
+fn conditions(opened: &[OpenTiles], target: YakuTarget) -> Vec<Condition> {
+    let mut result: Vec<Condition> = Vec::new();
+    match target {
+        YakuTarget::AllSimples => {
+            let condition = Condition::new().allow_only(|i| !Tile::new(i).is_1_9_honor());
+            result.push(Condition {
+                seven_pairs: true,
+                ..condition
+            });
+        }
+        YakuTarget::HalfFlush | YakuTarget::Flush => {
+            for suit in 0..3 {
+                let honor = target == YakuTarget::HalfFlush;
+                let condition = Condition::new()
+                    .allow_only(|i| i / 9 == suit || (honor && Tile::new(i).is_honor()));
+                result.push(Condition {
+                    seven_pairs: true,
+                    ..condition
+                });
+            }
+        }
+        YakuTarget::AllTripletHand => {
+            if opened.iter().all(|open| open.category != OpenType::Chi) {
+                result.push(Condition {
+                    rules: [BlockRule::HONOR; 4],
+                    ..Condition::new()
+                });
+            }
+        }
+        YakuTarget::TerminalOrHonorInEachSet => {
+            let terminal = BlockRule {
+                sequential: true,
+                terminal: true,
+            };
+            if opened
+                .iter()
+                .all(|open| open.tiles.iter().any(|tile| tile.is_1_9_honor()))
+            {
+                result.push(Condition {
+                    rules: [terminal, terminal, terminal, BlockRule::HONOR],
+                    ..Condition::new()
+                });
+            }
+        }
+        YakuTarget::HonorTiles {
+            seat_wind,
+            round_wind,
+        } => {
+            let mut honors = vec![Tile::Z5, Tile::Z6, Tile::Z7, seat_wind as TileType];
+            if !honors.contains(&(round_wind as TileType)) {
+                honors.push(round_wind as TileType);
+            }
+            if opened
+                .iter()
+                .any(|open| open.category != OpenType::Chi && honors.contains(&open.tiles[0].get()))
+            {
+                result.push(Condition::new());
+            } else {
+                for &honor in honors.iter() {
+                    result.push(forced_condition(opened, &[[honor; 3]]));
+                }
+            }
+        }
+        YakuTarget::ThreeColourStraight => {
+            for n in 0..7 {
+                let sets: Vec<[TileType; 3]> = (0..3)
+                    .map(|suit| [suit * 9 + n, suit * 9 + n + 1, suit * 9 + n + 2])
+                    .collect();
+                result.push(forced_condition(opened, &sets));
+            }
+        }
+        YakuTarget::Straight => {
+            for suit in 0..3 {
+                let sets: Vec<[TileType; 3]> = (0..3)
+                    .map(|n| [suit * 9 + n * 3, suit * 9 + n * 3 + 1, suit * 9 + n * 3 + 2])
+                    .collect();
+                result.push(forced_condition(opened, &sets));
+            }
+        }
+    }
+    result.retain(|condition| {
+        opened.iter().all(|open| {
+            open.tiles
+                .iter()
+                .all(|tile| condition.allowed[tile.get() as usize])
+        }) && opened.len() + condition.forced.len() <= 4
+    });
+    return result;
+}
+
+/// 指定した面子を必ず作る条件を返す
+///
+/// 副露で既にできている面子は、作る必要のある面子から除く。
+fn forced_condition(opened: &[OpenTiles], sets: &[[TileType; 3]]) -> Condition {
+    let mut forced = sets.to_vec();
+    for open in opened {
+        let mut tiles: Vec<TileType> = open.tiles.iter().map(|tile| tile.get()).collect();
+        tiles.sort_unstable();
+        if let Some(pos) = forced.iter().position(|set| set[..] == tiles[..]) {
+            forced.remove(pos);
+        }
+    }
+    return Condition {
+        forced,
+        ..Condition::new()
+    };
+}
+
+/// 副露していない牌と副露の数から、条件を満たす和了形への向聴数を計算する
+fn calc_with(concealed: &TileSummarize, fixed: u32, condition: &Condition) -> Option<i32> {
+    let mut t: TileSummarize = [0; Tile::LEN];
+    for i in 0..Tile::LEN {
+        if condition.allowed[i] {
+            t[i] = concealed[i];
+        }
+    }
+
+    // 必ず作る面子に使う牌を抜き出し、足りない牌を数える
+    let mut missing: i32 = 0;
+    for set in condition.forced.iter() {
+        for &tile in set.iter() {
+            if t[tile as usize] > 0 {
+                t[tile as usize] -= 1;
+            } else {
+                missing += 1;
+            }
+        }
+    }
+
+    let mut all: Option<SuitPartial> = None;
+    for (suit, rule) in condition.rules.iter().enumerate() {
+        let begin = suit * 9;
+        let end = min(begin + 9, Tile::LEN);
+        let partial = SuitPartial::calc_cached(&t[begin..end], *rule);
+        all = Some(match all {
+            Some(a) => merge(&a, &partial),
+            None => partial,
+        });
+    }
+    let fixed = fixed + condition.forced.len() as u32;
+    let sets = 4 - min(fixed, 4) as i32;
+    let tiles: i32 = t.iter().sum::<u32>() as i32;
+    // 使える牌が足りなければ、足りない分だけ牌を引く必要がある
+    let mut shanten = max(
+        calc_normal_shanten_with_fixed(&all?, fixed),
+        sets * 3 + 1 - tiles,
+    ) + missing;
+    if condition.seven_pairs && fixed == 0 {
+        shanten = min(shanten, HandAnalyzer::calc_seven_pairs(&t));
+    }
+    return Some(shanten);
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 断么九への向聴数
+    fn all_simples() {
+        let hand = Hand::from("123m567p34578s55z");
+        assert_eq!(HandAnalyzer::calc(&hand).shanten, 0);
+        assert_eq!(calc(&hand, YakuTarget::AllSimples), Some(3));
+        let hand = Hand::from("234m567p34578s22p");
+        assert_eq!(calc(&hand, YakuTarget::AllSimples), Some(0));
+        // 七対子でもよい
+        let hand = Hand::from("2233m4466p7788s1z");
+        assert_eq!(calc(&hand, YakuTarget::AllSimples), Some(1));
+    }
+
+    #[test]
+    /// 混一色・清一色への向聴数
+    fn flush() {
+        let hand = Hand::from("1234567899m1p11z");
+        assert_eq!(calc(&hand, YakuTarget::HalfFlush), Some(1));
+        assert_eq!(calc(&hand, YakuTarget::Flush), Some(3));
+        // 2色を鳴いていると和了できない
+        let hand = Hand::from("12345m11z 789p 123s");
+        assert_eq!(calc(&hand, YakuTarget::HalfFlush), None);
+        let hand = Hand::from("123456789m1p 111z");
+        assert_eq!(calc(&hand, YakuTarget::HalfFlush), Some(1));
+        assert_eq!(calc(&hand, YakuTarget::Flush), None);
+    }
+
+    #[test]
+    /// 対々和への向聴数
+    fn all_triplet_hand() {
+        let hand = Hand::from("111m22p33s445z 777z");
+        assert_eq!(calc(&hand, YakuTarget::AllTripletHand), Some(1));
+        let hand = Hand::from("123m222p33s44567z");
+        assert_eq!(calc(&hand, YakuTarget::AllTripletHand), Some(4));
+        // チーしていると和了できない
+        let hand = Hand::from("111m222p33s45z 789s");
+        assert_eq!(calc(&hand, YakuTarget::AllTripletHand), None);
+    }
+
+    #[test]
+    /// 混全帯么九への向聴数
+    fn terminal_or_honor_in_each_set() {
+        let hand = Hand::from("123m789p12399s11z");
+        assert_eq!(calc(&hand, YakuTarget::TerminalOrHonorInEachSet), Some(0));
+        // 4萬5萬は一九牌を含む順子にならない
+        let hand = Hand::from("45m789p123s11z 123p");
+        assert_eq!(HandAnalyzer::calc(&hand).shanten, 0);
+        assert_eq!(calc(&hand, YakuTarget::TerminalOrHonorInEachSet), Some(1));
+        let hand = Hand::from("45m789p123s11z 456p");
+        assert_eq!(calc(&hand, YakuTarget::TerminalOrHonorInEachSet), None);
+    }
+
+    #[test]
+    /// 役牌への向聴数
+    fn honor_tiles() {
+        let target = YakuTarget::HonorTiles {
+            seat_wind: Wind::South,
+            round_wind: Wind::East,
+        };
+        let hand = Hand::from("123m456p789s1155z");
+        assert_eq!(HandAnalyzer::calc(&hand).shanten, 0);
+        // 東の刻子を作る
+        assert_eq!(calc(&hand, target), Some(0));
+        let hand = Hand::from("123m456p789s3344z");
+        assert_eq!(calc(&hand, target), Some(2));
+        // 役牌を鳴いていれば役の条件は満たしている
+        let hand = Hand::from("123m456p78s33z 777z");
+        assert_eq!(calc(&hand, target), Some(0));
+        let hand = Hand::from("123m456p78s33z 444z");
+        assert_eq!(calc(&hand, target), Some(2));
+    }
+
+    #[test]
+    /// 三色同順・一気通貫への向聴数
+    fn straights() {
+        let hand = Hand::from("123m123p12s45688s");
+        assert_eq!(calc(&hand, YakuTarget::ThreeColourStraight), Some(0));
+        assert_eq!(calc(&hand, YakuTarget::Straight), Some(3));
+        // チーした順子も使える
+        let hand = Hand::from("123m12s45688s 123p");
+        assert_eq!(calc(&hand, YakuTarget::ThreeColourStraight), Some(0));
+        // 他の順子を3つ鳴いていると作れない
+        let hand = Hand::from("123m1s 456p 789p 567s");
+        assert_eq!(calc(&hand, YakuTarget::ThreeColourStraight), None);
+    }
+
+    #[test]
+    /// 最も近い役を選ぶ
+    fn min_target() {
+        let hand = Hand::from("2399m178p 345s 567p");
+        assert_eq!(
+            calc_min(&hand, Wind::East, Wind::East),
+            Some((YakuTarget::AllSimples, 3))
+        );
+    }
+}
@@ -1,6 +1,13 @@
+#![allow(clippy::needless_return, clippy::inherent_to_string)]
 /// 牌
 pub mod tile;
 /// 手牌
 pub mod hand;
 /// 手牌の情報（副露しているか、面子があるかなど）
 pub mod hand_info;
+/// ルール
+pub mod rule;
+/// 表示言語（日本語・英語・ローマ字）
+pub mod locale;
+/// 卓（牌山・局の進行）
+pub mod board;
@@ -0,0 +1,239 @@
+use crate::hand_info::decomposition::Wait;
+use crate::hand_info::fu::FuItem;
+use crate::hand_info::score::Limit;
+use crate::hand_info::yaku::Yaku;
+use crate::tile::*;
+
+/// 表示に使う言語
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum Locale {
+    /// 日本語
+    #[default]
+    Japanese,
+    /// 英語
+    English,
+    /// ローマ字
+    Romaji,
+}
+
+/// 1つの用語の各言語での表記
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Term {
+    /// 日本語
+    pub japanese: &'static str,
+    /// 英語
+    pub english: &'static str,
+    /// ローマ字
+    pub romaji: &'static str,
+}
+
+impl Term {
+    /// 各言語での表記から用語を作る
+    pub const fn new(japanese: &'static str, english: &'static str, romaji: &'static str) -> Term {
+        return Term {
+            japanese,
+            english,
+            romaji,
+        };
+    }
+
+    /// 指定した言語での表記を返す
+    pub fn get(&self, locale: Locale) -> &'static str {
+        return match locale {
+            Locale::Japanese => self.japanese,
+            Locale::English => self.english,
+            Locale::Romaji => self.romaji,
+        };
+    }
+}
+
+/// 役の名前
+///
+/// 並び順は`Yaku`と同じ。
+pub const YAKU_NAMES: [Term; 51] = [
+    Term::new("立直", "Ready hand", "Riichi"),
+    Term::new("七対子", "Seven pairs", "Chiitoitsu"),
+    Term::new("流し満貫", "Nagashi mangan", "Nagashi mangan"),
+    Term::new("門前清自摸和", "Self pick", "Menzen tsumo"),
+    Term::new("一発", "One shot", "Ippatsu"),
+    Term::new("海底撈月", "Last tile from the wall", "Haitei raoyue"),
+    Term::new("河底撈魚", "Last discard", "Houtei raoyui"),
+    Term::new("嶺上開花", "Dead wall draw", "Rinshan kaihou"),
+    Term::new("搶槓", "Robbing a quad", "Chankan"),
+    Term::new("ダブル立直", "Double ready", "Daburu riichi"),
+    Term::new("平和", "No points hand", "Pinfu"),
+    Term::new("一盃口", "One set of identical sequences", "Iipeikou"),
+    Term::new("三色同順", "Three colour straight", "Sanshoku doujun"),
+    Term::new("一気通貫", "Straight", "Ikkitsuukan"),
+    Term::new("二盃口", "Two sets of identical sequences", "Ryanpeikou"),
+    Term::new("対々和", "All triplet hand", "Toitoihou"),
+    Term::new("三暗刻", "Three closed triplets", "San ankou"),
+    Term::new("三色同刻", "Three colour triplets", "Sanshoku doukou"),
+    Term::new("三槓子", "Three kans", "San kantsu"),
+    Term::new("断么九", "All simples", "Tanyao"),
+    Term::new(
+        "役牌（自風牌）",
+        "Honor tiles (player's wind)",
+        "Yakuhai (jikaze)",
+    ),
+    Term::new(
+        "役牌（場風牌）",
+        "Honor tiles (prevailing wind)",
+        "Yakuhai (bakaze)",
+    ),
+    Term::new(
+        "役牌（三元牌）",
+        "Honor tiles (dragons)",
+        "Yakuhai (sangenpai)",
+    ),
+    Term::new("混全帯么九", "Terminal or honor in each set", "Chanta"),
+    Term::new("純全帯么九", "Terminal in each set", "Junchan"),
+    Term::new("混老頭", "All terminals and honors", "Honroutou"),
+    Term::new("小三元", "Little three dragons", "Shousangen"),
+    Term::new("混一色", "Half flush", "Honitsu"),
+    Term::new("清一色", "Flush", "Chinitsu"),
+    Term::new("国士無双", "Thirteen orphans", "Kokushi musou"),
+    Term::new(
+        "国士無双十三面待ち",
+        "Thirteen-wait thirteen orphans",
+        "Kokushi musou juusanmen machi",
+    ),
+    Term::new("四暗刻", "Four concealed triplets", "Suuankou"),
+    Term::new(
+        "四暗刻単騎",
+        "Single-wait four concealed triplets",
+        "Suuankou tanki",
+    ),
+    Term::new("大三元", "Big three dragons", "Daisangen"),
+    Term::new("小四喜", "Little four winds", "Shousuushii"),
+    Term::new("大四喜", "Big four winds", "Daisuushii"),
+    Term::new("字一色", "All honors", "Tsuuiisou"),
+    Term::new("清老頭", "All terminals", "Chinroutou"),
+    Term::new("緑一色", "All green", "Ryuuiisou"),
+    Term::new("九蓮宝燈", "Nine gates", "Chuuren poutou"),
+    Term::new("純正九蓮宝燈", "True nine gates", "Junsei chuuren poutou"),
+    Term::new("四槓子", "Four kans", "Suukantsu"),
+    Term::new("天和", "Heavenly hand", "Tenhou"),
+    Term::new("地和", "Hand of earth", "Chiihou"),
+    Term::new("人和", "Blessing of man", "Renhou"),
+    Term::new("大車輪", "Big wheels", "Daisharin"),
+    Term::new("三連刻", "Three consecutive triplets", "Sanrenkou"),
+    Term::new("一色三順", "Pure triple chow", "Iishoku sanjun"),
+    Term::new("オープン立直", "Open riichi", "Oopun riichi"),
+    Term::new("十三不塔", "Thirteen unrelated tiles", "Shiisan puutaa"),
+    Term::new("燕返し", "Swallow return", "Tsubame gaeshi"),
+];
+
+/// 役の名前を返す
+pub fn yaku_name(yaku: Yaku, locale: Locale) -> &'static str {
+    return YAKU_NAMES[yaku as usize].get(locale);
+}
+
+/// 符の内訳の項目の名前を返す
+///
+/// 役牌の雀頭は三元牌か風牌かで名前を分け、刻子は明刻・暗刻・明槓・暗槓で分ける。
+pub fn fu_item_name(item: FuItem, locale: Locale) -> &'static str {
+    let term = match item {
+        FuItem::Base => Term::new("副底", "Base", "Fuutei"),
+        FuItem::ClosedRon => Term::new("門前加符", "Closed ron", "Menzen kafu"),
+        FuItem::Tsumo => Term::new("ツモ符", "Self-draw", "Tsumo fu"),
+        FuItem::Triplet {
+            concealed: false,
+            kan: false,
+            ..
+        } => Term::new("明刻", "Open triplet", "Minkou"),
+        FuItem::Triplet {
+            concealed: true,
+            kan: false,
+            ..
+        } => Term::new("暗刻", "Concealed triplet", "Ankou"),
+        FuItem::Triplet {
+            concealed: false,
+            kan: true,
+            ..
+        } => Term::new("明槓", "Open kan", "Minkan"),
+        FuItem::Triplet {
+            concealed: true,
+            kan: true,
+            ..
+        } => Term::new("暗槓", "Concealed kan", "Ankan"),
+        FuItem::HonorPair(tile) if tile >= Tile::Z5 => {
+            Term::new("三元牌の雀頭", "Dragon pair", "Sangenpai jantou")
+        }
+        FuItem::HonorPair(_) => Term::new("風牌の雀頭", "Wind pair", "Kazehai jantou"),
+        FuItem::Wait(Wait::Ryanmen) => Term::new("両面待ち", "Two-sided wait", "Ryanmen"),
+        FuItem::Wait(Wait::Kanchan) => Term::new("嵌張待ち", "Closed wait", "Kanchan"),
+        FuItem::Wait(Wait::Penchan) => Term::new("辺張待ち", "Edge wait", "Penchan"),
+        FuItem::Wait(Wait::Shanpon) => Term::new("双碰待ち", "Dual pon wait", "Shanpon"),
+        FuItem::Wait(Wait::Tanki) => Term::new("単騎待ち", "Pair wait", "Tanki"),
+        FuItem::OpenNoPoints => Term::new("副露平和形", "Open pinfu", "Kui pinfu"),
+        FuItem::SevenPairs => Term::new("七対子", "Seven pairs", "Chiitoitsu"),
+    };
+    return term.get(locale);
+}
+
+/// 満貫以上の点数の区分の名前を返す
+///
+/// 満貫未満なら`None`を返す。役満は倍数に応じてダブル役満・トリプル役満などとする。
+pub fn limit_name(limit: Limit, locale: Locale) -> Option<String> {
+    let term = match limit {
+        Limit::None => return None,
+        Limit::Mangan => Term::new("満貫", "Mangan", "Mangan"),
+        Limit::Haneman => Term::new("跳満", "Haneman", "Haneman"),
+        Limit::Baiman => Term::new("倍満", "Baiman", "Baiman"),
+        Limit::Sanbaiman => Term::new("三倍満", "Sanbaiman", "Sanbaiman"),
+        Limit::Yakuman(1) => Term::new("役満", "Yakuman", "Yakuman"),
+        Limit::Yakuman(2) => Term::new("ダブル役満", "Double yakuman", "Daburu yakuman"),
+        Limit::Yakuman(3) => Term::new("トリプル役満", "Triple yakuman", "Toripuru yakuman"),
+        Limit::Yakuman(n) => {
+            return Some(match locale {
+                Locale::Japanese => format!("{}倍役満", n),
+                Locale::English => format!("{}x yakuman", n),
+                Locale::Romaji => format!("{}bai yakuman", n),
+            });
+        }
+    };
+    return Some(term.get(locale).to_string());
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 役の名前の表が`Yaku`の並び順と一致する
+    fn yaku_names() {
+        assert_eq!(yaku_name(Yaku::ReadyHand, Locale::Romaji), "Riichi");
+        assert_eq!(yaku_name(Yaku::SwallowReturn, Locale::Japanese), "燕返し");
+        assert_eq!(yaku_name(Yaku::AllSimples, Locale::English), "All simples");
+        // 英語名は`Yaku::key`と同じ語順
+        for yaku in Yaku::ALL.iter() {
+            assert_eq!(
+                yaku_name(*yaku, Locale::English)
+                    .to_lowercase()
+                    .replace([' ', '-'], "_")
+                    .replace(['(', ')', '\''], ""),
+                yaku.key()
+            );
+        }
+    }
+
+    #[test]
+    /// 点数の区分の名前
+    fn limit_names() {
+        assert_eq!(limit_name(Limit::None, Locale::Japanese), None);
+        assert_eq!(
+            limit_name(Limit::Haneman, Locale::Japanese),
+            Some("跳満".to_string())
+        );
+        assert_eq!(
+            limit_name(Limit::Yakuman(2), Locale::English),
+            Some("Double yakuman".to_string())
+        );
+        assert_eq!(
+            limit_name(Limit::Yakuman(4), Locale::Romaji),
+            Some("4bai yakuman".to_string())
+        );
+    }
+}
@@ -1,29 +1,16 @@
-//use rand::thread_rng;
-use rand::seq::SliceRandom;
-
-mod tile;
-use tile::*;
-
-mod hand;
-use hand::*;
-
-mod hand_info;
-use hand_info::*;
+use mahjong_rs::board::wall::*;
+use mahjong_rs::hand::*;
+use mahjong_rs::rule::Ruleset;
+use mahjong_rs::tile::*;
 
 fn main() {
-    let mut rng = rand::thread_rng();
-    let mut tiles: Vec<Tile> = Vec::new();
-    for _ in 0..4 {
-        for i in 0..Tile::LEN {
-            tiles.push(Tile::new(i as TileType));
-        }
-    }
-    tiles.shuffle(&mut rng);
+    let mut shuffler = RandomShuffler::new(rand::thread_rng());
+    let mut wall = Wall::new(&mut shuffler, Ruleset::default().red_fives);
     let mut hand_vec: Vec<Tile> = Vec::new();
     for _ in 0..13 {
-        hand_vec.push(tiles.pop().unwrap());
+        hand_vec.push(wall.draw().unwrap());
     }
     hand_vec.sort();
-    let hand = Hand::new(hand_vec, tiles.pop());
+    let hand = Hand::new(hand_vec, wall.draw().ok());
     println!("{}", hand.to_short_string());
 }
@@ -0,0 +1,589 @@
+use crate::hand_info::score::LimitRule;
+use crate::hand_info::yaku::Yaku;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// 対局のルール
+///
+/// 役の判定・点数計算・対局の進行で参照する。設定ファイル（TOML）から読み込むこともできる。
+/// 既定値は天鳳のルール（`Ruleset::tenhou()`）。
+/// # Examples
+///
+/// ```
+/// use mahjong_rs::rule::*;
+///
+/// let ruleset = Ruleset::from_toml(
+///     r#"
+///     preset = "wrc"
+///     open_tanyao = false
+///     "#,
+/// )
+/// .unwrap();
+/// assert!(!ruleset.open_tanyao);
+/// assert_eq!(ruleset.starting_points, Ruleset::wrc().starting_points);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Ruleset {
+    /// 喰い断（副露した断么九を認める）
+    pub open_tanyao: bool,
+    /// 後付け（聴牌した後に役が確定する和了を認める）。認めなければ全ての待ちで役が必要
+    pub atozuke: bool,
+    /// 萬子・筒子・索子それぞれの赤五の枚数
+    pub red_fives: [u32; 3],
+    /// ダブル役満（国士無双十三面待ち・四暗刻単騎・純正九蓮宝燈・大四喜）を認める
+    pub double_yakuman: bool,
+    /// 数え役満（役満以外で13翻以上を役満とする。認めなければ三倍満）
+    pub kazoe_yakuman: bool,
+    /// 切り上げ満貫（4翻30符・3翻60符を満貫とする）
+    pub kiriage_mangan: bool,
+    /// 連風牌の雀頭の符
+    pub double_wind_pair_fu: u32,
+    /// 七対子で同じ牌4枚を2つの対子として扱う
+    pub seven_pairs_four_identical: bool,
+    /// 5本場以上では2翻縛り（ドラを除いて2翻以上の役が必要）
+    pub two_han_minimum_after_five_honba: bool,
+    /// 飛び（持ち点が0点未満になった時点で対局を終了する）
+    pub tobi: bool,
+    /// 配給原点（開始時の持ち点）
+    pub starting_points: i32,
+    /// 返し点（順位点の計算で差し引く持ち点）
+    pub return_points: i32,
+    /// 1位から4位までの順位ウマ（点数）
+    pub uma: [i32; 4],
+    /// ローカル役
+    pub local_yaku: LocalYakuRules,
+    /// 流し満貫を認める
+    pub nagashi_mangan: bool,
+    /// 流し満貫が複数人で成立したときの扱い
+    pub multiple_nagashi: MultipleNagashi,
+    /// 同じ牌で複数人がロンしたときの扱い
+    pub multiple_ron: MultipleRon,
+    /// 対局の長さ（東風戦・半荘戦）
+    pub game_length: GameLength,
+    /// 和了止め・聴牌止め（オーラスで親がトップのとき、連荘せずに終了できる）
+    pub agari_yame: bool,
+    /// 聴牌連荘（荒牌平局で親が聴牌していれば連荘する。しなければ親が流れる）
+    pub tenpai_renchan: bool,
+    /// 延長戦（オーラスを終えて誰も`target_points`に達していなければ、次の場まで延長する）
+    ///
+    /// 半荘戦なら西場（西入）、東風戦なら南場（南入）まで延長する。
+    pub extension_round: bool,
+    /// 延長戦で対局を終えるのに必要な持ち点
+    pub target_points: i32,
+    /// 空聴（自分の手牌で4枚とも使っている牌だけを待つ聴牌）の扱い
+    pub karaten: Karaten,
+    /// 聴牌宣言（荒牌平局で聴牌者が手牌を公開するか選べる。選べなければ聴牌者は必ず公開する）
+    pub tenpai_declaration: bool,
+    /// 途中流局
+    pub abortive_draws: AbortiveDrawRules,
+}
+
+/// 空聴の扱い
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Karaten {
+    /// 聴牌と認める
+    Tenpai,
+    /// ノーテンとする
+    Noten,
+}
+
+/// 対局の長さ
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameLength {
+    /// 東風戦（東場のみ）
+    East,
+    /// 半荘戦（東場と南場）
+    EastSouth,
+}
+
+impl GameLength {
+    /// 延長しない場合の局数を返す
+    pub fn rounds(&self) -> usize {
+        return match self {
+            GameLength::East => 4,
+            GameLength::EastSouth => 8,
+        };
+    }
+}
+
+/// 流し満貫が複数人で成立したときの扱い
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MultipleNagashi {
+    /// 成立した全員が満貫の支払いを受ける
+    All,
+    /// 親から順に数えて最初の1人だけが支払いを受ける（頭跳ね）
+    FirstInTurnOrder,
+}
+
+/// 同じ牌で複数人がロンしたときの扱い
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MultipleRon {
+    /// ロンした全員が和了する（ダブロン・トリロン）
+    All,
+    /// 放銃者から順に数えて最初の1人だけが和了する（頭跳ね）
+    FirstInTurnOrder,
+}
+
+/// ローカル役のルール
+///
+/// それぞれの役を門前で和了したときの翻数。`None`なら採用しない。
+/// 副露して成立する役は、既定の翻数と同じだけ食い下がる。
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LocalYakuRules {
+    /// 人和
+    pub renhou: Option<u32>,
+    /// 大車輪
+    pub daisharin: Option<u32>,
+    /// 三連刻
+    pub sanrenkou: Option<u32>,
+    /// 一色三順
+    pub iishoku_sanjun: Option<u32>,
+    /// オープン立直
+    pub open_riichi: Option<u32>,
+    /// 十三不塔
+    pub shiisanbuta: Option<u32>,
+    /// 燕返し
+    pub tsubamegaeshi: Option<u32>,
+}
+
+/// 途中流局のルール
+///
+/// それぞれの途中流局を採用するか否か。採用しなければ流局せずに続ける（三家和なら3人とも和了する）。
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AbortiveDrawRules {
+    /// 九種九牌（第一ツモの時点で么九牌が9種類以上あれば流局を選べる）
+    pub kyuushu_kyuuhai: bool,
+    /// 四風連打（鳴きのない第一打で4人が同じ風牌を捨てる）
+    pub suufon_renda: bool,
+    /// 四家立直（4人目の立直宣言牌が通る）
+    pub suucha_riichi: bool,
+    /// 四開槓（2人以上で合わせて4回槓した後の打牌が通る）
+    pub suukaikan: bool,
+    /// 三家和（3人が同じ牌でロンする）
+    pub sanchahou: bool,
+    /// 途中流局で親が連荘する（しなければ親が流れる。どちらでも本場は増える）
+    pub dealer_keeps_seat: bool,
+}
+
+impl Default for AbortiveDrawRules {
+    fn default() -> AbortiveDrawRules {
+        return AbortiveDrawRules {
+            kyuushu_kyuuhai: true,
+            suufon_renda: true,
+            suucha_riichi: true,
+            suukaikan: true,
+            sanchahou: true,
+            dealer_keeps_seat: true,
+        };
+    }
+}
+
+impl AbortiveDrawRules {
+    /// 途中流局を1つも採用しない
+    pub fn none() -> AbortiveDrawRules {
+        return AbortiveDrawRules {
+            kyuushu_kyuuhai: false,
+            suufon_renda: false,
+            suucha_riichi: false,
+            suukaikan: false,
+            sanchahou: false,
+            dealer_keeps_seat: true,
+        };
+    }
+}
+
+impl LocalYakuRules {
+    /// 全てのローカル役を既定の翻数で採用する
+    pub fn all() -> LocalYakuRules {
+        let han = |yaku: Yaku| Some(yaku.closed_han());
+        return LocalYakuRules {
+            renhou: han(Yaku::BlessingOfMan),
+            daisharin: han(Yaku::BigWheels),
+            sanrenkou: han(Yaku::ThreeConsecutiveTriplets),
+            iishoku_sanjun: han(Yaku::PureTripleChow),
+            open_riichi: han(Yaku::OpenRiichi),
+            shiisanbuta: han(Yaku::ThirteenUnrelatedTiles),
+            tsubamegaeshi: han(Yaku::SwallowReturn),
+        };
+    }
+
+    /// ローカル役の翻数を返す
+    ///
+    /// 採用していない役、ローカル役でない役、副露していて成立しない役は`None`を返す。
+    pub fn han(&self, yaku: Yaku, closed: bool) -> Option<u32> {
+        let han = match yaku {
+            Yaku::BlessingOfMan => self.renhou,
+            Yaku::BigWheels => self.daisharin,
+            Yaku::ThreeConsecutiveTriplets => self.sanrenkou,
+            Yaku::PureTripleChow => self.iishoku_sanjun,
+            Yaku::OpenRiichi => self.open_riichi,
+            Yaku::ThirteenUnrelatedTiles => self.shiisanbuta,
+            Yaku::SwallowReturn => self.tsubamegaeshi,
+            _ => None,
+        }?;
+        if closed {
+            return Some(han);
+        }
+        let open = yaku.open_han()?;
+        return Some(han.saturating_sub(yaku.closed_han() - open));
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Ruleset {
+        return Ruleset::tenhou();
+    }
+}
+
+impl Ruleset {
+    /// プリセットの名前
+    pub const PRESETS: [&'static str; 4] = ["tenhou", "m-league", "wrc", "ema"];
+
+    /// 天鳳のルール
+    pub fn tenhou() -> Ruleset {
+        return Ruleset {
+            open_tanyao: true,
+            atozuke: true,
+            red_fives: [1, 1, 1],
+            double_yakuman: false,
+            kazoe_yakuman: true,
+            kiriage_mangan: false,
+            double_wind_pair_fu: 4,
+            seven_pairs_four_identical: false,
+            two_han_minimum_after_five_honba: false,
+            tobi: true,
+            starting_points: 25000,
+            return_points: 30000,
+            uma: [20000, 10000, -10000, -20000],
+            local_yaku: LocalYakuRules::default(),
+            nagashi_mangan: true,
+            multiple_nagashi: MultipleNagashi::All,
+            multiple_ron: MultipleRon::All,
+            game_length: GameLength::EastSouth,
+            agari_yame: true,
+            tenpai_renchan: true,
+            extension_round: true,
+            target_points: 30000,
+            karaten: Karaten::Noten,
+            tenpai_declaration: false,
+            abortive_draws: AbortiveDrawRules::default(),
+        };
+    }
+
+    /// Mリーグのルール
+    pub fn m_league() -> Ruleset {
+        return Ruleset {
+            kiriage_mangan: true,
+            double_wind_pair_fu: 2,
+            tobi: false,
+            uma: [30000, 10000, -10000, -30000],
+            multiple_ron: MultipleRon::FirstInTurnOrder,
+            agari_yame: false,
+            extension_round: false,
+            abortive_draws: AbortiveDrawRules {
+                sanchahou: false,
+                ..AbortiveDrawRules::default()
+            },
+            ..Ruleset::tenhou()
+        };
+    }
+
+    /// World Riichi Championshipのルール
+    pub fn wrc() -> Ruleset {
+        return Ruleset {
+            red_fives: [0, 0, 0],
+            kazoe_yakuman: false,
+            kiriage_mangan: true,
+            double_wind_pair_fu: 2,
+            tobi: false,
+            starting_points: 30000,
+            return_points: 30000,
+            uma: [15000, 5000, -5000, -15000],
+            multiple_ron: MultipleRon::FirstInTurnOrder,
+            agari_yame: false,
+            extension_round: false,
+            tenpai_declaration: true,
+            abortive_draws: AbortiveDrawRules::none(),
+            ..Ruleset::tenhou()
+        };
+    }
+
+    /// European Mahjong Associationのルール
+    pub fn ema() -> Ruleset {
+        return Ruleset {
+            nagashi_mangan: false,
+            ..Ruleset::wrc()
+        };
+    }
+
+    /// 名前からプリセットを返す
+    ///
+    /// 名前は`Ruleset::PRESETS`のいずれか。見つからなければ`None`を返す。
+    pub fn preset(name: &str) -> Option<Ruleset> {
+        return match name {
+            "tenhou" => Some(Ruleset::tenhou()),
+            "m-league" => Some(Ruleset::m_league()),
+            "wrc" => Some(Ruleset::wrc()),
+            "ema" => Some(Ruleset::ema()),
+            _ => None,
+        };
+    }
+
+    /// TOML形式の文字列から読み込む
+    ///
+    /// `preset`キーがあればそのプリセットを、なければ既定値を元にして、書かれた項目だけを上書きする。
+    /// 表の中も、書かれた項目だけを上書きする。知らない項目があれば`RulesetError::Parse`を返す。
+    pub fn from_toml(s: &str) -> Result<Ruleset, RulesetError> {
+        let mut table: toml::Table = toml::from_str(s).map_err(RulesetError::Parse)?;
+        let base = match table.remove("preset") {
+            Some(toml::Value::String(name)) => match Ruleset::preset(&name) {
+                Some(ruleset) => ruleset,
+                None => return Err(RulesetError::UnknownPreset(name)),
+            },
+            Some(value) => return Err(RulesetError::UnknownPreset(value.to_string())),
+            None => Ruleset::default(),
+        };
+        let mut merged = toml::Table::try_from(base).map_err(RulesetError::Serialize)?;
+        merge_table(&mut merged, table);
+        return merged.try_into().map_err(RulesetError::Parse);
+    }
+
+    /// TOML形式の設定ファイルから読み込む
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Ruleset, RulesetError> {
+        let s = std::fs::read_to_string(path).map_err(RulesetError::Io)?;
+        return Ruleset::from_toml(&s);
+    }
+
+    /// 点数の上限に関するルールを返す
+    pub fn limit_rule(&self) -> LimitRule {
+        return LimitRule {
+            kiriage_mangan: self.kiriage_mangan,
+            kazoe_yakuman: self.kazoe_yakuman,
+        };
+    }
+
+    /// オカ（1位が受け取る、返し点と配給原点の差の4人分）を返す
+    pub fn oka(&self) -> i32 {
+        return (self.return_points - self.starting_points) * 4;
+    }
+
+    /// 2翻縛りが適用されるか否かを返す
+    pub fn requires_two_han(&self, honba: u32) -> bool {
+        return self.two_han_minimum_after_five_honba && honba >= 5;
+    }
+}
+
+/// `base`を`overrides`の項目で上書きする
+///
+/// 両方にある表は丸ごと置き換えずに、項目ごとに再帰的に上書きする。
+fn merge_table(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overrides)) => {
+                merge_table(base, overrides);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// ルールの読み込みに失敗した理由
+#[derive(Debug)]
+pub enum RulesetError {
+    /// ファイルを読み込めなかった
+    Io(std::io::Error),
+    /// TOMLとして解釈できないか、知らない項目があるか、項目の型が正しくない
+    Parse(toml::de::Error),
+    /// ルールをTOMLに変換できなかった
+    Serialize(toml::ser::Error),
+    /// 存在しないプリセットが指定された
+    UnknownPreset(String),
+}
+
+impl fmt::Display for RulesetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            RulesetError::Io(e) => write!(f, "failed to read ruleset: {}", e),
+            RulesetError::Parse(e) => write!(f, "failed to parse ruleset: {}", e),
+            RulesetError::Serialize(e) => write!(f, "failed to serialize ruleset: {}", e),
+            RulesetError::UnknownPreset(name) => write!(f, "unknown preset: {}", name),
+        };
+    }
+}
+
+impl std::error::Error for RulesetError {}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// プリセットを名前から取得する
+    fn presets() {
+        for name in Ruleset::PRESETS.iter() {
+            assert!(Ruleset::preset(name).is_some(), "{}", name);
+        }
+        assert!(Ruleset::preset("unknown").is_none());
+        assert_eq!(Ruleset::default(), Ruleset::tenhou());
+        assert_eq!(Ruleset::tenhou().oka(), 20000);
+        assert_eq!(Ruleset::wrc().oka(), 0);
+    }
+
+    #[test]
+    /// 設定ファイルの項目で上書きする
+    fn from_toml() {
+        let ruleset = Ruleset::from_toml(
+            r#"
+            kiriage_mangan = true
+            uma = [10000, 5000, -5000, -10000]
+            "#,
+        )
+        .unwrap();
+        assert!(ruleset.kiriage_mangan);
+        assert_eq!(ruleset.uma, [10000, 5000, -5000, -10000]);
+        assert_eq!(ruleset.red_fives, Ruleset::tenhou().red_fives);
+        assert!(ruleset.limit_rule().kiriage_mangan);
+
+        let ruleset = Ruleset::from_toml("game_length = \"east\"").unwrap();
+        assert_eq!(ruleset.game_length, GameLength::East);
+        assert_eq!(ruleset.game_length.rounds(), 4);
+    }
+
+    #[test]
+    /// ローカル役を設定ファイルで採用する
+    fn local_yaku_from_toml() {
+        let ruleset = Ruleset::from_toml(
+            r#"
+            [local_yaku]
+            renhou = 5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(ruleset.local_yaku.renhou, Some(5));
+        assert_eq!(ruleset.local_yaku.daisharin, None);
+
+        // プリセットのほかの項目は変わらない
+        let ruleset = Ruleset::from_toml(
+            r#"
+            preset = "wrc"
+            [local_yaku]
+            renhou = 5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(ruleset.local_yaku.renhou, Some(5));
+        assert_eq!(
+            Ruleset {
+                local_yaku: LocalYakuRules::default(),
+                ..ruleset
+            },
+            Ruleset::wrc()
+        );
+        assert_eq!(ruleset.local_yaku.han(Yaku::BlessingOfMan, true), Some(5));
+        assert_eq!(ruleset.local_yaku.han(Yaku::BlessingOfMan, false), None);
+        assert_eq!(
+            LocalYakuRules::all().han(Yaku::PureTripleChow, false),
+            Some(2)
+        );
+    }
+
+    #[test]
+    /// 途中流局の一部を設定ファイルで採用しない
+    fn abortive_draws_from_toml() {
+        let ruleset = Ruleset::from_toml(
+            r#"
+            [abortive_draws]
+            sanchahou = false
+            "#,
+        )
+        .unwrap();
+        assert!(!ruleset.abortive_draws.sanchahou);
+        assert!(ruleset.abortive_draws.kyuushu_kyuuhai);
+        assert!(!Ruleset::wrc().abortive_draws.suucha_riichi);
+
+        let ruleset = Ruleset::from_toml(
+            r#"
+            preset = "wrc"
+            [abortive_draws]
+            sanchahou = true
+            "#,
+        )
+        .unwrap();
+        assert!(ruleset.abortive_draws.sanchahou);
+        assert!(!ruleset.abortive_draws.kyuushu_kyuuhai);
+        assert!(!ruleset.abortive_draws.suucha_riichi);
+        assert!(ruleset.abortive_draws.dealer_keeps_seat);
+    }
+
+    #[test]
+    /// 不正な設定ファイル
+    fn invalid_toml() {
+        assert!(matches!(
+            Ruleset::from_toml("preset = \"unknown\""),
+            Err(RulesetError::UnknownPreset(_))
+        ));
+        assert!(matches!(
+            Ruleset::from_toml("tobi = 1"),
+            Err(RulesetError::Parse(_))
+        ));
+        // 綴りを間違えた項目は無視せずにエラーにする
+        assert!(matches!(
+            Ruleset::from_toml("open_tanyo = false"),
+            Err(RulesetError::Parse(_))
+        ));
+        assert!(matches!(
+            Ruleset::from_toml("[local_yaku]\nrenho = 5"),
+            Err(RulesetError::Parse(_))
+        ));
+        assert!(matches!(
+            Ruleset::from_toml("[abortive_draws]\nsanchaho = false"),
+            Err(RulesetError::Parse(_))
+        ));
+    }
+
+    #[test]
+    /// 2翻縛り
+    fn two_han_minimum() {
+        let mut ruleset = Ruleset::tenhou();
+        assert!(!ruleset.requires_two_han(5));
+        ruleset.two_han_minimum_after_five_honba = true;
+        assert!(!ruleset.requires_two_han(4));
+        assert!(ruleset.requires_two_han(5));
+    }
+
+    #[test]
+    /// 入れ子の表は項目ごとに上書きする
+    fn merge_nested_table() {
+        let mut base: toml::Table = toml::from_str(
+            r#"
+            tobi = true
+            [nested]
+            a = 1
+            b = 2
+            "#,
+        )
+        .unwrap();
+        let overrides: toml::Table = toml::from_str(
+            r#"
+            [nested]
+            a = 3
+            "#,
+        )
+        .unwrap();
+        merge_table(&mut base, overrides);
+        assert_eq!(base["tobi"].as_bool(), Some(true));
+        assert_eq!(base["nested"]["a"].as_integer(), Some(3));
+        assert_eq!(base["nested"]["b"].as_integer(), Some(2));
+    }
+}
@@ -101,71 +101,87 @@ impl Tile {
         };
     }
 
+    /// 赤ドラの牌（赤五）を作る
+    ///
+    /// 五萬・五筒・五索以外を指定すると panic する。
+    pub fn new_red(tile_type: TileType) -> Tile {
+        if !matches!(tile_type, Tile::M5 | Tile::P5 | Tile::S5) {
+            panic!("Only fives can be red dora!");
+        }
+        return Tile {
+            index: tile_type,
+            red_dora: true,
+        };
+    }
+
     pub fn get(&self) -> TileType {
         return self.index;
     }
 
-    /*
-        /// 萬子か否かを返す
-        pub fn is_character(&self) -> bool {
-            return matches!(self.index, Tile::M1..=Tile::M9);
-        }
-        /// 筒子か否かを返す
-        pub fn is_circle(&self) -> bool {
-            return matches!(self.index, Tile::P1..=Tile::P9);
-        }
-        /// 索子か否かを返す
-        pub fn is_bamboo(&self) -> bool {
-            return matches!(self.index, Tile::S1..=Tile::S9);
-        }
-        /// 風牌か否かを返す
-        pub fn is_wind(&self) -> bool {
-            return matches!(self.index, Tile::Z1..=Tile::Z4);
-        }
-        /// 三元牌か否かを返す
-        pub fn is_dragon(&self) -> bool {
-            return matches!(self.index, Tile::Z5..=Tile::Z7);
-        }
-        /// 字牌か否かを返す
-        pub fn is_honor(&self) -> bool {
-            return self.is_wind() || self.is_dragon();
-        }
+    /// 赤ドラか否かを返す
+    pub fn is_red_dora(&self) -> bool {
+        return self.red_dora;
+    }
 
-        /// 老頭牌か否かを返す
-        pub fn is_1_or_9(&self) -> bool {
-            return matches!(
-                self.index,
-                Tile::M1 | Tile::M9 | Tile::P1 | Tile::P9 | Tile::S1 | Tile::S9
-            );
-        }
-        /// 么九牌（老頭牌＋字牌）か否かを返す
-        pub fn is_1_9_honor(&self) -> bool {
-            return self.is_1_or_9() || self.is_honor();
-        }
+    /// 萬子か否かを返す
+    pub fn is_character(&self) -> bool {
+        return matches!(self.index, Tile::M1..=Tile::M9);
+    }
+    /// 筒子か否かを返す
+    pub fn is_circle(&self) -> bool {
+        return matches!(self.index, Tile::P1..=Tile::P9);
+    }
+    /// 索子か否かを返す
+    pub fn is_bamboo(&self) -> bool {
+        return matches!(self.index, Tile::S1..=Tile::S9);
+    }
+    /// 風牌か否かを返す
+    pub fn is_wind(&self) -> bool {
+        return matches!(self.index, Tile::Z1..=Tile::Z4);
+    }
+    /// 三元牌か否かを返す
+    pub fn is_dragon(&self) -> bool {
+        return matches!(self.index, Tile::Z5..=Tile::Z7);
+    }
+    /// 字牌か否かを返す
+    pub fn is_honor(&self) -> bool {
+        return self.is_wind() || self.is_dragon();
+    }
+
+    /// 老頭牌か否かを返す
+    pub fn is_1_or_9(&self) -> bool {
+        return matches!(
+            self.index,
+            Tile::M1 | Tile::M9 | Tile::P1 | Tile::P9 | Tile::S1 | Tile::S9
+        );
+    }
+    /// 么九牌（老頭牌＋字牌）か否かを返す
+    pub fn is_1_9_honor(&self) -> bool {
+        return self.is_1_or_9() || self.is_honor();
+    }
 
-        /// 対子（同じ2枚）か否かを返す
-        pub fn is_same_to(&self, tile: Tile) -> bool {
-            return self.get() == tile.get();
+    /// 対子（同じ2枚）か否かを返す
+    pub fn is_same_to(&self, tile: Tile) -> bool {
+        return self.get() == tile.get();
+    }
+    /// 搭子（連続した2枚）か否かを返す
+    pub fn is_sequential_to(&self, tile: Tile) -> bool {
+        // 字牌ならば連続はありえない
+        if self.is_honor() {
+            return false;
         }
-        /// 搭子（連続した2枚）か否かを返す
-        pub fn is_sequential_to(&self, tile: Tile) -> bool {
-            // 字牌ならば連続はありえない
-            if self.is_honor() {
-                return false;
-            }
-            // 一萬・一筒・一索の時に1つ前（九萬・九筒）が来ても連続とはみなさない
-            if matches!(self.index, Tile::M1 | Tile::P1 | Tile::S1) && self.get() == tile.get() + 1 {
-                return false;
-            }
-            // 九萬・九筒・九索の時に1つ後（一筒・一索・東）が来ても連続とはみなさない
-            if matches!(self.index, Tile::M9 | Tile::P9 | Tile::S9) && self.get() == tile.get() - 1 {
-                return false;
-            } else if self.get() == tile.get() - 1 || self.get() == tile.get() + 1 {
-                return true;
-            }
+        // 一萬・一筒・一索の時に1つ前（九萬・九筒）が来ても連続とはみなさない
+        if matches!(self.index, Tile::M1 | Tile::P1 | Tile::S1) && self.get() == tile.get() + 1 {
+            return false;
+        }
+        // 九萬・九筒・九索の時に1つ後（一筒・一索・東）が来ても連続とはみなさない
+        if matches!(self.index, Tile::M9 | Tile::P9 | Tile::S9) && self.get() + 1 == tile.get() {
             return false;
+        } else if self.get() + 1 == tile.get() || self.get() == tile.get() + 1 {
+            return true;
         }
-    */
+        return false;
+    }
     pub fn to_char(&self) -> char {
         return Tile::CHARS[self.index as usize];
     }
@@ -218,6 +234,7 @@ impl Tile {
 }
 
 /// 自風／場風
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Wind {
     East = Tile::Z1 as isize,
     South = Tile::Z2 as isize,
@@ -240,24 +257,17 @@ impl Wind {
 #[cfg(test)]
 mod tests {
     use super::*;
-    /*
+
     /// 萬子の属性テスト
     #[test]
     fn suit_char_test() {
         for i in Tile::M1..=Tile::M9 {
             let t = Tile::new(i);
-            assert_eq!(t.is_character(), true);
-            assert_eq!(t.is_bamboo(), false);
-            assert_eq!(t.is_circle(), false);
-            assert_eq!(t.is_honor(), false);
-            assert_eq!(
-                t.is_1_or_9(),
-                if i == Tile::M1 || i == Tile::M9 {
-                    true
-                } else {
-                    false
-                }
-            );
+            assert!(t.is_character());
+            assert!(!t.is_bamboo());
+            assert!(!t.is_circle());
+            assert!(!t.is_honor());
+            assert_eq!(t.is_1_or_9(), i == Tile::M1 || i == Tile::M9);
         }
     }
 
@@ -266,18 +276,11 @@ mod tests {
     fn suit_circle_test() {
         for i in Tile::P1..=Tile::P9 {
             let t = Tile::new(i);
-            assert_eq!(t.is_character(), false);
-            assert_eq!(t.is_bamboo(), false);
-            assert_eq!(t.is_circle(), true);
-            assert_eq!(t.is_honor(), false);
-            assert_eq!(
-                t.is_1_or_9(),
-                if i == Tile::P1 || i == Tile::P9 {
-                    true
-                } else {
-                    false
-                }
-            );
+            assert!(!t.is_character());
+            assert!(!t.is_bamboo());
+            assert!(t.is_circle());
+            assert!(!t.is_honor());
+            assert_eq!(t.is_1_or_9(), i == Tile::P1 || i == Tile::P9);
         }
     }
     /// 索子の属性テスト
@@ -285,18 +288,11 @@ mod tests {
     fn suit_bamboo_test() {
         for i in Tile::S1..=Tile::S9 {
             let t = Tile::new(i);
-            assert_eq!(t.is_character(), false);
-            assert_eq!(t.is_bamboo(), true);
-            assert_eq!(t.is_circle(), false);
-            assert_eq!(t.is_honor(), false);
-            assert_eq!(
-                t.is_1_or_9(),
-                if i == Tile::S1 || i == Tile::S9 {
-                    true
-                } else {
-                    false
-                }
-            );
+            assert!(!t.is_character());
+            assert!(t.is_bamboo());
+            assert!(!t.is_circle());
+            assert!(!t.is_honor());
+            assert_eq!(t.is_1_or_9(), i == Tile::S1 || i == Tile::S9);
         }
     }
     /// 風牌の属性テスト
@@ -304,12 +300,12 @@ mod tests {
     fn suit_wind_test() {
         for i in Tile::Z1..=Tile::Z4 {
             let t = Tile::new(i);
-            assert_eq!(t.is_character(), false);
-            assert_eq!(t.is_bamboo(), false);
-            assert_eq!(t.is_circle(), false);
-            assert_eq!(t.is_wind(), true);
-            assert_eq!(t.is_dragon(), false);
-            assert_eq!(t.is_honor(), true);
+            assert!(!t.is_character());
+            assert!(!t.is_bamboo());
+            assert!(!t.is_circle());
+            assert!(t.is_wind());
+            assert!(!t.is_dragon());
+            assert!(t.is_honor());
         }
     }
     /// 三元牌の属性テスト
@@ -317,12 +313,12 @@ mod tests {
     fn suit_dragon_test() {
         for i in Tile::Z5..=Tile::Z7 {
             let t = Tile::new(i);
-            assert_eq!(t.is_character(), false);
-            assert_eq!(t.is_bamboo(), false);
-            assert_eq!(t.is_circle(), false);
-            assert_eq!(t.is_wind(), false);
-            assert_eq!(t.is_dragon(), true);
-            assert_eq!(t.is_honor(), true);
+            assert!(!t.is_character());
+            assert!(!t.is_bamboo());
+            assert!(!t.is_circle());
+            assert!(!t.is_wind());
+            assert!(t.is_dragon());
+            assert!(t.is_honor());
         }
     }
     /// 字牌の属性テスト
@@ -330,10 +326,10 @@ mod tests {
     fn suit_honor_test() {
         for i in Tile::Z1..=Tile::Z7 {
             let t = Tile::new(i);
-            assert_eq!(t.is_character(), false);
-            assert_eq!(t.is_bamboo(), false);
-            assert_eq!(t.is_circle(), false);
-            assert_eq!(t.is_honor(), true);
+            assert!(!t.is_character());
+            assert!(!t.is_bamboo());
+            assert!(!t.is_circle());
+            assert!(t.is_honor());
         }
     }
 
@@ -341,61 +337,43 @@ mod tests {
     #[test]
     fn sameness_test() {
         // 1m→1mは対子
-        assert_eq!(Tile::new(Tile::M1).is_same_to(Tile::new(Tile::M1)), true);
+        assert!(Tile::new(Tile::M1).is_same_to(Tile::new(Tile::M1)));
         // 1m→1pは対子ではない
-        assert_eq!(Tile::new(Tile::M1).is_same_to(Tile::new(Tile::P1)), false);
+        assert!(!Tile::new(Tile::M1).is_same_to(Tile::new(Tile::P1)));
         // 1z→1zは対子
-        assert_eq!(Tile::new(Tile::Z1).is_same_to(Tile::new(Tile::Z1)), true);
+        assert!(Tile::new(Tile::Z1).is_same_to(Tile::new(Tile::Z1)));
     }
 
     /// 搭子テスト
     #[test]
     fn sequential_test() {
         // 1m→2mは搭子
-        assert_eq!(
-            Tile::new(Tile::M1).is_sequential_to(Tile::new(Tile::M2)),
-            true
-        );
+        assert!(Tile::new(Tile::M1).is_sequential_to(Tile::new(Tile::M2)));
         // 3p→3pは搭子ではない
-        assert_eq!(
-            Tile::new(Tile::P3).is_sequential_to(Tile::new(Tile::P3)),
-            false
-        );
+        assert!(!Tile::new(Tile::P3).is_sequential_to(Tile::new(Tile::P3)));
         // 7s→8sは搭子
-        assert_eq!(
-            Tile::new(Tile::S7).is_sequential_to(Tile::new(Tile::S8)),
-            true
-        );
+        assert!(Tile::new(Tile::S7).is_sequential_to(Tile::new(Tile::S8)));
         // 1m→1pは搭子ではない
-        assert_eq!(
-            Tile::new(Tile::M1).is_sequential_to(Tile::new(Tile::P1)),
-            false
-        );
+        assert!(!Tile::new(Tile::M1).is_sequential_to(Tile::new(Tile::P1)));
         // 9m→8mは搭子
-        assert_eq!(
-            Tile::new(Tile::M9).is_sequential_to(Tile::new(Tile::M8)),
-            true
-        );
+        assert!(Tile::new(Tile::M9).is_sequential_to(Tile::new(Tile::M8)));
         // 9m→1pは搭子ではない
-        assert_eq!(
-            Tile::new(Tile::M9).is_sequential_to(Tile::new(Tile::P1)),
-            false
-        );
+        assert!(!Tile::new(Tile::M9).is_sequential_to(Tile::new(Tile::P1)));
         // 1s→9pは搭子ではない
-        assert_eq!(
-            Tile::new(Tile::S1).is_sequential_to(Tile::new(Tile::P9)),
-            false
-        );
+        assert!(!Tile::new(Tile::S1).is_sequential_to(Tile::new(Tile::P9)));
         // 9s→1zは搭子ではない
-        assert_eq!(
-            Tile::new(Tile::S9).is_sequential_to(Tile::new(Tile::Z1)),
-            false
-        );
+        assert!(!Tile::new(Tile::S9).is_sequential_to(Tile::new(Tile::Z1)));
         // 1z→2zは搭子ではない
-        assert_eq!(
-            Tile::new(Tile::Z1).is_sequential_to(Tile::new(Tile::Z2)),
-            false
-        );
+        assert!(!Tile::new(Tile::Z1).is_sequential_to(Tile::new(Tile::Z2)));
+    }
+
+    /// 赤ドラテスト
+    #[test]
+    fn red_dora_test() {
+        let red = Tile::new_red(Tile::P5);
+        assert!(red.is_red_dora());
+        assert!(!Tile::new(Tile::P5).is_red_dora());
+        // 赤ドラでも牌の種類は同じ
+        assert!(red.is_same_to(Tile::new(Tile::P5)));
     }
-    */
 }